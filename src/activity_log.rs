@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How much of the activity log is kept line by line, e.g.
+/// `{ "keep_days": 30, "max_bytes": 65536 }`. Older entries, and the oldest
+/// ones while the log is over the size, are compacted into one summary line
+/// per month. Story status history, which cycle times are measured from,
+/// lives on the board and is never compacted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityRetention {
+    pub keep_days: i64,
+    pub max_bytes: u64,
+}
+
+impl ActivityRetention {
+    /// Rejects a negative `keep_days`, which would compact the whole log.
+    pub fn validate(&self) -> Result<()> {
+        if self.keep_days < 0 {
+            return Err(anyhow!("activity_retention.keep_days can't be negative"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ActivityRetention {
+    fn default() -> Self {
+        Self {
+            keep_days: 90,
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// What a vacuum did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compaction {
+    /// Entries folded into summaries.
+    pub compacted: usize,
+    /// Summary lines in the log afterwards.
+    pub months: usize,
+    pub bytes: u64,
+}
+
+/// Changes and items of a month, as in `2024-03 summary: 5 changes, 73 items`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct MonthSummary {
+    changes: usize,
+    items: usize,
+}
+
+enum Line {
+    Summary(String, MonthSummary),
+    Entry {
+        at: DateTime<Utc>,
+        items: usize,
+        text: String,
+    },
+    /// Anything not written by the log, kept as it is.
+    Other(String),
+}
+
+fn parse_line(line: &str) -> Line {
+    if let Some((month, rest)) = line.split_once(" summary: ") {
+        let counts: Option<Vec<usize>> = rest
+            .split(", ")
+            .map(|part| part.split_once(' ')?.0.parse().ok())
+            .collect();
+        if let Some([changes, items]) = counts.as_deref() {
+            let summary = MonthSummary {
+                changes: *changes,
+                items: *items,
+            };
+            return Line::Summary(month.to_owned(), summary);
+        }
+    }
+    let at = line
+        .get(..19)
+        .and_then(|stamp| NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S").ok());
+    match at {
+        Some(at) => Line::Entry {
+            at: at.and_utc(),
+            items: entry_items(line),
+            text: line.to_owned(),
+        },
+        None => Line::Other(line.to_owned()),
+    }
+}
+
+/// The count of a bulk change entry, e.g. 14 for `delete epic 3 (14 items)`.
+fn entry_items(line: &str) -> usize {
+    line.rfind(" items)")
+        .and_then(|end| {
+            let start = line[..end].rfind('(')? + 1;
+            line[start..end].parse().ok()
+        })
+        .unwrap_or(0)
+}
+
+fn render(summaries: &BTreeMap<String, MonthSummary>, kept: &[&Line]) -> String {
+    let mut content = String::new();
+    for (month, summary) in summaries {
+        content.push_str(&format!(
+            "{} summary: {} changes, {} items\n",
+            month, summary.changes, summary.items
+        ));
+    }
+    for line in kept {
+        match line {
+            Line::Entry { text, .. } | Line::Other(text) => {
+                content.push_str(text);
+                content.push('\n');
+            }
+            Line::Summary(..) => {}
+        }
+    }
+    content
+}
+
+/// The log as it would be after a vacuum, and how many entries went.
+fn compacted(content: &str, retention: &ActivityRetention, now: DateTime<Utc>) -> (String, usize) {
+    let lines: Vec<Line> = content.lines().map(parse_line).collect();
+    let mut summaries: BTreeMap<String, MonthSummary> = BTreeMap::new();
+    for line in &lines {
+        if let Line::Summary(month, summary) = line {
+            let total = summaries.entry(month.clone()).or_default();
+            total.changes += summary.changes;
+            total.items += summary.items;
+        }
+    }
+    let cutoff = Duration::try_days(retention.keep_days)
+        .and_then(|keep| now.checked_sub_signed(keep))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let mut kept: Vec<&Line> = vec![];
+    let mut compacted = 0;
+    let mut fold = |summaries: &mut BTreeMap<String, MonthSummary>, at: &DateTime<Utc>, items| {
+        let total = summaries.entry(at.format("%Y-%m").to_string()).or_default();
+        total.changes += 1;
+        total.items += items;
+        compacted += 1;
+    };
+    for line in &lines {
+        match line {
+            Line::Entry { at, items, .. } if *at < cutoff => fold(&mut summaries, at, *items),
+            Line::Summary(..) => {}
+            line => kept.push(line),
+        }
+    }
+    // still too big: give up the oldest months that are kept in full
+    while render(&summaries, &kept).len() as u64 > retention.max_bytes {
+        let Some(month) = kept.iter().find_map(|line| match line {
+            Line::Entry { at, .. } => Some(at.format("%Y-%m").to_string()),
+            _ => None,
+        }) else {
+            break;
+        };
+        kept.retain(|line| match line {
+            Line::Entry { at, items, .. } if at.format("%Y-%m").to_string() == month => {
+                fold(&mut summaries, at, *items);
+                false
+            }
+            _ => true,
+        });
+    }
+    (render(&summaries, &kept), compacted)
+}
+
+/// Whether a vacuum would compact anything. A missing log needs none.
+pub fn needs_compaction(path: &Path, retention: &ActivityRetention, now: DateTime<Utc>) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| compacted(&content, retention, now).1 > 0)
+}
+
+/// Applies the retention policy to the log at `path`, replacing it only
+/// once the compacted version is fully written.
+pub fn vacuum(
+    path: &Path,
+    retention: &ActivityRetention,
+    now: DateTime<Utc>,
+) -> Result<Compaction> {
+    if !path.exists() {
+        return Ok(Compaction {
+            compacted: 0,
+            months: 0,
+            bytes: 0,
+        });
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| anyhow!("could not read {}", path.display()))?;
+    let (content, compacted) = compacted(&content, retention, now);
+    if compacted > 0 {
+        let temporary = path.with_extension("log.tmp");
+        fs::write(&temporary, &content)
+            .with_context(|| anyhow!("could not write {}", temporary.display()))?;
+        fs::rename(&temporary, path)
+            .with_context(|| anyhow!("could not replace {}", path.display()))?;
+    }
+    Ok(Compaction {
+        compacted,
+        months: content
+            .lines()
+            .filter(|line| line.contains(" summary: "))
+            .count(),
+        bytes: content.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    const LOG: &str = "\
+2024-02 summary: 2 changes, 30 items
+2024-03-05 10:15:00 UTC acme: delete epic 3 (14 items), restore with: jira_cli import-archive a.tar.gz
+2024-03-20 08:00:00 UTC acme: split epic 4 into 9 (12 items)
+2024-06-10 09:30:00 UTC acme: merge epic 5 into 2 (11 items)
+";
+
+    #[test]
+    fn vacuum_should_fold_old_entries_into_monthly_summaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.log");
+        fs::write(&path, LOG).unwrap();
+        let retention = ActivityRetention {
+            keep_days: 30,
+            ..ActivityRetention::default()
+        };
+
+        assert_eq!(needs_compaction(&path, &retention, now()), true);
+        let compaction = vacuum(&path, &retention, now()).unwrap();
+
+        assert_eq!(compaction.compacted, 2);
+        assert_eq!(compaction.months, 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "2024-02 summary: 2 changes, 30 items\n\
+             2024-03 summary: 2 changes, 26 items\n\
+             2024-06-10 09:30:00 UTC acme: merge epic 5 into 2 (11 items)\n"
+        );
+        assert_eq!(needs_compaction(&path, &retention, now()), false);
+        assert_eq!(vacuum(&path, &retention, now()).unwrap().compacted, 0);
+    }
+
+    #[test]
+    fn vacuum_should_keep_everything_when_keep_days_is_out_of_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.log");
+        fs::write(&path, LOG).unwrap();
+        let retention = ActivityRetention {
+            keep_days: i64::MAX,
+            ..ActivityRetention::default()
+        };
+
+        assert_eq!(needs_compaction(&path, &retention, now()), false);
+        assert_eq!(vacuum(&path, &retention, now()).unwrap().compacted, 0);
+        assert_eq!(fs::read_to_string(&path).unwrap(), LOG);
+    }
+
+    #[test]
+    fn vacuum_should_compact_the_oldest_months_when_over_the_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.log");
+        fs::write(&path, LOG).unwrap();
+        let retention = ActivityRetention {
+            keep_days: 365,
+            max_bytes: 150,
+        };
+
+        let compaction = vacuum(&path, &retention, now()).unwrap();
+
+        assert_eq!(compaction.compacted, 2);
+        assert_eq!(compaction.bytes <= 150, true);
+        assert_eq!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .ends_with("merge epic 5 into 2 (11 items)\n"),
+            true
+        );
+        assert_eq!(
+            vacuum(&dir.path().join("missing.log"), &retention, now()).unwrap(),
+            Compaction {
+                compacted: 0,
+                months: 0,
+                bytes: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Ok, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::dao::JiraDAO;
+use crate::integrity::check_database;
+use crate::models::DBState;
+
+/// Bumped whenever the layout of the archive or of the database it carries changes.
+pub const ARCHIVE_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "db.json";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    schema_version: u32,
+    app_version: String,
+}
+
+pub fn export_archive(dao: &JiraDAO, archive_path: &Path) -> Result<()> {
+    let state = dao.read_db()?;
+    let manifest = Manifest {
+        schema_version: ARCHIVE_SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_owned(),
+    };
+
+    let file = File::create(archive_path)
+        .with_context(|| anyhow!("could not create {}", archive_path.display()))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append_json(&mut builder, MANIFEST_ENTRY, &manifest)?;
+    let database = append_json(&mut builder, DATABASE_ENTRY, &state)?;
+    // an archive that wouldn't restore to the same board is worse than none
+    if serde_json::from_slice::<DBState>(&database)? != state {
+        return Err(anyhow!("the board did not survive serialization unchanged"));
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Restores an archive through the DAO, so the database lands wherever this
+/// installation keeps it regardless of the path it was exported from. With
+/// `strict`, the database is first checked for duplicate ids and dangling
+/// references, and nothing is restored if any are found.
+pub fn import_archive(dao: &JiraDAO, archive_path: &Path, strict: bool) -> Result<()> {
+    let file = File::open(archive_path)
+        .with_context(|| anyhow!("could not open {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest: Option<Manifest> = None;
+    let mut database: Option<String> = None;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        match name.as_str() {
+            MANIFEST_ENTRY => manifest = Some(read_json(&mut entry, MANIFEST_ENTRY)?),
+            DATABASE_ENTRY => {
+                let mut content = String::new();
+                entry.read_to_string(&mut content)?;
+                database = Some(content);
+            }
+            _ => {}
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| anyhow!("archive has no {}", MANIFEST_ENTRY))?;
+    if manifest.schema_version > ARCHIVE_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "archive schema version {} is newer than the supported version {}",
+            manifest.schema_version,
+            ARCHIVE_SCHEMA_VERSION
+        ));
+    }
+    let database = database.ok_or_else(|| anyhow!("archive has no {}", DATABASE_ENTRY))?;
+    if strict {
+        let problems = check_database(&database);
+        if !problems.is_empty() {
+            return Err(anyhow!(
+                "{} in archive failed validation:\n{}",
+                DATABASE_ENTRY,
+                problems
+                    .iter()
+                    .map(|problem| format!("  {}", problem))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+    }
+    let state: DBState = serde_json::from_str(&database)
+        .with_context(|| anyhow!("invalid {} in archive", DATABASE_ENTRY))?;
+    dao.replace_db(&state)
+}
+
+/// Writes the value as pretty-printed JSON, so problems found on import can
+/// point at a line, and returns what was written.
+fn append_json<W: Write, T: Serialize>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    value: &T,
+) -> Result<Vec<u8>> {
+    let content = serde_json::to_vec_pretty(value)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, content.as_slice())?;
+    Ok(content)
+}
+
+fn read_json<T: DeserializeOwned>(entry: &mut impl Read, name: &str) -> Result<T> {
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    serde_json::from_str(&content).with_context(|| anyhow!("invalid {} in archive", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dao::test_utils::MockDB;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::models::{Epic, Status, Story};
+
+    fn make_dao() -> JiraDAO {
+        JiraDAO::new(Box::new(MockDB::new()))
+    }
+
+    #[test]
+    fn export_then_import_should_restore_the_same_state() {
+        let source = make_dao();
+        let epic_id = source
+            .create_epic(Epic::new("epic".to_owned(), "description".to_owned()))
+            .unwrap();
+        source
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("board.tar.gz");
+
+        export_archive(&source, &archive_path).unwrap();
+        let target = make_dao();
+        import_archive(&target, &archive_path, false).unwrap();
+
+        assert_eq!(target.read_db().unwrap(), source.read_db().unwrap());
+    }
+
+    #[test]
+    fn strict_round_trip_should_keep_every_detail() {
+        let source = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_epic_status(Status::InProgress)
+            .with_story("Refunds")
+            .with_story_comment("needs a design")
+            .with_story("Invoices")
+            .with_story_status(Status::Resolved)
+            .with_story_blocked_by(2)
+            .build_dao();
+        source.queue_story(2).unwrap();
+        source
+            .add_acceptance_criterion(3, "sends an email".to_owned())
+            .unwrap();
+        source.split_epic(1, &[3], "Invoicing".to_owned()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("board.tar.gz");
+
+        export_archive(&source, &archive_path).unwrap();
+        let target = make_dao();
+        import_archive(&target, &archive_path, true).unwrap();
+
+        assert_eq!(target.read_db().unwrap(), source.read_db().unwrap());
+    }
+
+    #[test]
+    fn strict_import_should_reject_dangling_references() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build();
+        state.queue.push(42);
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("board.tar.gz");
+        let file = File::create(&archive_path).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let manifest = Manifest {
+            schema_version: ARCHIVE_SCHEMA_VERSION,
+            app_version: "0.0.0".to_owned(),
+        };
+        append_json(&mut builder, MANIFEST_ENTRY, &manifest).unwrap();
+        append_json(&mut builder, DATABASE_ENTRY, &state).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let target = make_dao();
+        let error = import_archive(&target, &archive_path, true).unwrap_err();
+        assert_eq!(error.to_string().contains("lists story 42"), true);
+        assert_eq!(target.read_db().unwrap().epics.is_empty(), true);
+        assert_eq!(import_archive(&target, &archive_path, false).is_ok(), true);
+    }
+
+    #[test]
+    fn import_should_reject_newer_schema_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("board.tar.gz");
+        let file = File::create(&archive_path).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let manifest = Manifest {
+            schema_version: ARCHIVE_SCHEMA_VERSION + 1,
+            app_version: "0.0.0".to_owned(),
+        };
+        append_json(&mut builder, MANIFEST_ENTRY, &manifest).unwrap();
+        append_json(&mut builder, DATABASE_ENTRY, &make_dao().read_db().unwrap()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(
+            import_archive(&make_dao(), &archive_path, false).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn import_should_fail_for_missing_file() {
+        let result = import_archive(&make_dao(), Path::new("INVALID_PATH.tar.gz"), false);
+        assert_eq!(result.is_err(), true);
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::archive::export_archive;
+use crate::dao::JiraDAO;
+
+/// Where the board is backed up before risky operations, and which
+/// workspace to name when restoring it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupPolicy {
+    pub dir: PathBuf,
+    pub workspace: Option<String>,
+}
+
+impl BackupPolicy {
+    /// Backups go to a `backups` folder next to the database, e.g.
+    /// `data/backups` for `data/db.json`.
+    pub fn for_database(database_path: &Path, workspace: Option<&str>) -> Self {
+        let parent = database_path.parent().unwrap_or(Path::new("."));
+        Self {
+            dir: parent.join("backups"),
+            workspace: workspace.map(str::to_owned),
+        }
+    }
+
+    /// Saves the board as an archive named after the time and the
+    /// operation, e.g. `20240305-101500-delete-epic.tar.gz`, and returns
+    /// the command that restores it.
+    pub fn back_up(&self, dao: &JiraDAO, operation: &str) -> Result<String> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| anyhow!("could not create {}", self.dir.display()))?;
+        let stem = format!("{}-{}", Utc::now().format("%Y%m%d-%H%M%S"), operation);
+        let mut path = self.dir.join(format!("{}.tar.gz", stem));
+        let mut attempt = 1;
+        while path.exists() {
+            attempt += 1;
+            path = self.dir.join(format!("{}-{}.tar.gz", stem, attempt));
+        }
+        export_archive(dao, &path)
+            .with_context(|| anyhow!("could not back up the board before {}", operation))?;
+        Ok(self.restore_command(&path))
+    }
+
+    /// Appends a line about a bulk change to `activity.log` next to the
+    /// backups, e.g. `2024-03-05 10:15:00 UTC acme: delete epic 3 (14 items)`.
+    pub fn log_activity(&self, summary: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| anyhow!("could not create {}", self.dir.display()))?;
+        let path = self.activity_log_path();
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| anyhow!("could not open {}", path.display()))?;
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+        match &self.workspace {
+            Some(workspace) => writeln!(log, "{} {}: {}", timestamp, workspace, summary)?,
+            None => writeln!(log, "{} {}", timestamp, summary)?,
+        }
+        Ok(())
+    }
+
+    pub fn activity_log_path(&self) -> PathBuf {
+        self.dir.join("activity.log")
+    }
+
+    /// The command that runs `command` on this workspace.
+    pub fn command_for(&self, command: &str) -> String {
+        match &self.workspace {
+            Some(workspace) => format!("jira_cli --workspace {} {}", workspace, command),
+            None => format!("jira_cli {}", command),
+        }
+    }
+
+    pub fn restore_command(&self, backup_path: &Path) -> String {
+        self.command_for(&format!("import-archive {}", backup_path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::import_archive;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn for_database_should_keep_backups_next_to_the_board() {
+        let policy = BackupPolicy::for_database(Path::new("./data/db.json"), None);
+        assert_eq!(policy.dir, Path::new("./data/backups"));
+    }
+
+    #[test]
+    fn back_up_should_write_a_restorable_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build_dao();
+        let policy = BackupPolicy {
+            dir: dir.path().join("backups"),
+            workspace: Some("acme".to_owned()),
+        };
+
+        let first = policy.back_up(&dao, "delete-epic").unwrap();
+        let second = policy.back_up(&dao, "delete-epic").unwrap();
+
+        assert_eq!(
+            first.starts_with("jira_cli --workspace acme import-archive "),
+            true
+        );
+        assert_eq!(first.ends_with("-delete-epic.tar.gz"), true);
+        assert_ne!(first, second);
+        let backup_path = first.rsplit(' ').next().unwrap();
+        let expected = dao.read_db().unwrap();
+        dao.delete_epic(1).unwrap();
+        import_archive(&dao, Path::new(backup_path), true).unwrap();
+        assert_eq!(dao.read_db().unwrap(), expected);
+    }
+}
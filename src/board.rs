@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use crate::cached_database::CachedFileDatabase;
+use crate::dao::JiraDAO;
+use crate::journal::{self, JournaledDatabase};
+use crate::json_file_database_adapter::JSONFileJiraDAOAdapter;
+use crate::memory_database::MemoryDatabase;
+use crate::models::{DBState, Epic, Status, Story};
+
+/// A board opened without the terminal UI, for scripts and other tools'
+/// integration tests.
+///
+/// ```no_run
+/// use jira_cli::{JiraBoard, Status};
+///
+/// let board = JiraBoard::open("./data/db.json")?;
+/// let epic_id = board.create_epic("Payments", "Card and bank payments")?;
+/// board.create_story(epic_id, "Refunds", "Partial and full refunds")?;
+/// for (id, story) in board.stories(epic_id)? {
+///     if story.status == Status::Open {
+///         println!("{} {}", id, story.name);
+///     }
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct JiraBoard {
+    dao: JiraDAO,
+}
+
+impl JiraBoard {
+    /// Opens the board stored at `path`, creating an empty one if the file
+    /// does not exist yet. Writes are journaled next to it like the app's,
+    /// e.g. in `data/db.journal`, and a write a crash cut short is finished
+    /// on open.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            fs::write(path, serde_json::to_vec(&empty_state())?)?;
+        }
+        let journal_path = path.with_extension("journal");
+        let database = CachedFileDatabase::new(JSONFileJiraDAOAdapter {
+            path: path.to_string_lossy().into_owned(),
+        });
+        if let Some(intent) = journal::read_pending_intent(&journal_path)? {
+            journal::replay_intent(&database, &journal_path, &intent)?;
+        }
+        Ok(Self {
+            dao: JiraDAO::new(Box::new(JournaledDatabase::new(
+                Box::new(database),
+                journal_path,
+            ))),
+        })
+    }
+
+    /// An empty board that lives only as long as the handle.
+    pub fn in_memory() -> Self {
+        Self {
+            dao: JiraDAO::new(Box::new(MemoryDatabase::new(empty_state()))),
+        }
+    }
+
+    pub fn create_epic(&self, name: &str, description: &str) -> Result<u32> {
+        self.dao
+            .create_epic(Epic::new(name.to_owned(), description.to_owned()))
+    }
+
+    pub fn create_story(&self, epic_id: u32, name: &str, description: &str) -> Result<u32> {
+        self.dao
+            .create_story(Story::new(name.to_owned(), description.to_owned()), epic_id)
+    }
+
+    pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
+        self.dao.update_epic_status(epic_id, status)
+    }
+
+    pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
+        self.dao.update_story_status(story_id, status)
+    }
+
+    /// Deletes the epic together with its stories.
+    pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
+        self.dao.delete_epic(epic_id)
+    }
+
+    pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
+        self.dao.delete_story(epic_id, story_id)
+    }
+
+    pub fn epic(&self, epic_id: u32) -> Result<Option<Epic>> {
+        Ok(self.dao.read_db()?.epics.remove(&epic_id))
+    }
+
+    pub fn story(&self, story_id: u32) -> Result<Option<Story>> {
+        Ok(self.dao.read_db()?.stories.remove(&story_id))
+    }
+
+    /// All epics, ordered by id.
+    pub fn epics(&self) -> Result<impl Iterator<Item = (u32, Epic)>> {
+        let epics = self.dao.read_db()?.epics;
+        Ok(epics.into_iter().sorted_by_key(|(id, _)| *id))
+    }
+
+    /// The stories of an epic, in the order they were added to it.
+    pub fn stories(&self, epic_id: u32) -> Result<impl Iterator<Item = (u32, Story)>> {
+        let mut db_state = self.dao.read_db()?;
+        let epic = db_state
+            .epics
+            .remove(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+        Ok(epic
+            .stories
+            .into_iter()
+            .filter_map(move |id| db_state.stories.remove(&id).map(|story| (id, story))))
+    }
+}
+
+fn empty_state() -> DBState {
+    DBState {
+        last_item_id: 0,
+        epics: Default::default(),
+        stories: Default::default(),
+        queue: vec![],
+        aliases: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_should_create_a_missing_board() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json");
+
+        let board = JiraBoard::open(&path).unwrap();
+        let epic_id = board.create_epic("epic", "").unwrap();
+
+        let reopened = JiraBoard::open(&path).unwrap();
+        assert_eq!(reopened.epic(epic_id).unwrap().unwrap().name, "epic");
+        assert_eq!(dir.path().join("db.journal").exists(), false);
+    }
+
+    #[test]
+    fn open_should_finish_an_interrupted_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json");
+        JiraBoard::open(&path).unwrap();
+        let mut state = empty_state();
+        state.last_item_id = 1;
+        state
+            .epics
+            .insert(1, Epic::new("unsaved".to_owned(), "".to_owned()));
+        fs::write(
+            dir.path().join("db.journal"),
+            serde_json::to_vec(&journal::Intent {
+                started_at: chrono::Utc::now(),
+                state,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        let board = JiraBoard::open(&path).unwrap();
+
+        assert_eq!(board.epic(1).unwrap().unwrap().name, "unsaved");
+        assert_eq!(dir.path().join("db.journal").exists(), false);
+    }
+
+    #[test]
+    fn queries_should_return_items_in_order() {
+        let sut = JiraBoard::in_memory();
+        let second_epic = sut.create_epic("b", "").unwrap();
+        let first_story = sut.create_story(second_epic, "story 1", "").unwrap();
+        let second_story = sut.create_story(second_epic, "story 2", "").unwrap();
+        sut.update_story_status(second_story, Status::Closed)
+            .unwrap();
+        let third_epic = sut.create_epic("c", "").unwrap();
+
+        let epic_ids: Vec<u32> = sut.epics().unwrap().map(|(id, _)| id).collect();
+        assert_eq!(epic_ids, vec![second_epic, third_epic]);
+
+        let stories: Vec<(u32, Status)> = sut
+            .stories(second_epic)
+            .unwrap()
+            .map(|(id, story)| (id, story.status))
+            .collect();
+        assert_eq!(
+            stories,
+            vec![(first_story, Status::Open), (second_story, Status::Closed)]
+        );
+        assert_eq!(sut.stories(999).is_err(), true);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::models::{DBState, Epic, ScopeChange, Status};
+
+/// How much of an epic was done, out of its scope, at the end of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurnupPoint {
+    pub day: NaiveDate,
+    pub done: usize,
+    pub scope: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Burnup {
+    /// One point per day on which something changed, and one for today.
+    pub points: Vec<BurnupPoint>,
+    /// When the first story of the epic left `Open`, if any did.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Stories added or removed after work started.
+    pub scope_changes: Vec<ScopeChange>,
+}
+
+/// Replays the epic's scope changes and its stories' status changes. Stories
+/// from before those were recorded count from their creation, or from the
+/// start when that isn't known either, with the status they have now.
+pub fn epic_burnup(state: &DBState, epic_id: u32, now: DateTime<Utc>) -> Result<Burnup> {
+    let epic = state
+        .epics
+        .get(&epic_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+    let mut seen = HashSet::new();
+    let story_ids: Vec<u32> = epic
+        .scope_changes
+        .iter()
+        .map(|change| change.story_id)
+        .chain(epic.stories.iter().copied())
+        .filter(|story_id| seen.insert(*story_id))
+        .collect();
+
+    let mut days = BTreeSet::from([now.date_naive()]);
+    days.extend(
+        epic.scope_changes
+            .iter()
+            .map(|change| change.at.date_naive()),
+    );
+    for story in story_ids.iter().filter_map(|id| state.stories.get(id)) {
+        days.extend(story.created_at.map(|at| at.date_naive()));
+        days.extend(
+            story
+                .status_changes
+                .iter()
+                .map(|change| change.at.date_naive()),
+        );
+    }
+    let points = days
+        .into_iter()
+        .filter(|day| *day <= now.date_naive())
+        .map(|day| {
+            let end_of_day = day
+                .succ_opt()
+                .and_then(|next| next.and_hms_opt(0, 0, 0))
+                .map(|next| next.and_utc())
+                .unwrap_or(now);
+            let in_scope: Vec<u32> = story_ids
+                .iter()
+                .copied()
+                .filter(|story_id| in_scope(state, epic, *story_id, end_of_day))
+                .collect();
+            BurnupPoint {
+                day,
+                done: in_scope
+                    .iter()
+                    .filter(|story_id| is_done(state, **story_id, end_of_day))
+                    .count(),
+                scope: in_scope.len(),
+            }
+        })
+        .collect();
+
+    let started_at = story_ids
+        .iter()
+        .filter_map(|id| state.stories.get(id))
+        .flat_map(|story| &story.status_changes)
+        .filter(|change| change.from == Status::Open)
+        .map(|change| change.at)
+        .min();
+    let scope_changes = match started_at {
+        Some(started_at) => epic
+            .scope_changes
+            .iter()
+            .filter(|change| change.at > started_at)
+            .cloned()
+            .collect(),
+        None => vec![],
+    };
+    Ok(Burnup {
+        points,
+        started_at,
+        scope_changes,
+    })
+}
+
+fn in_scope(state: &DBState, epic: &Epic, story_id: u32, at: DateTime<Utc>) -> bool {
+    let changes: Vec<&ScopeChange> = epic
+        .scope_changes
+        .iter()
+        .filter(|change| change.story_id == story_id)
+        .collect();
+    if let Some(last) = changes.iter().rev().find(|change| change.at <= at) {
+        return last.added;
+    }
+    match changes.first() {
+        // removed later, so it was there before anything was recorded
+        Some(first) => !first.added,
+        None => {
+            epic.stories.contains(&story_id)
+                && state
+                    .stories
+                    .get(&story_id)
+                    .and_then(|story| story.created_at)
+                    .is_none_or(|created_at| created_at <= at)
+        }
+    }
+}
+
+fn is_done(state: &DBState, story_id: u32, at: DateTime<Utc>) -> bool {
+    let Some(story) = state.stories.get(&story_id) else {
+        return false;
+    };
+    match story
+        .status_changes
+        .iter()
+        .rev()
+        .find(|change| change.at <= at)
+    {
+        Some(change) => change.to.is_done(),
+        None => match story.status_changes.first() {
+            Some(first) => first.from.is_done(),
+            None => story.status.is_done(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::models::StatusChange;
+
+    #[test]
+    fn epic_burnup_should_show_scope_added_after_the_start() {
+        let now = Utc::now();
+        let days_ago = |days: i64| now - Duration::days(days);
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story("Disputes")
+            .build();
+        for (story_id, created) in [(2, 4), (3, 4), (4, 1)] {
+            state.stories.get_mut(&story_id).unwrap().created_at = Some(days_ago(created));
+        }
+        let epic = state.epics.get_mut(&1).unwrap();
+        epic.stories.retain(|id| *id != 4);
+        epic.scope_changes.push(ScopeChange {
+            story_id: 4,
+            added: true,
+            at: days_ago(1),
+        });
+        epic.stories.push(4);
+        let refunds = state.stories.get_mut(&2).unwrap();
+        refunds.status = Status::Closed;
+        refunds.status_changes = vec![
+            StatusChange {
+                from: Status::Open,
+                to: Status::InProgress,
+                at: days_ago(3),
+            },
+            StatusChange {
+                from: Status::InProgress,
+                to: Status::Closed,
+                at: days_ago(2),
+            },
+        ];
+
+        let burnup = epic_burnup(&state, 1, now).unwrap();
+
+        let points: Vec<(usize, usize)> = burnup
+            .points
+            .iter()
+            .map(|point| (point.done, point.scope))
+            .collect();
+        assert_eq!(points, vec![(0, 2), (0, 2), (1, 2), (1, 3), (1, 3)]);
+        assert_eq!(burnup.started_at, Some(days_ago(3)));
+        assert_eq!(
+            burnup
+                .scope_changes
+                .iter()
+                .map(|change| (change.story_id, change.added))
+                .collect::<Vec<_>>(),
+            vec![(4, true)]
+        );
+    }
+}
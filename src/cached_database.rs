@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+use std::fs;
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+use crate::dao::Database;
+use crate::json_file_database_adapter::JSONFileJiraDAOAdapter;
+use crate::models::DBState;
+
+/// What a file looked like when it was last read or written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &str) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Wraps the JSON file so the board is parsed on the first read instead of
+/// on every page draw, and parsed again only once the file changed on disk,
+/// e.g. because another jira_cli process saved it.
+pub struct CachedFileDatabase {
+    database: JSONFileJiraDAOAdapter,
+    cache: RefCell<Option<(FileStamp, DBState)>>,
+}
+
+impl CachedFileDatabase {
+    pub fn new(database: JSONFileJiraDAOAdapter) -> Self {
+        Self {
+            database,
+            cache: RefCell::new(None),
+        }
+    }
+
+    fn remember(&self, state: &DBState) {
+        *self.cache.borrow_mut() =
+            FileStamp::of(&self.database.path).map(|stamp| (stamp, state.clone()));
+    }
+}
+
+impl Database for CachedFileDatabase {
+    fn retrieve(&self) -> Result<DBState> {
+        if let Some((stamp, state)) = self.cache.borrow().as_ref() {
+            if FileStamp::of(&self.database.path) == Some(*stamp) {
+                return Ok(state.clone());
+            }
+        }
+        let state = self.database.retrieve()?;
+        self.remember(&state);
+        Ok(state)
+    }
+
+    fn persist(&self, state: &DBState) -> Result<()> {
+        self.cache.borrow_mut().take();
+        self.database.persist(state)?;
+        self.remember(state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn retrieve_should_reread_the_file_only_once_it_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json").to_str().unwrap().to_owned();
+        let board = DbFixtureBuilder::new().with_epic("epic").build();
+        fs::write(&path, serde_json::to_vec(&board).unwrap()).unwrap();
+        let sut = CachedFileDatabase::new(JSONFileJiraDAOAdapter { path: path.clone() });
+
+        assert_eq!(sut.retrieve().unwrap(), board);
+        let mut saved = board.clone();
+        saved.last_item_id = 7;
+        sut.persist(&saved).unwrap();
+        assert_eq!(sut.retrieve().unwrap(), saved);
+
+        let mut changed_elsewhere = board.clone();
+        changed_elsewhere.last_item_id = 42;
+        let mut content = serde_json::to_vec(&changed_elsewhere).unwrap();
+        // a different length, so the change shows even within one mtime tick
+        content.push(b'\n');
+        fs::write(&path, content).unwrap();
+        assert_eq!(sut.retrieve().unwrap(), changed_elsewhere);
+    }
+}
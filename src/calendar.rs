@@ -0,0 +1,96 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Which days count as working days, e.g.
+/// `{ "working_days": ["Mon", "Tue", "Wed", "Thu"], "holidays": ["2023-12-25"] }`.
+/// Defaults to Monday to Friday without holidays.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Calendar {
+    pub working_days: Vec<Weekday>,
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            working_days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            holidays: vec![],
+        }
+    }
+}
+
+impl Calendar {
+    pub fn is_working_day(&self, day: NaiveDate) -> bool {
+        self.working_days.contains(&day.weekday()) && !self.holidays.contains(&day)
+    }
+
+    /// Working days from the day after `from` up to and including `to`, so
+    /// something started on a Monday is one working day old on Tuesday.
+    pub fn working_days_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        from.iter_days()
+            .skip(1)
+            .take_while(|day| *day <= to)
+            .filter(|day| self.is_working_day(*day))
+            .count() as i64
+    }
+
+    /// The `count`th working day after `day`, or `None` when the calendar
+    /// has no working days.
+    pub fn add_working_days(&self, day: NaiveDate, count: u32) -> Option<NaiveDate> {
+        if self.working_days.is_empty() {
+            return None;
+        }
+        day.iter_days()
+            .skip(1)
+            .filter(|day| self.is_working_day(*day))
+            .nth(count.checked_sub(1)? as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A day of May 2023; the 1st was a Monday.
+    fn may(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 5, day).unwrap()
+    }
+
+    #[test]
+    fn working_days_between_should_skip_weekends_and_holidays() {
+        let calendar = Calendar::default();
+        assert_eq!(calendar.working_days_between(may(1), may(2)), 1);
+        assert_eq!(calendar.working_days_between(may(5), may(8)), 1);
+        assert_eq!(calendar.working_days_between(may(1), may(15)), 10);
+        assert_eq!(calendar.working_days_between(may(3), may(3)), 0);
+
+        let calendar: Calendar = serde_json::from_str(r#"{ "holidays": ["2023-05-08"] }"#).unwrap();
+        assert_eq!(calendar.working_days_between(may(5), may(9)), 1);
+    }
+
+    #[test]
+    fn add_working_days_should_land_on_a_working_day() {
+        let calendar: Calendar = serde_json::from_str(
+            r#"{ "working_days": ["Monday", "Tue", "Wed", "Thu"], "holidays": ["2023-05-09"] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(calendar.add_working_days(may(3), 1), Some(may(4)));
+        assert_eq!(calendar.add_working_days(may(4), 2), Some(may(10)));
+        assert_eq!(
+            Calendar {
+                working_days: vec![],
+                holidays: vec![],
+            }
+            .add_working_days(may(4), 2),
+            None
+        );
+    }
+}
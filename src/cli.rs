@@ -0,0 +1,539 @@
+use anyhow::{anyhow, Result};
+
+use crate::story_template::parse_var;
+
+pub const USAGE: &str = "Usage:
+    jira_cli                              start the interactive board
+    jira_cli --epic <id>                  start focused on a single epic
+    jira_cli --tutorial                   learn the basics on a sample board
+    jira_cli --workspace <name> ...       run any command on a configured workspace
+    jira_cli open <id>                    start on an epic or story page
+    jira_cli export-archive <file>        write the board to a .tar.gz archive
+    jira_cli import-archive <file> [--strict]
+                                          restore the board from an archive, with
+                                          --strict rejecting broken references
+    jira_cli import-outline <file>        create epics and stories from a bullet outline
+    jira_cli import-csv <file> [--profile <name>]
+                                          create epics and stories from a CSV file,
+                                          mapping its columns or reusing a saved profile
+    jira_cli export-dot <file>            write the story dependencies as Graphviz DOT,
+                                          or as SVG for a .svg file when dot is installed
+    jira_cli import-dot <file>            set the blockers of the stories in a DOT file
+                                          from its edges
+    jira_cli story create --template <name> --epic <id> [--var <name>=<value>...]
+                                          create a story from a configured template
+    jira_cli stats [--json]               print story counts and epic progress
+    jira_cli renumber [--by-epic] [--dry-run]
+                                          close the gaps in item ids, or number each
+                                          epic followed by its stories
+    jira_cli install-commit-hook [--epic <id>]
+                                          prefix commit messages with a story key
+    jira_cli plugins                      list the installed plugins
+    jira_cli plugin <name> [<id>]         run a plugin, optionally on an epic or story
+    jira_cli report [<name>]              run a report from the configured script,
+                                          or list every report
+    jira_cli report run <name>            run the report defined in <name>.toml
+    jira_cli doctor                       check the config, board and journal and
+                                          suggest fixes
+    jira_cli vacuum                       fold old activity log entries into monthly
+                                          summaries, per \"activity_retention\" in the config";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Interactive {
+        focus_epic: Option<String>,
+    },
+    Tutorial,
+    Open {
+        item: String,
+    },
+    ExportArchive {
+        archive_path: String,
+    },
+    ImportArchive {
+        archive_path: String,
+        strict: bool,
+    },
+    ImportOutline {
+        outline_path: String,
+    },
+    ImportCsv {
+        csv_path: String,
+        profile: Option<String>,
+    },
+    ExportDot {
+        dot_path: String,
+    },
+    ImportDot {
+        dot_path: String,
+    },
+    InstallCommitHook {
+        focus_epic: Option<String>,
+    },
+    CreateStory {
+        template: String,
+        epic: String,
+        vars: Vec<(String, String)>,
+    },
+    Stats {
+        json: bool,
+    },
+    Renumber {
+        by_epic: bool,
+        dry_run: bool,
+    },
+    ListPlugins,
+    RunPlugin {
+        name: String,
+        item: Option<String>,
+    },
+    Report {
+        name: Option<String>,
+    },
+    RunReport {
+        name: String,
+    },
+    Doctor,
+    Vacuum,
+}
+
+/// The command that opens an item, printed in exports so scripts can go from
+/// a report back to the board.
+pub fn permalink(key: &str) -> String {
+    format!("jira_cli open {}", key)
+}
+
+/// Takes a leading `--workspace <name>` off the arguments. It picks the
+/// database for whichever command follows.
+pub fn take_workspace(args: &[String]) -> Result<(Option<String>, &[String])> {
+    match args {
+        [flag, name, rest @ ..] if flag == "--workspace" => Ok((Some(name.clone()), rest)),
+        [flag] if flag == "--workspace" => {
+            Err(anyhow!("'--workspace' expects a workspace name\n{}", USAGE))
+        }
+        _ => Ok((None, args)),
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        None => Command::Interactive { focus_epic: None },
+        Some("--epic") => Command::Interactive {
+            focus_epic: Some(next_value(&mut args, "--epic", "an epic id")?),
+        },
+        Some("--tutorial") => Command::Tutorial,
+        Some("open") => Command::Open {
+            item: next_value(&mut args, "open", "an epic or story id")?,
+        },
+        Some("export-archive") => Command::ExportArchive {
+            archive_path: next_value(&mut args, "export-archive", "a file path")?,
+        },
+        Some("import-archive") => Command::ImportArchive {
+            archive_path: next_value(&mut args, "import-archive", "a file path")?,
+            strict: match args.next().map(String::as_str) {
+                None => false,
+                Some("--strict") => true,
+                Some(other) => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+            },
+        },
+        Some("import-outline") => Command::ImportOutline {
+            outline_path: next_value(&mut args, "import-outline", "a file path")?,
+        },
+        Some("import-csv") => Command::ImportCsv {
+            csv_path: next_value(&mut args, "import-csv", "a file path")?,
+            profile: match args.next().map(String::as_str) {
+                None => None,
+                Some("--profile") => Some(next_value(&mut args, "--profile", "a profile name")?),
+                Some(other) => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+            },
+        },
+        Some("export-dot") => Command::ExportDot {
+            dot_path: next_value(&mut args, "export-dot", "a file path")?,
+        },
+        Some("import-dot") => Command::ImportDot {
+            dot_path: next_value(&mut args, "import-dot", "a file path")?,
+        },
+        Some("stats") => Command::Stats {
+            json: match args.next().map(String::as_str) {
+                None => false,
+                Some("--json") => true,
+                Some(other) => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+            },
+        },
+        Some("story") => match args.next().map(String::as_str) {
+            Some("create") => {
+                let (mut template, mut epic, mut vars) = (None, None, vec![]);
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--template" => {
+                            template = Some(next_value(&mut args, "--template", "a template name")?)
+                        }
+                        "--epic" => epic = Some(next_value(&mut args, "--epic", "an epic id")?),
+                        "--var" => vars.push(parse_var(&next_value(
+                            &mut args,
+                            "--var",
+                            "a name=value variable",
+                        )?)?),
+                        other => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+                    }
+                }
+                Command::CreateStory {
+                    template: template
+                        .ok_or_else(|| anyhow!("'story create' expects --template\n{}", USAGE))?,
+                    epic: epic
+                        .ok_or_else(|| anyhow!("'story create' expects --epic\n{}", USAGE))?,
+                    vars,
+                }
+            }
+            _ => return Err(anyhow!("'story' expects 'create'\n{}", USAGE)),
+        },
+        Some("renumber") => {
+            let (mut by_epic, mut dry_run) = (false, false);
+            for arg in args.by_ref() {
+                match arg.as_str() {
+                    "--by-epic" => by_epic = true,
+                    "--dry-run" => dry_run = true,
+                    other => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+                }
+            }
+            Command::Renumber { by_epic, dry_run }
+        }
+        Some("install-commit-hook") => Command::InstallCommitHook {
+            focus_epic: match args.next().map(String::as_str) {
+                None => None,
+                Some("--epic") => Some(next_value(&mut args, "--epic", "an epic id")?),
+                Some(other) => return Err(anyhow!("unexpected argument '{}'\n{}", other, USAGE)),
+            },
+        },
+        Some("plugins") => Command::ListPlugins,
+        Some("plugin") => Command::RunPlugin {
+            name: next_value(&mut args, "plugin", "a plugin name")?,
+            item: args.next().cloned(),
+        },
+        Some("report") => match args.next().map(String::as_str) {
+            Some("run") => Command::RunReport {
+                name: next_value(&mut args, "report run", "a report name")?,
+            },
+            name => Command::Report {
+                name: name.map(str::to_owned),
+            },
+        },
+        Some("doctor") => Command::Doctor,
+        Some("vacuum") => Command::Vacuum,
+        Some(other) => return Err(anyhow!("unknown command '{}'\n{}", other, USAGE)),
+    };
+    if let Some(extra) = args.next() {
+        return Err(anyhow!("unexpected argument '{}'\n{}", extra, USAGE));
+    }
+    Ok(command)
+}
+
+fn next_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    command: &str,
+    expected: &str,
+) -> Result<String> {
+    args.next()
+        .cloned()
+        .ok_or_else(|| anyhow!("'{}' expects {}\n{}", command, expected, USAGE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_args_should_default_to_interactive() {
+        assert_eq!(
+            parse_args(&args(&[])).unwrap(),
+            Command::Interactive { focus_epic: None }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_focus_epic() {
+        assert_eq!(
+            parse_args(&args(&["--epic", "5"])).unwrap(),
+            Command::Interactive {
+                focus_epic: Some("5".to_owned())
+            }
+        );
+        assert_eq!(parse_args(&args(&["--epic"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_tutorial() {
+        assert_eq!(
+            parse_args(&args(&["--tutorial"])).unwrap(),
+            Command::Tutorial
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_open() {
+        assert_eq!(
+            parse_args(&args(&["open", "PROJ-42"])).unwrap(),
+            Command::Open {
+                item: "PROJ-42".to_owned()
+            }
+        );
+        assert_eq!(parse_args(&args(&["open"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_archive_commands() {
+        assert_eq!(
+            parse_args(&args(&["export-archive", "board.tar.gz"])).unwrap(),
+            Command::ExportArchive {
+                archive_path: "board.tar.gz".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["import-archive", "board.tar.gz"])).unwrap(),
+            Command::ImportArchive {
+                archive_path: "board.tar.gz".to_owned(),
+                strict: false
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["import-archive", "board.tar.gz", "--strict"])).unwrap(),
+            Command::ImportArchive {
+                archive_path: "board.tar.gz".to_owned(),
+                strict: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_import_outline() {
+        assert_eq!(
+            parse_args(&args(&["import-outline", "plan.md"])).unwrap(),
+            Command::ImportOutline {
+                outline_path: "plan.md".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_dot_export_and_import() {
+        assert_eq!(
+            parse_args(&args(&["export-dot", "deps.svg"])).unwrap(),
+            Command::ExportDot {
+                dot_path: "deps.svg".to_owned()
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["import-dot", "deps.dot"])).unwrap(),
+            Command::ImportDot {
+                dot_path: "deps.dot".to_owned()
+            }
+        );
+        assert_eq!(parse_args(&args(&["import-dot"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_import_csv() {
+        assert_eq!(
+            parse_args(&args(&["import-csv", "stories.csv"])).unwrap(),
+            Command::ImportCsv {
+                csv_path: "stories.csv".to_owned(),
+                profile: None
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&[
+                "import-csv",
+                "stories.csv",
+                "--profile",
+                "tracker"
+            ]))
+            .unwrap(),
+            Command::ImportCsv {
+                csv_path: "stories.csv".to_owned(),
+                profile: Some("tracker".to_owned())
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["import-csv", "stories.csv", "--profile"])).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_story_create() {
+        assert_eq!(
+            parse_args(&args(&[
+                "story",
+                "create",
+                "--template",
+                "bug",
+                "--var",
+                "severity=high",
+                "--epic",
+                "4",
+                "--var",
+                "title=Refunds fail",
+            ]))
+            .unwrap(),
+            Command::CreateStory {
+                template: "bug".to_owned(),
+                epic: "4".to_owned(),
+                vars: vec![
+                    ("severity".to_owned(), "high".to_owned()),
+                    ("title".to_owned(), "Refunds fail".to_owned())
+                ]
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["story", "create", "--template", "bug"])).is_err(),
+            true
+        );
+        assert_eq!(
+            parse_args(&args(&[
+                "story",
+                "create",
+                "--template",
+                "bug",
+                "--epic",
+                "4",
+                "--var",
+                "severity"
+            ]))
+            .is_err(),
+            true
+        );
+        assert_eq!(parse_args(&args(&["story"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_install_commit_hook() {
+        assert_eq!(
+            parse_args(&args(&["install-commit-hook"])).unwrap(),
+            Command::InstallCommitHook { focus_epic: None }
+        );
+        assert_eq!(
+            parse_args(&args(&["install-commit-hook", "--epic", "PROJ-1"])).unwrap(),
+            Command::InstallCommitHook {
+                focus_epic: Some("PROJ-1".to_owned())
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["install-commit-hook", "--force"])).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn parse_args_should_parse_stats() {
+        assert_eq!(
+            parse_args(&args(&["stats"])).unwrap(),
+            Command::Stats { json: false }
+        );
+        assert_eq!(
+            parse_args(&args(&["stats", "--json"])).unwrap(),
+            Command::Stats { json: true }
+        );
+        assert_eq!(parse_args(&args(&["stats", "--csv"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_renumber() {
+        assert_eq!(
+            parse_args(&args(&["renumber"])).unwrap(),
+            Command::Renumber {
+                by_epic: false,
+                dry_run: false
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["renumber", "--dry-run", "--by-epic"])).unwrap(),
+            Command::Renumber {
+                by_epic: true,
+                dry_run: true
+            }
+        );
+        assert_eq!(parse_args(&args(&["renumber", "--all"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_plugins() {
+        assert_eq!(
+            parse_args(&args(&["plugins"])).unwrap(),
+            Command::ListPlugins
+        );
+        assert_eq!(
+            parse_args(&args(&["plugin", "release-notes", "PROJ-4"])).unwrap(),
+            Command::RunPlugin {
+                name: "release-notes".to_owned(),
+                item: Some("PROJ-4".to_owned())
+            }
+        );
+        assert_eq!(parse_args(&args(&["plugin"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_report() {
+        assert_eq!(
+            parse_args(&args(&["report"])).unwrap(),
+            Command::Report { name: None }
+        );
+        assert_eq!(
+            parse_args(&args(&["report", "velocity"])).unwrap(),
+            Command::Report {
+                name: Some("velocity".to_owned())
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["report", "velocity", "x"])).is_err(),
+            true
+        );
+        assert_eq!(
+            parse_args(&args(&["report", "run", "open-bugs"])).unwrap(),
+            Command::RunReport {
+                name: "open-bugs".to_owned()
+            }
+        );
+        assert_eq!(parse_args(&args(&["report", "run"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_doctor() {
+        assert_eq!(parse_args(&args(&["doctor"])).unwrap(), Command::Doctor);
+        assert_eq!(parse_args(&args(&["doctor", "x"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_parse_vacuum() {
+        assert_eq!(parse_args(&args(&["vacuum"])).unwrap(), Command::Vacuum);
+    }
+
+    #[test]
+    fn take_workspace_should_split_off_the_flag() {
+        let with_flag = args(&["--workspace", "acme", "stats"]);
+        assert_eq!(
+            take_workspace(&with_flag).unwrap(),
+            (Some("acme".to_owned()), &with_flag[2..])
+        );
+        let without_flag = args(&["stats", "--json"]);
+        assert_eq!(
+            take_workspace(&without_flag).unwrap(),
+            (None, &without_flag[..])
+        );
+        assert_eq!(take_workspace(&args(&["--workspace"])).is_err(), true);
+    }
+
+    #[test]
+    fn parse_args_should_reject_invalid_input() {
+        assert_eq!(parse_args(&args(&["export-archive"])).is_err(), true);
+        assert_eq!(parse_args(&args(&["unknown"])).is_err(), true);
+        assert_eq!(
+            parse_args(&args(&["import-archive", "a.tar.gz", "b.tar.gz"])).is_err(),
+            true
+        );
+    }
+}
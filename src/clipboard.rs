@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+
+use anyhow::{anyhow, Result};
+use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
+
+use crate::models::Status;
+
+/// How `[y] yank` formats an item before copying it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum YankFormat {
+    #[default]
+    Markdown,
+    Plain,
+}
+
+impl YankFormat {
+    pub fn summary(&self, key: &str, name: &str, status: &Status) -> String {
+        match self {
+            YankFormat::Markdown => format!("**{}** {} `{}`", key, name, status),
+            YankFormat::Plain => format!("{} {} [{}]", key, name, status),
+        }
+    }
+}
+
+pub type CopyToClipboard = dyn Fn(&str) -> Result<()>;
+
+thread_local! {
+    // On Linux the copied text is served by this process, so the clipboard
+    // has to outlive the copy call.
+    static CLIPBOARD: RefCell<Option<Clipboard>> = const { RefCell::new(None) };
+}
+
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    CLIPBOARD.with(|clipboard| {
+        let mut clipboard = clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(
+                Clipboard::new().map_err(|error| anyhow!("clipboard unavailable: {}", error))?,
+            );
+        }
+        clipboard
+            .as_mut()
+            .unwrap()
+            .set_text(text)
+            .map_err(|error| anyhow!("failed to copy to clipboard: {}", error))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_should_follow_the_format() {
+        assert_eq!(
+            YankFormat::Markdown.summary("PROJ-12", "Refunds", &Status::InProgress),
+            "**PROJ-12** Refunds `IN PROGRESS`"
+        );
+        assert_eq!(
+            YankFormat::Plain.summary("12", "Refunds", &Status::Open),
+            "12 Refunds [OPEN]"
+        );
+    }
+}
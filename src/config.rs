@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::activity_log::ActivityRetention;
+use crate::calendar::Calendar;
+use crate::clipboard::YankFormat;
+use crate::estimation::PointScale;
+use crate::feedback::FeedbackConfig;
+use crate::id_format::IdFormat;
+use crate::models::{Status, Story};
+use crate::recipes::Recipe;
+use crate::sla::Sla;
+use crate::story_template::StoryTemplate;
+use crate::time_display::TimeDisplay;
+use crate::ui::{InputMode, ListColumns};
+use crate::workflow::Workflow;
+
+pub const DEFAULT_DATABASE_PATH: &str = "./data/db.json";
+pub const DEFAULT_CONFIG_PATH: &str = "./data/config.json";
+pub const DEFAULT_WATCHLIST_PATH: &str = "./data/watchlist.json";
+pub const DEFAULT_CSV_PROFILES_PATH: &str = "./data/csv_profiles.json";
+
+/// User preferences read from `data/config.json`. Every field has a default,
+/// so a missing file or a partial one is valid.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub id_format: IdFormat,
+    pub time_display: TimeDisplay,
+    pub input_mode: InputMode,
+    pub yank_format: YankFormat,
+    /// Repository to look up story commits and create branches in. Git
+    /// features are hidden when it is not set.
+    pub git_repo: Option<String>,
+    /// When set, edits are saved in the background once no other edit came
+    /// in for this many milliseconds. Quitting saves anything pending.
+    pub write_behind_ms: Option<u64>,
+    /// When true, a story can't be resolved while any of its acceptance
+    /// criteria is unchecked.
+    pub require_acceptance_criteria: bool,
+    /// Columns of the epic and story listings, e.g.
+    /// `{ "stories": ["id", "name", "progress", "status"] }`.
+    pub columns: ListColumns,
+    /// How many stories the grooming page picks each day. Defaults to 3.
+    pub grooming_count: Option<usize>,
+    /// Status workflows by story kind, e.g.
+    /// `{ "chore": [{ "from": "Open", "to": ["Closed"] }] }`.
+    pub workflows: HashMap<String, Workflow>,
+    /// Bell, flash or banner on errors and confirmations, e.g.
+    /// `{ "on_error": "bell", "on_success": "banner" }`.
+    pub feedback: FeedbackConfig,
+    /// Where to look for plugin executables. Defaults to
+    /// `~/.config/jira-cli/plugins`.
+    pub plugins_dir: Option<String>,
+    /// Rhai script defining event hooks such as `on_story_closed` and
+    /// `report_<name>` functions.
+    pub script: Option<String>,
+    /// Named databases to pick with `--workspace` or switch between on the
+    /// home page, e.g. `{ "acme": "/home/me/boards/acme.json" }`.
+    pub workspaces: HashMap<String, String>,
+    /// What stories are estimated in: `fibonacci` (the default),
+    /// `powers_of_two` or `t_shirt`.
+    pub point_scale: PointScale,
+    /// Changes touching more items than this, e.g. deleting a large epic,
+    /// ask for the count to be typed, are backed up and go to the activity
+    /// log. Defaults to 10.
+    pub bulk_limit: Option<usize>,
+    /// Where `jira_cli report run <name>` finds `<name>.toml`. Defaults to
+    /// `data/reports`.
+    pub reports_dir: Option<String>,
+    /// How many working days a story may stay in a status, e.g.
+    /// `[{ "status": "InProgress", "working_days": 5 }]`. Stories over the
+    /// limit are flagged in listings and counted in `stats`.
+    pub slas: Vec<Sla>,
+    /// Working days and holidays, skipped by SLA timers, working-day
+    /// reminders and the timeline.
+    pub calendar: Calendar,
+    /// How long bulk changes stay in the activity log line by line before
+    /// `jira_cli vacuum` folds them into monthly summaries.
+    pub activity_retention: ActivityRetention,
+    /// Chains of pages and actions run from the recipes page, e.g.
+    /// `[{ "name": "plan", "steps": ["grooming", "refinement"] }]`.
+    /// Defaults to a "start my day" recipe.
+    pub recipes: Vec<Recipe>,
+    /// Stories to create with `jira_cli story create --template <name>`,
+    /// by name; see [`StoryTemplate`].
+    pub story_templates: HashMap<String, StoryTemplate>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let config: Config = serde_json::from_str(&content)
+            .with_context(|| anyhow!("invalid configuration in {}", path.display()))?;
+        config.time_display.validate()?;
+        config.activity_retention.validate()?;
+        Ok(config)
+    }
+
+    /// Where the board of the workspace is stored; without a workspace it
+    /// is `data/db.json`.
+    pub fn database_path(&self, workspace: Option<&str>) -> Result<String> {
+        let Some(name) = workspace else {
+            return Ok(DEFAULT_DATABASE_PATH.to_owned());
+        };
+        self.workspaces.get(name).cloned().ok_or_else(|| {
+            anyhow!(
+                "unknown workspace '{}', configured: {}",
+                name,
+                self.workspace_names().join(", ")
+            )
+        })
+    }
+
+    pub fn workspace_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.workspaces.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// The workflow a story of this kind follows, if one is configured.
+    pub fn workflow_for(&self, kind: Option<&str>) -> Option<&Workflow> {
+        self.workflows.get(kind?)
+    }
+
+    /// The story kinds with a workflow, sorted.
+    pub fn story_kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = self.workflows.keys().cloned().collect();
+        kinds.sort();
+        kinds
+    }
+
+    pub fn ensure_known_kind(&self, kind: &str) -> Result<()> {
+        if !self.workflows.contains_key(kind) {
+            return Err(anyhow!("'{}' is not a configured story type", kind));
+        }
+        Ok(())
+    }
+
+    /// Fails when the story's workflow doesn't allow the change, or when it
+    /// would resolve the story with unchecked acceptance criteria and those
+    /// are required.
+    pub fn ensure_can_transition(&self, story: &Story, status: &Status) -> Result<()> {
+        if let Some(workflow) = self.workflow_for(story.kind.as_deref()) {
+            if !workflow.allows(&story.status, status) {
+                return Err(anyhow!(
+                    "a {} story can't go from {} to {}",
+                    story.kind.clone().unwrap_or_default(),
+                    story.status,
+                    status
+                ));
+            }
+        }
+        if !self.require_acceptance_criteria || *status != Status::Resolved {
+            return Ok(());
+        }
+        let open_criteria = story.open_criteria();
+        if open_criteria.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "can't resolve while {} unchecked",
+            open_criteria
+                .iter()
+                .map(|number| format!("AC-{}", number))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    #[test]
+    fn database_path_should_look_up_the_workspace() {
+        let config: Config =
+            serde_json::from_str(r#"{ "workspaces": { "acme": "boards/acme.json" } }"#).unwrap();
+
+        assert_eq!(config.database_path(None).unwrap(), DEFAULT_DATABASE_PATH);
+        assert_eq!(
+            config.database_path(Some("acme")).unwrap(),
+            "boards/acme.json"
+        );
+        assert_eq!(config.database_path(Some("globex")).is_err(), true);
+    }
+
+    #[test]
+    fn load_should_default_when_file_is_missing() {
+        let config = Config::load(Path::new("INVALID_PATH")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn load_should_parse_config_file() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmpfile,
+            r#"{{ "id_format": {{ "style": "prefixed", "prefix": "PROJ" }} }}"#
+        )
+        .unwrap();
+
+        let config = Config::load(tmpfile.path()).unwrap();
+        assert_eq!(
+            config.id_format,
+            IdFormat::Prefixed {
+                prefix: "PROJ".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn load_should_reject_invalid_time_display() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmpfile,
+            r#"{{ "time_display": {{ "time_zone": "Mars/Olympus" }} }}"#
+        )
+        .unwrap();
+        assert_eq!(Config::load(tmpfile.path()).is_err(), true);
+
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmpfile,
+            r#"{{ "time_display": {{ "date_format": "%Q" }} }}"#
+        )
+        .unwrap();
+        assert_eq!(Config::load(tmpfile.path()).is_err(), true);
+    }
+
+    #[test]
+    fn load_should_reject_negative_activity_retention() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            tmpfile,
+            r#"{{ "activity_retention": {{ "keep_days": -1 }} }}"#
+        )
+        .unwrap();
+        assert_eq!(Config::load(tmpfile.path()).is_err(), true);
+    }
+
+    #[test]
+    fn load_should_parse_input_mode() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(tmpfile, r#"{{ "input_mode": "line" }}"#).unwrap();
+
+        let config = Config::load(tmpfile.path()).unwrap();
+        assert_eq!(config.input_mode, InputMode::Line);
+    }
+
+    #[test]
+    fn load_should_fail_with_invalid_json() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        write!(tmpfile, r#"{{ "id_format": "#).unwrap();
+        assert_eq!(Config::load(tmpfile.path()).is_err(), true);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Epic, Status, Story};
+
+/// Epic of the stories whose row names none.
+const DEFAULT_EPIC: &str = "Imported";
+
+/// What a CSV column holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvField {
+    Name,
+    Description,
+    Status,
+    /// The name of the epic; rows with the same one share an epic.
+    Epic,
+    Points,
+    Kind,
+}
+
+impl CsvField {
+    pub const ALL: [CsvField; 6] = [
+        CsvField::Name,
+        CsvField::Description,
+        CsvField::Status,
+        CsvField::Epic,
+        CsvField::Points,
+        CsvField::Kind,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CsvField::Name => "name",
+            CsvField::Description => "description",
+            CsvField::Status => "status",
+            CsvField::Epic => "epic",
+            CsvField::Points => "points",
+            CsvField::Kind => "kind",
+        }
+    }
+
+    /// The field a header or a typed answer stands for, e.g. `Summary`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "name" | "title" | "summary" => Some(CsvField::Name),
+            "description" | "details" => Some(CsvField::Description),
+            "status" | "state" => Some(CsvField::Status),
+            "epic" | "epic name" | "epic link" => Some(CsvField::Epic),
+            "points" | "story points" | "estimate" => Some(CsvField::Points),
+            "kind" | "type" | "issue type" => Some(CsvField::Kind),
+            _ => None,
+        }
+    }
+}
+
+/// Fields by column header; columns left out are skipped.
+pub type CsvMapping = BTreeMap<String, CsvField>;
+
+/// Mappings saved under a name to reuse on the next import, kept in a file
+/// of their own, e.g. `data/csv_profiles.json`.
+pub struct CsvProfiles {
+    path: PathBuf,
+    pub profiles: HashMap<String, CsvMapping>,
+}
+
+impl CsvProfiles {
+    /// Starts empty when the file doesn't exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let profiles = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .with_context(|| anyhow!("invalid CSV profiles in {}", path.display()))?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self { path, profiles })
+    }
+
+    pub fn save(&mut self, name: &str, mapping: CsvMapping) -> Result<()> {
+        self.profiles.insert(name.to_owned(), mapping);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.profiles)?)
+            .with_context(|| anyhow!("could not save {}", self.path.display()))
+    }
+}
+
+/// Splits CSV into rows of fields. Fields may be quoted, with `""` for a
+/// quote, and quoted fields may span lines.
+pub fn parse_csv(content: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("row {}: unterminated quote", rows.len() + 1));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    Ok(rows)
+}
+
+/// Maps the headers that name a field, the first one winning.
+pub fn guess_mapping(header: &[String]) -> CsvMapping {
+    let mut mapping = CsvMapping::new();
+    for column in header {
+        if let Some(field) = CsvField::from_label(column) {
+            if !mapping.values().any(|mapped| *mapped == field) {
+                mapping.insert(column.clone(), field);
+            }
+        }
+    }
+    mapping
+}
+
+fn parse_status(value: &str) -> Option<Status> {
+    let value = value.trim().to_lowercase().replace(['_', '-'], " ");
+    match value.as_str() {
+        "" | "open" | "to do" | "todo" => Some(Status::Open),
+        "in progress" | "inprogress" => Some(Status::InProgress),
+        "resolved" | "done" => Some(Status::Resolved),
+        "closed" => Some(Status::Closed),
+        _ => None,
+    }
+}
+
+/// Builds stories from the rows after the header, grouped into epics by
+/// their epic column in the order the epics first appear.
+pub fn apply_mapping(
+    rows: &[Vec<String>],
+    mapping: &CsvMapping,
+) -> Result<Vec<(Epic, Vec<Story>)>> {
+    let (header, rows) = rows
+        .split_first()
+        .ok_or_else(|| anyhow!("the file is empty"))?;
+    if !mapping.values().any(|field| *field == CsvField::Name) {
+        return Err(anyhow!("no column is mapped to the story name"));
+    }
+    let columns: Vec<(usize, CsvField)> = header
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| Some((index, *mapping.get(column)?)))
+        .collect();
+
+    let mut epics: Vec<(Epic, Vec<Story>)> = vec![];
+    for (index, row) in rows.iter().enumerate() {
+        let row_number = index + 2;
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        let mut epic_name = DEFAULT_EPIC;
+        for (column, field) in &columns {
+            let value = row.get(*column).map_or("", |value| value.trim());
+            match field {
+                CsvField::Name => story.name = value.to_owned(),
+                CsvField::Description => story.description = value.to_owned(),
+                CsvField::Status => {
+                    story.status = parse_status(value)
+                        .ok_or_else(|| anyhow!("row {}: unknown status '{}'", row_number, value))?
+                }
+                CsvField::Epic if !value.is_empty() => epic_name = value,
+                CsvField::Epic => {}
+                CsvField::Points if value.is_empty() => {}
+                CsvField::Points => {
+                    story.points = Some(value.parse().map_err(|_| {
+                        anyhow!("row {}: '{}' is not a number of points", row_number, value)
+                    })?)
+                }
+                CsvField::Kind => {
+                    story.kind = Some(value.to_lowercase()).filter(|kind| !kind.is_empty())
+                }
+            }
+        }
+        if story.name.is_empty() {
+            return Err(anyhow!("row {}: the story has no name", row_number));
+        }
+        match epics.iter_mut().find(|(epic, _)| epic.name == epic_name) {
+            Some((_, stories)) => stories.push(story),
+            None => epics.push((Epic::new(epic_name.to_owned(), "".to_owned()), vec![story])),
+        }
+    }
+    Ok(epics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+Summary,Story Points,Epic,Notes,State\r
+Refunds,3,Payments,\"Partial, too\",In Progress\r
+\"Invoices \"\"v2\"\"\",,Billing,,\r
+Receipts,5,Payments,,done\r
+";
+
+    fn header() -> Vec<String> {
+        parse_csv(CSV).unwrap().remove(0)
+    }
+
+    #[test]
+    fn parse_csv_should_handle_quotes_and_line_endings() {
+        let rows = parse_csv(CSV).unwrap();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1][3], "Partial, too");
+        assert_eq!(rows[2][0], "Invoices \"v2\"");
+        assert_eq!(parse_csv("a,\"b\nc\"\n").unwrap(), vec![vec!["a", "b\nc"]]);
+        assert_eq!(parse_csv("a,\"b\n").is_err(), true);
+    }
+
+    #[test]
+    fn guess_mapping_should_recognize_common_headers() {
+        let mapping = guess_mapping(&header());
+
+        assert_eq!(mapping.get("Summary"), Some(&CsvField::Name));
+        assert_eq!(mapping.get("Story Points"), Some(&CsvField::Points));
+        assert_eq!(mapping.get("State"), Some(&CsvField::Status));
+        assert_eq!(mapping.get("Notes"), None);
+    }
+
+    #[test]
+    fn apply_mapping_should_group_stories_by_epic() {
+        let mut mapping = guess_mapping(&header());
+        mapping.insert("Notes".to_owned(), CsvField::Description);
+
+        let epics = apply_mapping(&parse_csv(CSV).unwrap(), &mapping).unwrap();
+
+        assert_eq!(epics.len(), 2);
+        let (payments, stories) = &epics[0];
+        assert_eq!(payments.name, "Payments");
+        assert_eq!(stories.len(), 2);
+        assert_eq!(stories[0].status, Status::InProgress);
+        assert_eq!(stories[0].points, Some(3));
+        assert_eq!(stories[0].description, "Partial, too");
+        assert_eq!(stories[1].status, Status::Resolved);
+        assert_eq!(epics[1].1[0].points, None);
+
+        mapping.remove("Summary");
+        assert_eq!(
+            apply_mapping(&parse_csv(CSV).unwrap(), &mapping).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn apply_mapping_should_report_row_numbers() {
+        let rows = parse_csv("name,status\nRefunds,blocked\n").unwrap();
+
+        let error = apply_mapping(&rows, &guess_mapping(&rows[0])).unwrap_err();
+
+        assert_eq!(error.to_string(), "row 2: unknown status 'blocked'");
+    }
+
+    #[test]
+    fn csv_profiles_should_be_saved_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("csv_profiles.json");
+
+        let mut profiles = CsvProfiles::load(&path).unwrap();
+        profiles.save("tracker", guess_mapping(&header())).unwrap();
+
+        let profiles = CsvProfiles::load(&path).unwrap();
+        assert_eq!(profiles.profiles["tracker"], guess_mapping(&header()));
+    }
+}
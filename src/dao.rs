@@ -1,10 +1,90 @@
 use anyhow::{anyhow, Ok, Result};
+use chrono::{DateTime, Utc};
 
-use crate::models::{DBState, Epic, Status, Story};
+use crate::dependencies::{plan_epic, DependencyPlan};
+use crate::models::{AcceptanceCriterion, Comment, DBState, Epic, ExternalBlocker, Status, Story};
 
 pub trait Database {
     fn retrieve(&self) -> Result<DBState>;
     fn persist(&self, state: &DBState) -> Result<()>;
+
+    /// Waits until every accepted write is stored. Databases that write
+    /// synchronously have nothing to do.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An epic as shown in listings, without its story ids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpicSummary {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub status: Status,
+    pub stories_total: usize,
+    pub stories_done: usize,
+}
+
+/// A story as shown in listings, without its comments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorySummary {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub status: Status,
+    pub criteria_total: usize,
+    pub criteria_done: usize,
+    pub ready: bool,
+    pub blocked_by: Vec<u32>,
+    pub external_blockers: usize,
+}
+
+/// Everything the story page shows, read from the board in one go.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryView {
+    pub story: Story,
+    pub epic: EpicSummary,
+    /// The stories this one is blocked by that are still on the board.
+    pub blockers: Vec<StorySummary>,
+}
+
+fn epic_summary(state: &DBState, id: u32, epic: &Epic) -> EpicSummary {
+    EpicSummary {
+        id,
+        name: epic.name.clone(),
+        description: epic.description.clone(),
+        status: epic.status.clone(),
+        stories_total: epic.stories.len(),
+        stories_done: epic
+            .stories
+            .iter()
+            .filter_map(|story_id| state.stories.get(story_id))
+            .filter(|story| story.status.is_done())
+            .count(),
+    }
+}
+
+fn story_summary(id: u32, story: &Story) -> StorySummary {
+    StorySummary {
+        id,
+        name: story.name.clone(),
+        description: story.description.clone(),
+        status: story.status.clone(),
+        criteria_total: story.acceptance_criteria.len(),
+        criteria_done: story.acceptance_criteria.len() - story.open_criteria().len(),
+        ready: story.ready,
+        blocked_by: story.blocked_by.clone(),
+        external_blockers: story.external_blockers.len(),
+    }
+}
+
+/// How an epic's stories can be sequenced, with the stories themselves.
+#[derive(Debug, PartialEq)]
+pub struct DependencyView {
+    pub plan: DependencyPlan,
+    /// The stories of `plan.order` followed by those of `plan.cyclic`.
+    pub stories: Vec<StorySummary>,
 }
 
 pub struct JiraDAO {
@@ -20,6 +100,91 @@ impl JiraDAO {
         self.database.retrieve()
     }
 
+    pub fn replace_db(&self, state: &DBState) -> Result<()> {
+        self.database.persist(state)
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.database.flush()
+    }
+
+    pub fn get_epic(&self, epic_id: u32) -> Result<Epic> {
+        self.database
+            .retrieve()?
+            .epics
+            .remove(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {}", epic_id))
+    }
+
+    pub fn get_story(&self, story_id: u32) -> Result<Story> {
+        self.database
+            .retrieve()?
+            .stories
+            .remove(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))
+    }
+
+    /// Every epic ordered by id.
+    pub fn list_epics_summary(&self) -> Result<Vec<EpicSummary>> {
+        let state = self.database.retrieve()?;
+        let mut summaries: Vec<EpicSummary> = state
+            .epics
+            .iter()
+            .map(|(id, epic)| epic_summary(&state, *id, epic))
+            .collect();
+        summaries.sort_by_key(|summary| summary.id);
+        Ok(summaries)
+    }
+
+    /// The stories of an epic in the order they were added to it.
+    pub fn list_stories_of_epic(&self, epic_id: u32) -> Result<Vec<StorySummary>> {
+        let state = self.database.retrieve()?;
+        let epic = state
+            .epics
+            .get(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+        Ok(epic
+            .stories
+            .iter()
+            .filter_map(|id| Some(story_summary(*id, state.stories.get(id)?)))
+            .collect())
+    }
+
+    pub fn get_dependency_view(&self, epic_id: u32) -> Result<DependencyView> {
+        let state = self.database.retrieve()?;
+        let plan = plan_epic(&state, epic_id)?;
+        let stories = plan
+            .order
+            .iter()
+            .chain(plan.cyclic.iter())
+            .filter_map(|id| Some(story_summary(*id, state.stories.get(id)?)))
+            .collect();
+        Ok(DependencyView { plan, stories })
+    }
+
+    pub fn get_story_view(&self, epic_id: u32, story_id: u32) -> Result<StoryView> {
+        let mut state = self.database.retrieve()?;
+        let epic = state
+            .epics
+            .get(&epic_id)
+            .map(|epic| epic_summary(&state, epic_id, epic))
+            .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+        let story = state
+            .stories
+            .remove(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?;
+        let blockers = story
+            .blocked_by
+            .iter()
+            .filter_map(|id| Some(story_summary(*id, state.stories.get(id)?)))
+            .collect();
+        Ok(StoryView {
+            story,
+            epic,
+            blockers,
+        })
+    }
+
     pub fn create_epic(&self, epic: Epic) -> Result<u32> {
         let mut state = self.database.retrieve()?;
         state.last_item_id += 1;
@@ -35,14 +200,86 @@ impl JiraDAO {
             .epics
             .get_mut(&epic_id)
             .ok_or_else(|| anyhow!("Couldn't find epic in database"))?
-            .stories
-            .push(new_id);
+            .add_story(new_id);
         state.stories.insert(new_id, story);
         state.last_item_id = new_id;
         self.database.persist(&state)?;
         Ok(new_id)
     }
 
+    /// Creates every epic together with its stories and persists them at once,
+    /// so a failed import never leaves a partial structure behind.
+    pub fn create_epics_with_stories(&self, epics: Vec<(Epic, Vec<Story>)>) -> Result<Vec<u32>> {
+        let mut state = self.database.retrieve()?;
+        let mut epic_ids = vec![];
+        for (mut epic, stories) in epics {
+            state.last_item_id += 1;
+            let epic_id = state.last_item_id;
+            for story in stories {
+                state.last_item_id += 1;
+                epic.add_story(state.last_item_id);
+                state.stories.insert(state.last_item_id, story);
+            }
+            state.epics.insert(epic_id, epic);
+            epic_ids.push(epic_id);
+        }
+        self.database.persist(&state)?;
+        Ok(epic_ids)
+    }
+
+    /// Moves the given stories, which must all belong to the epic, into a new
+    /// epic and records on both where the split happened. Returns the id of
+    /// the new epic.
+    pub fn split_epic(&self, epic_id: u32, story_ids: &[u32], name: String) -> Result<u32> {
+        if story_ids.is_empty() {
+            return Err(anyhow!("select at least one story to split off"));
+        }
+        let mut state = self.database.retrieve()?;
+        let epic = state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        if let Some(story_id) = story_ids.iter().find(|id| !epic.stories.contains(id)) {
+            return Err(anyhow!("story {} does not belong to this epic", story_id));
+        }
+        let new_epic_id = state.last_item_id + 1;
+        let moved: Vec<u32> = epic
+            .stories
+            .iter()
+            .copied()
+            .filter(|story_id| story_ids.contains(story_id))
+            .collect();
+        let mut new_epic = Epic::new(name, format!("Split from {}", epic.name));
+        for story_id in moved {
+            epic.remove_story(story_id);
+            new_epic.add_story(story_id);
+        }
+        epic.split_into.push(new_epic_id);
+        new_epic.split_from = Some(epic_id);
+        state.epics.insert(new_epic_id, new_epic);
+        state.last_item_id = new_epic_id;
+        self.database.persist(&state)?;
+        Ok(new_epic_id)
+    }
+
+    /// The target epic as [`JiraDAO::merge_epics`] would leave it, without
+    /// saving anything.
+    pub fn preview_epic_merge(&self, source_id: u32, target_id: u32) -> Result<Epic> {
+        let mut state = self.database.retrieve()?;
+        merge_epic_into(&mut state, source_id, target_id)?;
+        Ok(state.epics[&target_id].clone())
+    }
+
+    /// Moves the stories of `source_id` to the end of `target_id`, appends
+    /// its description and deletes it. Split links to the source now point
+    /// to the target.
+    pub fn merge_epics(&self, source_id: u32, target_id: u32) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        merge_epic_into(&mut state, source_id, target_id)?;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
     pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
         let mut state = self.database.retrieve()?;
         for story_id in &state
@@ -52,8 +289,18 @@ impl JiraDAO {
             .stories
         {
             state.stories.remove(story_id);
+            state.queue.retain(|id| id != story_id);
         }
         state.epics.remove(&epic_id);
+        // the epics split off from this one or into it stay, unlinked
+        for epic in state.epics.values_mut() {
+            if epic.split_from == Some(epic_id) {
+                epic.split_from = None;
+            }
+            epic.split_into.retain(|id| *id != epic_id);
+        }
+        remove_dangling_blockers(&mut state);
+        remove_dangling_mirrors(&mut state);
         self.database.persist(&state)?;
         Ok(())
     }
@@ -64,17 +311,134 @@ impl JiraDAO {
             .epics
             .get_mut(&epic_id)
             .ok_or_else(|| anyhow!("could not find epic in database!"))?;
-        let story_index = epic
-            .stories
-            .iter()
-            .position(|id| id == &story_id)
-            .ok_or_else(|| anyhow!("story id not found in epic stories vector"))?;
-        epic.stories.remove(story_index);
+        if !epic.stories.contains(&story_id) {
+            return Err(anyhow!("story id not found in epic stories vector"));
+        }
+        epic.remove_story(story_id);
         state.stories.remove(&story_id);
+        state.queue.retain(|id| *id != story_id);
+        remove_dangling_blockers(&mut state);
+        remove_dangling_mirrors(&mut state);
         self.database.persist(&state)?;
         Ok(())
     }
 
+    /// Records that `blocker_id` has to be done before `story_id`, or removes
+    /// that link if it already exists. Returns whether the link now exists.
+    pub fn toggle_blocker(&self, story_id: u32, blocker_id: u32) -> Result<bool> {
+        let mut state = self.database.retrieve()?;
+        if story_id == blocker_id {
+            return Err(anyhow!("a story can't block itself"));
+        }
+        if !state.stories.contains_key(&blocker_id) {
+            return Err(anyhow!("could not find story {}", blocker_id));
+        }
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?;
+        let linked = match story.blocked_by.iter().position(|id| *id == blocker_id) {
+            Some(index) => {
+                story.blocked_by.remove(index);
+                false
+            }
+            None => {
+                story.blocked_by.push(blocker_id);
+                true
+            }
+        };
+        self.database.persist(&state)?;
+        Ok(linked)
+    }
+
+    /// Lists a story of another epic in this one too, or stops listing it
+    /// there. Returns whether the story is mirrored now.
+    pub fn toggle_mirror(&self, epic_id: u32, story_id: u32) -> Result<bool> {
+        let mut state = self.database.retrieve()?;
+        if !state.stories.contains_key(&story_id) {
+            return Err(anyhow!("could not find story {}", story_id));
+        }
+        let epic = state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+        if epic.stories.contains(&story_id) {
+            return Err(anyhow!("story {} already belongs to this epic", story_id));
+        }
+        let mirrored = match epic.mirrors.iter().position(|id| *id == story_id) {
+            Some(index) => {
+                epic.mirrors.remove(index);
+                false
+            }
+            None => {
+                epic.mirrors.push(story_id);
+                true
+            }
+        };
+        self.database.persist(&state)?;
+        Ok(mirrored)
+    }
+
+    /// Links the story to an outside ticket, or unlinks it when the URL is
+    /// already there. Returns whether the ticket is now linked.
+    pub fn toggle_external_blocker(&self, story_id: u32, url: &str, title: &str) -> Result<bool> {
+        let mut state = self.database.retrieve()?;
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?;
+        let position = story
+            .external_blockers
+            .iter()
+            .position(|blocker| blocker.url == url);
+        let linked = match position {
+            Some(index) => {
+                story.external_blockers.remove(index);
+                false
+            }
+            None => {
+                story.external_blockers.push(ExternalBlocker {
+                    url: url.to_owned(),
+                    title: if title.is_empty() { url } else { title }.to_owned(),
+                });
+                true
+            }
+        };
+        self.database.persist(&state)?;
+        Ok(linked)
+    }
+
+    /// Appends a criterion to the story and returns its number.
+    pub fn add_acceptance_criterion(&self, story_id: u32, text: String) -> Result<usize> {
+        let mut state = self.database.retrieve()?;
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?;
+        story
+            .acceptance_criteria
+            .push(AcceptanceCriterion::new(text));
+        let number = story.acceptance_criteria.len();
+        self.database.persist(&state)?;
+        Ok(number)
+    }
+
+    /// Flips the done flag of a criterion and returns the new value.
+    pub fn toggle_acceptance_criterion(&self, story_id: u32, number: usize) -> Result<bool> {
+        let mut state = self.database.retrieve()?;
+        let criterion = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?
+            .acceptance_criteria
+            .get_mut(number.wrapping_sub(1))
+            .ok_or_else(|| anyhow!("could not find AC-{}", number))?;
+        criterion.done = !criterion.done;
+        let done = criterion.done;
+        self.database.persist(&state)?;
+        Ok(done)
+    }
+
     pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
         let mut state = self.database.retrieve()?;
         let epic = state
@@ -86,18 +450,346 @@ impl JiraDAO {
         Ok(())
     }
 
+    pub fn rename_epic(&self, epic_id: u32, name: String) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("epic id not found"))?
+            .name = name;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn update_epic_description(&self, epic_id: u32, description: String) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("epic id not found"))?
+            .description = description;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
     pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
         let mut state = self.database.retrieve()?;
         let story = state
             .stories
             .get_mut(&story_id)
             .ok_or_else(|| anyhow!("story not found"))?;
-        story.status = status;
+        story.set_status(status);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Records the closing comment and the final status with a single persist.
+    pub fn close_story(&self, story_id: u32, status: Status, comment: Comment) -> Result<()> {
+        if !status.is_done() {
+            return Err(anyhow!("a story can only be closed as resolved or closed"));
+        }
+        let mut state = self.database.retrieve()?;
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?;
+        story.set_status(status);
+        story.comments.push(comment);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Appends the story to the "next up" queue, unless it is already queued.
+    pub fn queue_story(&self, story_id: u32) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        if !state.stories.contains_key(&story_id) {
+            return Err(anyhow!("story not found"));
+        }
+        if !state.queue.contains(&story_id) {
+            state.queue.push(story_id);
+            self.database.persist(&state)?;
+        }
+        Ok(())
+    }
+
+    /// Puts the story at the front of the "next up" queue, moving it there if
+    /// it was already queued.
+    pub fn queue_story_first(&self, story_id: u32) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        if !state.stories.contains_key(&story_id) {
+            return Err(anyhow!("story not found"));
+        }
+        state.queue.retain(|id| *id != story_id);
+        state.queue.insert(0, story_id);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn set_story_kind(&self, story_id: u32, kind: Option<String>) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .kind = kind;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn set_story_points(&self, story_id: u32, points: Option<u32>) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .points = points;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn rename_story(&self, story_id: u32, name: String) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .name = name;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn update_story_description(&self, story_id: u32, description: String) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .description = description;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Marks a story as ready, which requires every refinement gap to be
+    /// filled, or as not ready.
+    pub fn set_story_ready(&self, story_id: u32, ready: bool) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?;
+        let gaps = story.refinement_gaps();
+        if ready && !gaps.is_empty() {
+            return Err(anyhow!(
+                "add {} before marking the story ready",
+                gaps.join(" and ")
+            ));
+        }
+        story.ready = ready;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn set_reminder(&self, story_id: u32, remind_at: Option<DateTime<Utc>>) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .remind_at = remind_at;
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    pub fn add_comment(&self, story_id: u32, comment: Comment) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?
+            .comments
+            .push(comment);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Answers comment `number` (counted from 1) and returns the number of
+    /// the reply.
+    pub fn reply_to_comment(&self, story_id: u32, number: usize, text: String) -> Result<usize> {
+        let mut state = self.database.retrieve()?;
+        let comments = &mut story_mut(&mut state, story_id)?.comments;
+        comment_index(comments, number)?;
+        comments.push(Comment::reply(text, number));
+        let reply_number = comments.len();
+        self.database.persist(&state)?;
+        Ok(reply_number)
+    }
+
+    pub fn edit_comment(&self, story_id: u32, number: usize, text: String) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        let comments = &mut story_mut(&mut state, story_id)?.comments;
+        let index = comment_index(comments, number)?;
+        let comment = &mut comments[index];
+        comment.text = text;
+        comment.edited_at = Some(Utc::now());
         self.database.persist(&state)?;
         Ok(())
     }
+
+    /// Removes comment `number`. Its replies move up to answer whatever it
+    /// answered, and later comments are renumbered.
+    pub fn delete_comment(&self, story_id: u32, number: usize) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        let comments = &mut story_mut(&mut state, story_id)?.comments;
+        let index = comment_index(comments, number)?;
+        let removed = comments.remove(index);
+        for comment in comments.iter_mut() {
+            comment.reply_to = match comment.reply_to {
+                Some(parent) if parent == number => removed.reply_to,
+                Some(parent) if parent > number => Some(parent - 1),
+                parent => parent,
+            };
+        }
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Drops the aliases [`DBState::dead_aliases`] lists and returns how
+    /// many there were.
+    pub fn prune_aliases(&self) -> Result<usize> {
+        let mut state = self.database.retrieve()?;
+        let dead = state.dead_aliases();
+        if dead.is_empty() {
+            return Ok(0);
+        }
+        state.aliases.retain(|id, _| !dead.contains(id));
+        self.database.persist(&state)?;
+        Ok(dead.len())
+    }
+
+    pub fn unqueue_story(&self, story_id: u32) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        state.queue.retain(|id| *id != story_id);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Moves a queued story by `offset` positions, stopping at either end.
+    pub fn move_queued_story(&self, story_id: u32, offset: isize) -> Result<()> {
+        let mut state = self.database.retrieve()?;
+        let position = state
+            .queue
+            .iter()
+            .position(|id| *id == story_id)
+            .ok_or_else(|| anyhow!("story is not queued"))?;
+        let last_position = state.queue.len() as isize - 1;
+        let new_position = (position as isize + offset).clamp(0, last_position) as usize;
+        let story_id = state.queue.remove(position);
+        state.queue.insert(new_position, story_id);
+        self.database.persist(&state)?;
+        Ok(())
+    }
+
+    /// Takes the story at the top of the queue and marks it in progress.
+    pub fn start_next_queued_story(&self) -> Result<Option<u32>> {
+        let mut state = self.database.retrieve()?;
+        if state.queue.is_empty() {
+            return Ok(None);
+        }
+        let story_id = state.queue.remove(0);
+        let story = state
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?;
+        story.set_status(Status::InProgress);
+        self.database.persist(&state)?;
+        Ok(Some(story_id))
+    }
+}
+
+fn merge_epic_into(state: &mut DBState, source_id: u32, target_id: u32) -> Result<()> {
+    if source_id == target_id {
+        return Err(anyhow!("an epic can't be merged into itself"));
+    }
+    if !state.epics.contains_key(&target_id) {
+        return Err(anyhow!("could not find epic {}", target_id));
+    }
+    let source = state
+        .epics
+        .remove(&source_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", source_id))?;
+    state.aliases.insert(source_id, target_id);
+    for epic in state.epics.values_mut() {
+        if epic.split_from == Some(source_id) {
+            epic.split_from = Some(target_id);
+        }
+        for split_id in epic.split_into.iter_mut() {
+            if *split_id == source_id {
+                *split_id = target_id;
+            }
+        }
+    }
+    let target = state
+        .epics
+        .get_mut(&target_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", target_id))?;
+    for story_id in source.stories {
+        target.add_story(story_id);
+    }
+    target.split_into.extend(source.split_into);
+    target.split_into.retain(|id| *id != target_id);
+    if target.split_from == Some(target_id) {
+        target.split_from = None;
+    }
+    if !source.description.trim().is_empty() {
+        let merged = format!("From {}: {}", source.name, source.description);
+        target.description = if target.description.trim().is_empty() {
+            merged
+        } else {
+            format!("{}\n\n{}", target.description, merged)
+        };
+    }
+    target.merged_from.extend(source.merged_from);
+    target.merged_from.push(source.name);
+    for story_id in source.mirrors {
+        if !target.mirrors.contains(&story_id) {
+            target.mirrors.push(story_id);
+        }
+    }
+    remove_dangling_mirrors(state);
+    Ok(())
+}
+
+fn story_mut(state: &mut DBState, story_id: u32) -> Result<&mut Story> {
+    state
+        .stories
+        .get_mut(&story_id)
+        .ok_or_else(|| anyhow!("could not find story {}", story_id))
+}
+
+fn comment_index(comments: &[Comment], number: usize) -> Result<usize> {
+    match number.checked_sub(1) {
+        Some(index) if index < comments.len() => Ok(index),
+        _ => Err(anyhow!("could not find C-{}", number)),
+    }
+}
+
+fn remove_dangling_blockers(state: &mut DBState) {
+    let story_ids: Vec<u32> = state.stories.keys().copied().collect();
+    for story in state.stories.values_mut() {
+        story.blocked_by.retain(|id| story_ids.contains(id));
+    }
+}
+
+/// Drops mirrors of stories that are gone or that the epic owns by now.
+fn remove_dangling_mirrors(state: &mut DBState) {
+    for epic in state.epics.values_mut() {
+        let stories = &epic.stories;
+        epic.mirrors
+            .retain(|id| state.stories.contains_key(id) && !stories.contains(id));
+    }
 }
 
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils {
     use std::{cell::RefCell, collections::HashMap};
 
@@ -114,9 +806,23 @@ pub mod test_utils {
                     last_item_id: 0,
                     epics: HashMap::new(),
                     stories: HashMap::new(),
+                    queue: vec![],
+                    aliases: Default::default(),
                 }),
             }
         }
+
+        pub fn with_state(state: DBState) -> Self {
+            Self {
+                last_written_state: RefCell::new(state),
+            }
+        }
+    }
+
+    impl Default for MockDB {
+        fn default() -> Self {
+            Self::new()
+        }
     }
 
     impl Database for MockDB {
@@ -177,6 +883,320 @@ mod tests {
         assert_eq!(result.is_err(), true);
     }
 
+    #[test]
+    fn read_models_should_only_return_the_requested_items() {
+        let db = make_sut();
+        let first_epic = db.create_epic(empty_epic()).unwrap();
+        let second_epic = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), first_epic).unwrap();
+        db.create_story(empty_story(), second_epic).unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        assert_eq!(db.get_epic(first_epic).unwrap().stories, vec![story_id]);
+        assert_eq!(db.get_story(story_id).unwrap().status, Status::Closed);
+        assert_eq!(db.get_epic(999).is_err(), true);
+        assert_eq!(db.get_story(999).is_err(), true);
+
+        let epics = db.list_epics_summary().unwrap();
+        assert_eq!(
+            epics.iter().map(|epic| epic.id).collect::<Vec<_>>(),
+            vec![first_epic, second_epic]
+        );
+        assert_eq!(epics[0].stories_done, 1);
+        assert_eq!(epics[1].stories_done, 0);
+
+        let stories = db.list_stories_of_epic(first_epic).unwrap();
+        assert_eq!(
+            stories,
+            vec![StorySummary {
+                id: story_id,
+                name: "".to_owned(),
+                description: "".to_owned(),
+                status: Status::Closed,
+                criteria_total: 0,
+                criteria_done: 0,
+                ready: false,
+                blocked_by: vec![],
+                external_blockers: 0,
+            }]
+        );
+        assert_eq!(db.list_stories_of_epic(999).is_err(), true);
+
+        let blocked_id = db.create_story(empty_story(), second_epic).unwrap();
+        db.toggle_blocker(blocked_id, story_id).unwrap();
+        let view = db.get_story_view(second_epic, blocked_id).unwrap();
+        assert_eq!(view.epic.stories_total, 2);
+        assert_eq!(view.blockers, stories);
+        assert_eq!(db.get_story_view(second_epic, 999).is_err(), true);
+        assert_eq!(db.get_story_view(999, blocked_id).is_err(), true);
+
+        let view = db.get_dependency_view(second_epic).unwrap();
+        assert_eq!(view.plan.order, vec![story_id + 1, blocked_id]);
+        assert_eq!(
+            view.stories
+                .iter()
+                .map(|story| story.id)
+                .collect::<Vec<_>>(),
+            view.plan.order
+        );
+        assert_eq!(view.stories[1].blocked_by, vec![story_id]);
+    }
+
+    #[test]
+    fn toggle_blocker_should_link_and_unlink_stories() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let blocker_id = db.create_story(empty_story(), epic_id).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+
+        assert_eq!(db.toggle_blocker(story_id, blocker_id).unwrap(), true);
+        assert_eq!(db.get_story(story_id).unwrap().blocked_by, vec![blocker_id]);
+        assert_eq!(db.toggle_blocker(story_id, blocker_id).unwrap(), false);
+        assert_eq!(
+            db.get_story(story_id).unwrap().blocked_by,
+            Vec::<u32>::new()
+        );
+
+        assert_eq!(db.toggle_blocker(story_id, story_id).is_err(), true);
+        assert_eq!(db.toggle_blocker(story_id, 999).is_err(), true);
+    }
+
+    #[test]
+    fn toggle_mirror_should_list_stories_of_other_epics() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let own_story = db.create_story(empty_story(), epic_id).unwrap();
+        let other_epic = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), other_epic).unwrap();
+
+        assert_eq!(db.toggle_mirror(epic_id, story_id).unwrap(), true);
+        assert_eq!(db.get_epic(epic_id).unwrap().mirrors, vec![story_id]);
+        assert_eq!(db.toggle_mirror(epic_id, story_id).unwrap(), false);
+        assert_eq!(db.get_epic(epic_id).unwrap().mirrors, Vec::<u32>::new());
+        assert_eq!(db.toggle_mirror(epic_id, own_story).is_err(), true);
+        assert_eq!(db.toggle_mirror(epic_id, 999).is_err(), true);
+
+        db.toggle_mirror(epic_id, story_id).unwrap();
+        db.delete_story(other_epic, story_id).unwrap();
+        assert_eq!(db.get_epic(epic_id).unwrap().mirrors, Vec::<u32>::new());
+
+        let story_id = db.create_story(empty_story(), other_epic).unwrap();
+        db.toggle_mirror(epic_id, story_id).unwrap();
+        db.merge_epics(other_epic, epic_id).unwrap();
+        assert_eq!(db.get_epic(epic_id).unwrap().mirrors, Vec::<u32>::new());
+    }
+
+    #[test]
+    fn toggle_external_blocker_should_link_and_unlink_tickets() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+        let url = "https://tracker.example.com/OPS-7";
+
+        assert_eq!(db.toggle_external_blocker(story_id, url, "").unwrap(), true);
+        assert_eq!(
+            db.get_story(story_id).unwrap().external_blockers,
+            vec![ExternalBlocker {
+                url: url.to_owned(),
+                title: url.to_owned()
+            }]
+        );
+        assert_eq!(
+            db.toggle_external_blocker(story_id, url, "ignored")
+                .unwrap(),
+            false
+        );
+        assert_eq!(
+            db.get_story(story_id).unwrap().external_blockers.is_empty(),
+            true
+        );
+        assert_eq!(db.toggle_external_blocker(999, url, "").is_err(), true);
+    }
+
+    #[test]
+    fn delete_story_should_remove_it_from_blocked_stories() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let blocker_id = db.create_story(empty_story(), epic_id).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+        db.toggle_blocker(story_id, blocker_id).unwrap();
+
+        db.delete_story(epic_id, blocker_id).unwrap();
+
+        assert_eq!(
+            db.get_story(story_id).unwrap().blocked_by,
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn acceptance_criteria_should_be_numbered_and_toggled() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+
+        assert_eq!(
+            db.add_acceptance_criterion(story_id, "logs in".to_owned())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            db.add_acceptance_criterion(story_id, "logs out".to_owned())
+                .unwrap(),
+            2
+        );
+        assert_eq!(db.toggle_acceptance_criterion(story_id, 2).unwrap(), true);
+        assert_eq!(db.get_story(story_id).unwrap().open_criteria(), vec![1]);
+
+        assert_eq!(db.toggle_acceptance_criterion(story_id, 0).is_err(), true);
+        assert_eq!(db.toggle_acceptance_criterion(story_id, 3).is_err(), true);
+    }
+
+    #[test]
+    fn queue_story_first_should_move_the_story_to_the_front() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let first = db.create_story(empty_story(), epic_id).unwrap();
+        let second = db.create_story(empty_story(), epic_id).unwrap();
+        db.queue_story(first).unwrap();
+        db.queue_story(second).unwrap();
+
+        db.queue_story_first(second).unwrap();
+
+        assert_eq!(db.read_db().unwrap().queue, vec![second, first]);
+        assert_eq!(db.queue_story_first(999).is_err(), true);
+    }
+
+    #[test]
+    fn split_epic_should_move_the_selected_stories() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let first = db.create_story(empty_story(), epic_id).unwrap();
+        let second = db.create_story(empty_story(), epic_id).unwrap();
+        let third = db.create_story(empty_story(), epic_id).unwrap();
+        db.update_story_status(third, Status::InProgress).unwrap();
+
+        let new_epic_id = db
+            .split_epic(epic_id, &[third, first], "Phase 2".to_owned())
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let original = &db_state.epics[&epic_id];
+        let split = &db_state.epics[&new_epic_id];
+        assert_eq!(original.stories, vec![second]);
+        assert_eq!(original.split_into, vec![new_epic_id]);
+        assert_eq!(split.name, "Phase 2");
+        assert_eq!(split.stories, vec![first, third]);
+        assert_eq!(split.split_from, Some(epic_id));
+        assert_eq!(db_state.stories[&third].status, Status::InProgress);
+    }
+
+    #[test]
+    fn merge_epics_should_move_stories_and_delete_the_source() {
+        let db = make_sut();
+        let target_id = db
+            .create_epic(Epic::new("Payments".to_owned(), "Cards".to_owned()))
+            .unwrap();
+        let kept = db.create_story(empty_story(), target_id).unwrap();
+        let source_id = db
+            .create_epic(Epic::new("Billing".to_owned(), "Invoices".to_owned()))
+            .unwrap();
+        let moved = db.create_story(empty_story(), source_id).unwrap();
+        let split_off = db.create_story(empty_story(), source_id).unwrap();
+        let split_id = db
+            .split_epic(source_id, &[split_off], "Billing 2".to_owned())
+            .unwrap();
+
+        let mut preview = db.preview_epic_merge(source_id, target_id).unwrap();
+        assert_eq!(db.read_db().unwrap().epics.contains_key(&source_id), true);
+
+        db.merge_epics(source_id, target_id).unwrap();
+        let db_state = db.read_db().unwrap();
+        let merged = &db_state.epics[&target_id];
+        // the preview records the moved stories at its own time
+        for (previewed, change) in preview.scope_changes.iter_mut().zip(&merged.scope_changes) {
+            previewed.at = change.at;
+        }
+        assert_eq!(merged, &preview);
+        assert_eq!(
+            merged
+                .scope_changes
+                .last()
+                .map(|change| (change.story_id, change.added)),
+            Some((moved, true))
+        );
+        assert_eq!(db_state.epics.contains_key(&source_id), false);
+        assert_eq!(db_state.resolve(source_id), Some(target_id));
+        assert_eq!(merged.stories, vec![kept, moved]);
+        assert_eq!(merged.description, "Cards\n\nFrom Billing: Invoices");
+        assert_eq!(merged.merged_from, vec!["Billing".to_owned()]);
+        assert_eq!(merged.split_into, vec![split_id]);
+        assert_eq!(db_state.epics[&split_id].split_from, Some(target_id));
+
+        assert_eq!(db.merge_epics(target_id, target_id).is_err(), true);
+        assert_eq!(db.merge_epics(source_id, target_id).is_err(), true);
+    }
+
+    #[test]
+    fn delete_epic_should_unlink_split_epics() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+        db.create_story(empty_story(), epic_id).unwrap();
+        let split_id = db
+            .split_epic(epic_id, &[story_id], "Phase 2".to_owned())
+            .unwrap();
+        let resplit_id = db
+            .split_epic(split_id, &[story_id], "Phase 3".to_owned())
+            .unwrap();
+
+        db.delete_epic(epic_id).unwrap();
+        db.delete_epic(resplit_id).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&split_id].split_from, None);
+        assert_eq!(db_state.epics[&split_id].split_into, Vec::<u32>::new());
+        let content = serde_json::to_string(&db_state).unwrap();
+        assert_eq!(crate::integrity::check_database(&content), vec![]);
+    }
+
+    #[test]
+    fn split_epic_should_reject_foreign_or_missing_stories() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let other_epic_id = db.create_epic(empty_epic()).unwrap();
+        let foreign = db.create_story(empty_story(), other_epic_id).unwrap();
+
+        assert_eq!(
+            db.split_epic(epic_id, &[foreign], "".to_owned()).is_err(),
+            true
+        );
+        assert_eq!(db.split_epic(epic_id, &[], "".to_owned()).is_err(), true);
+        assert_eq!(db.split_epic(999, &[foreign], "".to_owned()).is_err(), true);
+        assert_eq!(db.read_db().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn set_story_ready_should_require_a_refined_story() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+
+        let result = db.set_story_ready(story_id, true);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "add a description and acceptance criteria before marking the story ready"
+        );
+
+        db.update_story_description(story_id, "Refund card payments".to_owned())
+            .unwrap();
+        db.add_acceptance_criterion(story_id, "refunds in full".to_owned())
+            .unwrap();
+        db.set_story_ready(story_id, true).unwrap();
+        assert_eq!(db.get_story(story_id).unwrap().ready, true);
+        assert_eq!(db.set_story_ready(999, false).is_err(), true);
+    }
+
     #[test]
     fn should_create_story() {
         let db = make_sut();
@@ -201,6 +1221,24 @@ mod tests {
         assert_eq!(db_state.stories.get(&id), Some(&story));
     }
 
+    #[test]
+    fn should_create_epics_with_stories() {
+        let db = make_sut();
+        let epics = vec![
+            (empty_epic(), vec![empty_story(), empty_story()]),
+            (empty_epic(), vec![]),
+        ];
+
+        let epic_ids = db.create_epics_with_stories(epics).unwrap();
+        assert_eq!(epic_ids, vec![1, 4]);
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.last_item_id, 4);
+        assert_eq!(db_state.epics.get(&1).unwrap().stories, vec![2, 3]);
+        assert_eq!(db_state.epics.get(&4).unwrap().stories.is_empty(), true);
+        assert_eq!(db_state.stories.len(), 2);
+    }
+
     #[test]
     fn delete_epic_should_error_if_invalid_epic_id() {
         let db = make_sut();
@@ -326,4 +1364,125 @@ mod tests {
             Status::Closed
         );
     }
+
+    #[test]
+    fn delete_comment_should_keep_replies_in_their_thread() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+        db.add_comment(story_id, Comment::new("first".to_owned()))
+            .unwrap();
+        db.add_comment(story_id, Comment::new("question".to_owned()))
+            .unwrap();
+        assert_eq!(
+            db.reply_to_comment(story_id, 2, "answer".to_owned())
+                .unwrap(),
+            3
+        );
+        db.reply_to_comment(story_id, 3, "thanks".to_owned())
+            .unwrap();
+        db.edit_comment(story_id, 3, "the answer".to_owned())
+            .unwrap();
+
+        db.delete_comment(story_id, 1).unwrap();
+        db.delete_comment(story_id, 2).unwrap();
+
+        let comments = db.get_story(story_id).unwrap().comments;
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].text, "question");
+        assert_eq!(comments[1].text, "thanks");
+        assert_eq!(comments[1].reply_to, Some(1));
+        assert_eq!(
+            db.reply_to_comment(story_id, 3, "".to_owned()).is_err(),
+            true
+        );
+        assert_eq!(db.edit_comment(story_id, 0, "".to_owned()).is_err(), true);
+    }
+
+    #[test]
+    fn close_story_should_error_if_invalid_story_id() {
+        let db = make_sut();
+        let comment = Comment::new("done".to_owned());
+        let result = db.close_story(999, Status::Resolved, comment);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn close_story_should_reject_open_statuses() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+
+        let comment = Comment::new("done".to_owned());
+        let result = db.close_story(story_id, Status::InProgress, comment);
+        assert_eq!(result.is_err(), true);
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.status, Status::Open);
+        assert_eq!(story.comments.is_empty(), true);
+    }
+
+    #[test]
+    fn close_story_should_work() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+
+        let comment = Comment::new("shipped in 1.2".to_owned());
+        let result = db.close_story(story_id, Status::Resolved, comment.clone());
+        assert_eq!(result.is_ok(), true);
+
+        let db_state = db.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.status, Status::Resolved);
+        assert_eq!(story.comments, vec![comment]);
+    }
+
+    #[test]
+    fn queue_story_should_error_if_invalid_story_id() {
+        let db = make_sut();
+        assert_eq!(db.queue_story(999).is_err(), true);
+    }
+
+    #[test]
+    fn queue_should_keep_order_and_skip_duplicates() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let first = db.create_story(empty_story(), epic_id).unwrap();
+        let second = db.create_story(empty_story(), epic_id).unwrap();
+        let third = db.create_story(empty_story(), epic_id).unwrap();
+
+        db.queue_story(first).unwrap();
+        db.queue_story(second).unwrap();
+        db.queue_story(third).unwrap();
+        db.queue_story(first).unwrap();
+        assert_eq!(db.read_db().unwrap().queue, vec![first, second, third]);
+
+        db.move_queued_story(third, -1).unwrap();
+        assert_eq!(db.read_db().unwrap().queue, vec![first, third, second]);
+        db.move_queued_story(first, 10).unwrap();
+        assert_eq!(db.read_db().unwrap().queue, vec![third, second, first]);
+
+        db.unqueue_story(second).unwrap();
+        db.delete_story(epic_id, first).unwrap();
+        assert_eq!(db.read_db().unwrap().queue, vec![third]);
+    }
+
+    #[test]
+    fn start_next_queued_story_should_pop_and_start_the_top_story() {
+        let db = make_sut();
+        let epic_id = db.create_epic(empty_epic()).unwrap();
+        let story_id = db.create_story(empty_story(), epic_id).unwrap();
+        db.queue_story(story_id).unwrap();
+
+        assert_eq!(db.start_next_queued_story().unwrap(), Some(story_id));
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.queue.is_empty(), true);
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().status,
+            Status::InProgress
+        );
+        assert_eq!(db.start_next_queued_story().unwrap(), None);
+    }
 }
@@ -0,0 +1,171 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::models::DBState;
+
+/// How the stories of an epic can be sequenced given their blockers. Links
+/// to stories of other epics are ignored.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DependencyPlan {
+    /// Stories in an order where every blocker comes first; ties keep the
+    /// epic order.
+    pub order: Vec<u32>,
+    /// The longest chain of stories that have to be done one after another.
+    pub critical_path: Vec<u32>,
+    /// Stories that can't be ordered because they are on a cycle or wait on
+    /// one.
+    pub cyclic: Vec<u32>,
+}
+
+pub fn plan_epic(db_state: &DBState, epic_id: u32) -> Result<DependencyPlan> {
+    let epic = db_state
+        .epics
+        .get(&epic_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+    let blockers: HashMap<u32, Vec<u32>> = epic
+        .stories
+        .iter()
+        .filter_map(|id| {
+            let story = db_state.stories.get(id)?;
+            let blocked_by = story
+                .blocked_by
+                .iter()
+                .copied()
+                .filter(|blocker_id| epic.stories.contains(blocker_id))
+                .collect();
+            Some((*id, blocked_by))
+        })
+        .collect();
+
+    let mut remaining: Vec<u32> = epic
+        .stories
+        .iter()
+        .copied()
+        .filter(|id| blockers.contains_key(id))
+        .collect();
+    let mut order = vec![];
+    while let Some(index) = remaining.iter().position(|id| {
+        blockers[id]
+            .iter()
+            .all(|blocker_id| order.contains(blocker_id))
+    }) {
+        order.push(remaining.remove(index));
+    }
+
+    // longest chain ending at each story, walking the stories in order so
+    // every blocker is already measured
+    let mut chain_length: HashMap<u32, usize> = HashMap::new();
+    let mut previous: HashMap<u32, u32> = HashMap::new();
+    for id in &order {
+        // ties go to the lowest id so the path is stable
+        let longest_blocker = blockers[id]
+            .iter()
+            .max_by_key(|blocker_id| (chain_length[*blocker_id], Reverse(**blocker_id)));
+        match longest_blocker {
+            Some(blocker_id) => {
+                chain_length.insert(*id, chain_length[blocker_id] + 1);
+                previous.insert(*id, *blocker_id);
+            }
+            None => {
+                chain_length.insert(*id, 1);
+            }
+        }
+    }
+    let mut critical_path = vec![];
+    let mut current = order
+        .iter()
+        .rev()
+        .max_by_key(|id| chain_length[*id])
+        .copied();
+    while let Some(id) = current {
+        critical_path.push(id);
+        current = previous.get(&id).copied();
+    }
+    critical_path.reverse();
+
+    Ok(DependencyPlan {
+        order,
+        critical_path,
+        cyclic: remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn plan_epic_should_put_blockers_first() {
+        // 2 <- 3 <- 4, 5 independent
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("design")
+            .with_story("build")
+            .with_story_blocked_by(2)
+            .with_story("release")
+            .with_story_blocked_by(3)
+            .with_story("docs")
+            .build();
+
+        let plan = plan_epic(&state, 1).unwrap();
+
+        assert_eq!(plan.order, vec![2, 3, 4, 5]);
+        assert_eq!(plan.critical_path, vec![2, 3, 4]);
+        assert_eq!(plan.cyclic, Vec::<u32>::new());
+    }
+
+    #[test]
+    fn plan_epic_should_reorder_stories_blocked_by_later_ones() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("release")
+            .with_story_blocked_by(3)
+            .with_story("build")
+            .build();
+
+        let plan = plan_epic(&state, 1).unwrap();
+
+        assert_eq!(plan.order, vec![3, 2]);
+        assert_eq!(plan.critical_path, vec![3, 2]);
+    }
+
+    #[test]
+    fn plan_epic_should_flag_cycles() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("a")
+            .with_story_blocked_by(3)
+            .with_story("b")
+            .with_story_blocked_by(2)
+            .with_story("c")
+            .with_story_blocked_by(2)
+            .with_story("d")
+            .build();
+
+        let plan = plan_epic(&state, 1).unwrap();
+
+        assert_eq!(plan.order, vec![5]);
+        assert_eq!(plan.cyclic, vec![2, 3, 4]);
+        assert_eq!(plan.critical_path, vec![5]);
+    }
+
+    #[test]
+    fn plan_epic_should_ignore_blockers_in_other_epics() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("other")
+            .with_story("elsewhere")
+            .with_epic("epic")
+            .with_story("story")
+            .with_story_blocked_by(2)
+            .build();
+
+        let plan = plan_epic(&state, 3).unwrap();
+
+        assert_eq!(plan.order, vec![4]);
+        assert_eq!(plan.cyclic, Vec::<u32>::new());
+        assert_eq!(plan_epic(&state, 999).is_err(), true);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::{self, Display};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::activity_log::needs_compaction;
+use crate::backup::BackupPolicy;
+use crate::config::Config;
+use crate::integrity::check_database;
+use crate::journal::read_pending_intent;
+use crate::scripting::Scripts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// The outcome of one check, with what to do about it when it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: &'static str,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Ok,
+            check,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        severity: Severity,
+        check: &'static str,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            check,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Ok => "ok",
+            Severity::Warning => "warn",
+            Severity::Error => "fail",
+        };
+        write!(f, "[{}] {}: {}", label, self.check, self.detail)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n       fix: {}", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the config and the workspace's board. `thorough` also parses the
+/// whole board for broken references, which takes a while on large boards.
+pub fn diagnose(config_path: &Path, workspace: Option<&str>, thorough: bool) -> Vec<Finding> {
+    let mut findings = vec![];
+    let config = match Config::load(config_path) {
+        Ok(config) => {
+            findings.push(Finding::ok("config", config_path.display().to_string()));
+            config
+        }
+        Err(error) => {
+            findings.push(Finding::problem(
+                Severity::Error,
+                "config",
+                format!("{:#}", error),
+                format!(
+                    "correct {} or move it away to use the defaults",
+                    config_path.display()
+                ),
+            ));
+            Config::default()
+        }
+    };
+    if let Some(script) = &config.script {
+        if let Err(error) = Scripts::load(Path::new(script)) {
+            findings.push(Finding::problem(
+                Severity::Error,
+                "script",
+                format!("{:#}", error),
+                "correct the script or remove \"script\" from the config",
+            ));
+        }
+    }
+    if let Some(dir) = &config.plugins_dir {
+        if !Path::new(dir).is_dir() {
+            findings.push(Finding::problem(
+                Severity::Warning,
+                "plugins",
+                format!("{} does not exist", dir),
+                format!(
+                    "create it with `mkdir -p {}` or remove \"plugins_dir\"",
+                    dir
+                ),
+            ));
+        }
+    }
+    let database_path = match config.database_path(workspace) {
+        Ok(path) => path,
+        Err(error) => {
+            findings.push(Finding::problem(
+                Severity::Error,
+                "workspace",
+                error.to_string(),
+                "add the workspace to \"workspaces\" in the config",
+            ));
+            return findings;
+        }
+    };
+    findings.extend(check_board(Path::new(&database_path), workspace, thorough));
+    let backups = BackupPolicy::for_database(Path::new(&database_path), workspace);
+    if thorough
+        && needs_compaction(
+            &backups.activity_log_path(),
+            &config.activity_retention,
+            Utc::now(),
+        )
+    {
+        findings.push(Finding::problem(
+            Severity::Warning,
+            "activity log",
+            format!(
+                "{} has entries past \"activity_retention\"",
+                backups.activity_log_path().display()
+            ),
+            format!("compact it with `{}`", backups.command_for("vacuum")),
+        ));
+    }
+    findings
+}
+
+fn check_board(path: &Path, workspace: Option<&str>, thorough: bool) -> Vec<Finding> {
+    let restore = || match latest_backup(path, workspace) {
+        Some(command) => format!("restore the last backup with `{}`", command),
+        None => "restore the file from a copy".to_owned(),
+    };
+    let mut findings = vec![];
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            findings.push(Finding::problem(
+                Severity::Error,
+                "database",
+                format!("can't read {}: {}", path.display(), error),
+                restore(),
+            ));
+            return findings;
+        }
+    };
+    match thorough.then(|| check_database(&content)) {
+        Some(problems) if !problems.is_empty() => findings.push(Finding::problem(
+            Severity::Error,
+            "database",
+            problems
+                .iter()
+                .map(|problem| problem.to_string())
+                .collect::<Vec<_>>()
+                .join("; "),
+            format!("edit those lines in {}, or {}", path.display(), restore()),
+        )),
+        _ => findings.push(Finding::ok("database", path.display().to_string())),
+    }
+    if let Err(error) = OpenOptions::new().append(true).open(path) {
+        findings.push(Finding::problem(
+            Severity::Error,
+            "database",
+            format!("can't write {}: {}", path.display(), error),
+            format!("make it writable, e.g. `chmod u+w {}`", path.display()),
+        ));
+    }
+
+    let journal_path = path.with_extension("journal");
+    match read_pending_intent(&journal_path) {
+        Ok(None) => {}
+        Ok(Some(intent)) => findings.push(Finding::problem(
+            Severity::Warning,
+            "journal",
+            format!(
+                "a write started at {} never finished",
+                intent.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            "start jira_cli, which offers to finish or discard it",
+        )),
+        Err(error) => findings.push(Finding::problem(
+            Severity::Error,
+            "journal",
+            format!("{:#}", error),
+            format!("delete {}", journal_path.display()),
+        )),
+    }
+    findings
+}
+
+/// The command restoring the newest backup of the board, if there is one.
+fn latest_backup(database_path: &Path, workspace: Option<&str>) -> Option<String> {
+    let policy = BackupPolicy::for_database(database_path, workspace);
+    let newest: PathBuf = fs::read_dir(&policy.dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".tar.gz"))
+        // backups start with their timestamp, so names sort by age
+        .max()?;
+    Some(policy.restore_command(&newest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    fn write_workspace(dir: &Path, board: &str) -> PathBuf {
+        let database_path = dir.join("acme.json");
+        fs::write(&database_path, board).unwrap();
+        let config_path = dir.join("config.json");
+        let config = serde_json::json!({
+            "workspaces": { "acme": database_path },
+            "plugins_dir": dir.join("missing"),
+        });
+        fs::write(&config_path, config.to_string()).unwrap();
+        config_path
+    }
+
+    #[test]
+    fn diagnose_should_pass_a_healthy_board() {
+        let dir = tempfile::tempdir().unwrap();
+        let board = DbFixtureBuilder::new().with_epic("epic").build();
+        let config_path = write_workspace(dir.path(), &serde_json::to_string(&board).unwrap());
+
+        let findings = diagnose(&config_path, Some("acme"), true);
+
+        let severities: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.check, finding.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                ("config", Severity::Ok),
+                ("plugins", Severity::Warning),
+                ("database", Severity::Ok),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnose_should_explain_how_to_fix_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let board = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build();
+        let mut broken = serde_json::to_value(&board).unwrap();
+        broken["stories"] = serde_json::json!({});
+        let config_path = write_workspace(dir.path(), &broken.to_string());
+        fs::write(dir.path().join("acme.journal"), "not json").unwrap();
+        fs::create_dir(dir.path().join("backups")).unwrap();
+        fs::write(
+            dir.path()
+                .join("backups/20240305-101500-delete-epic.tar.gz"),
+            "",
+        )
+        .unwrap();
+
+        let findings = diagnose(&config_path, Some("acme"), true);
+        let database = findings
+            .iter()
+            .find(|finding| finding.check == "database")
+            .unwrap();
+        assert_eq!(database.severity, Severity::Error);
+        assert_eq!(
+            database.detail.contains("story 2, which does not exist"),
+            true
+        );
+        assert_eq!(
+            database
+                .fix
+                .as_ref()
+                .unwrap()
+                .contains("jira_cli --workspace acme import-archive"),
+            true
+        );
+        assert_eq!(
+            findings
+                .iter()
+                .any(|finding| finding.check == "journal" && finding.severity == Severity::Error),
+            true
+        );
+
+        let findings = diagnose(&config_path, Some("other"), true);
+        assert_eq!(findings.last().unwrap().check, "workspace");
+        assert_eq!(findings.last().unwrap().severity, Severity::Error);
+    }
+}
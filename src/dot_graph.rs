@@ -0,0 +1,342 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::models::DBState;
+
+/// The dependency graph with each epic as a cluster of its stories and an
+/// edge from every blocker to the story waiting on it.
+pub fn render_dot(db_state: &DBState, config: &Config) -> String {
+    let mut lines = vec!["digraph board {".to_owned(), "    rankdir=LR;".to_owned()];
+    for (epic_id, epic) in db_state.epics.iter().sorted_by_key(|(id, _)| **id) {
+        lines.push(format!("    subgraph cluster_{} {{", epic_id));
+        lines.push(format!(
+            "        label={};",
+            quote(&format!(
+                "{} {}",
+                config.id_format.format(*epic_id),
+                epic.name
+            ))
+        ));
+        for story_id in &epic.stories {
+            let Some(story) = db_state.stories.get(story_id) else {
+                continue;
+            };
+            let key = config.id_format.format(*story_id);
+            lines.push(format!(
+                "        {} [label={}];",
+                quote(&key),
+                quote(&format!("{}\n{}", key, story.name))
+            ));
+        }
+        lines.push("    }".to_owned());
+    }
+    for (story_id, story) in db_state.stories.iter().sorted_by_key(|(id, _)| **id) {
+        for blocker_id in &story.blocked_by {
+            lines.push(format!(
+                "    {} -> {};",
+                quote(&config.id_format.format(*blocker_id)),
+                quote(&config.id_format.format(*story_id))
+            ));
+        }
+    }
+    lines.push("}".to_owned());
+    lines.join("\n") + "\n"
+}
+
+/// Writes the graph as DOT, or as SVG when the path ends in `.svg`, which
+/// needs Graphviz's `dot` on the PATH.
+pub fn export_dot(dao: &JiraDAO, config: &Config, path: &Path) -> Result<()> {
+    let dot = render_dot(&dao.read_db()?, config);
+    if path.extension().is_none_or(|extension| extension != "svg") {
+        std::fs::write(path, dot)?;
+        return Ok(());
+    }
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| anyhow!("could not run Graphviz's dot, export to a .dot file instead"))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("could not write to dot"))?
+        .write_all(dot.as_bytes())?;
+    if !child.wait()?.success() {
+        return Err(anyhow!("dot could not render {}", path.display()));
+    }
+    Ok(())
+}
+
+/// The stories and blocker edges of a DOT file. Only nodes named with a
+/// story id count; everything else, such as epic clusters, is skipped.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DotGraph {
+    pub stories: Vec<u32>,
+    /// `(blocker, blocked story)` pairs.
+    pub edges: Vec<(u32, u32)>,
+}
+
+/// Reads the node and edge statements of a DOT file, e.g. as exported and
+/// then edited in a graph tool. Attribute lists and graph settings are
+/// ignored.
+pub fn parse_dot(content: &str, config: &Config) -> Result<DotGraph> {
+    let mut graph = DotGraph::default();
+    for statement in statements(content)? {
+        // `label=...` and other graph attributes
+        if statement.get(1).is_some_and(|token| token == "=") {
+            continue;
+        }
+        let ids: Vec<Option<u32>> = statement
+            .iter()
+            .filter(|token| *token != "->")
+            .map(|token| config.id_format.parse(token))
+            .collect();
+        for id in ids.iter().flatten() {
+            if !graph.stories.contains(id) {
+                graph.stories.push(*id);
+            }
+        }
+        for pair in ids.windows(2) {
+            if let [Some(blocker_id), Some(story_id)] = pair {
+                graph.edges.push((*blocker_id, *story_id));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Makes the blockers of every story in the graph match its edges, so edges
+/// deleted in the file are removed too. Blockers outside the graph are kept.
+/// Returns how many links were added and removed.
+pub fn import_dot(dao: &JiraDAO, graph: &DotGraph) -> Result<(usize, usize)> {
+    let mut state = dao.read_db()?;
+    // files exported before a merge or renumbering use the old ids
+    let resolve = |id: &u32| state.resolve(*id).unwrap_or(*id);
+    let graph = DotGraph {
+        stories: graph.stories.iter().map(resolve).unique().collect(),
+        edges: graph
+            .edges
+            .iter()
+            .map(|(blocker_id, story_id)| (resolve(blocker_id), resolve(story_id)))
+            .collect(),
+    };
+    for id in &graph.stories {
+        if !state.stories.contains_key(id) {
+            return Err(anyhow!("could not find story {}", id));
+        }
+    }
+    if let Some((id, _)) = graph.edges.iter().find(|(blocker, story)| blocker == story) {
+        return Err(anyhow!("story {} can't block itself", id));
+    }
+    let in_graph: HashSet<u32> = graph.stories.iter().copied().collect();
+    let mut wanted: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (blocker_id, story_id) in &graph.edges {
+        let blockers = wanted.entry(*story_id).or_default();
+        if !blockers.contains(blocker_id) {
+            blockers.push(*blocker_id);
+        }
+    }
+    let (mut added, mut removed) = (0, 0);
+    for story_id in &graph.stories {
+        let story = state
+            .stories
+            .get_mut(story_id)
+            .ok_or_else(|| anyhow!("could not find story {}", story_id))?;
+        let wanted = wanted.remove(story_id).unwrap_or_default();
+        let before = story.blocked_by.len();
+        story
+            .blocked_by
+            .retain(|id| !in_graph.contains(id) || wanted.contains(id));
+        removed += before - story.blocked_by.len();
+        for blocker_id in wanted {
+            if !story.blocked_by.contains(&blocker_id) {
+                story.blocked_by.push(blocker_id);
+                added += 1;
+            }
+        }
+    }
+    dao.replace_db(&state)?;
+    Ok((added, removed))
+}
+
+fn quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Splits DOT into statements of tokens, unquoting strings and dropping
+/// comments, braces, `subgraph` headers and `[...]` attribute lists.
+fn statements(content: &str) -> Result<Vec<Vec<String>>> {
+    let mut statements = vec![];
+    let mut current: Vec<String> = vec![];
+    let mut chars = content.chars().peekable();
+    let mut end_statement = |current: &mut Vec<String>| {
+        if !current.is_empty() {
+            statements.push(std::mem::take(current));
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => text.extend(chars.next()),
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(anyhow!("unterminated string in the DOT file")),
+                    }
+                }
+                current.push(text);
+            }
+            '[' => {
+                let mut in_string = false;
+                for c in chars.by_ref() {
+                    match c {
+                        '"' => in_string = !in_string,
+                        ']' if !in_string => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                end_statement(&mut current);
+            }
+            '#' if current.is_empty() => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                current.push("->".to_owned());
+            }
+            '=' => current.push("=".to_owned()),
+            ';' | '\n' | '{' | '}' => end_statement(&mut current),
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_alphanumeric() || matches!(next, '_' | '.') {
+                        word.push(*next);
+                        chars.next();
+                    } else if *next == '-' {
+                        // `PROJ-2`, but not the `-` of `->`
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if lookahead.peek() == Some(&'>') {
+                            break;
+                        }
+                        word.push('-');
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if !matches!(
+                    word.to_ascii_lowercase().as_str(),
+                    "digraph" | "graph" | "subgraph" | "node" | "edge" | "strict"
+                ) || !current.is_empty()
+                {
+                    current.push(word);
+                }
+            }
+        }
+    }
+    end_statement(&mut current);
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::id_format::IdFormat;
+
+    fn make_config() -> Config {
+        Config {
+            id_format: IdFormat::Prefixed {
+                prefix: "PROJ".to_owned(),
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn render_dot_should_cluster_stories_by_epic() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story_blocked_by(2)
+            .build();
+
+        let dot = render_dot(&state, &make_config());
+
+        assert_eq!(
+            dot.contains("    subgraph cluster_1 {\n        label=\"PROJ-1 Payments\";"),
+            true
+        );
+        assert_eq!(
+            dot.contains("        \"PROJ-2\" [label=\"PROJ-2\\nRefunds\"];"),
+            true
+        );
+        assert_eq!(dot.contains("    \"PROJ-2\" -> \"PROJ-3\";"), true);
+        assert_eq!(parse_dot(&dot, &make_config()).unwrap().edges, vec![(2, 3)]);
+    }
+
+    #[test]
+    fn import_dot_should_sync_the_blockers_of_the_listed_stories() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story_blocked_by(2)
+            .with_story("Disputes");
+        let dao = fixture.build_dao();
+        let graph = parse_dot(
+            r#"
+            digraph edited {
+                // Disputes now blocks Refunds
+                PROJ-2; "PROJ-3" [color=red];
+                PROJ-4 -> PROJ-2 -> 3
+            }
+            "#,
+            &make_config(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.stories, vec![2, 3, 4]);
+        assert_eq!(graph.edges, vec![(4, 2), (2, 3)]);
+        assert_eq!(import_dot(&dao, &graph).unwrap(), (1, 0));
+        assert_eq!(dao.get_story(2).unwrap().blocked_by, vec![4]);
+        assert_eq!(dao.get_story(3).unwrap().blocked_by, vec![2]);
+
+        let graph = parse_dot("digraph { PROJ-2; PROJ-3 }", &make_config()).unwrap();
+        assert_eq!(import_dot(&dao, &graph).unwrap(), (0, 1));
+        assert_eq!(dao.get_story(2).unwrap().blocked_by, vec![4]);
+        assert_eq!(dao.get_story(3).unwrap().blocked_by, Vec::<u32>::new());
+        let graph = parse_dot("digraph { 9 -> 2 }", &make_config()).unwrap();
+        assert_eq!(import_dot(&dao, &graph).is_err(), true);
+    }
+}
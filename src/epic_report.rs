@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+
+use crate::cli::permalink;
+use crate::config::Config;
+use crate::models::DBState;
+
+/// Renders a standalone, printable HTML report of an epic with its stories
+/// and their comments.
+pub fn render_epic_html(db_state: &DBState, epic_id: u32, config: &Config) -> Result<String> {
+    let epic = db_state
+        .epics
+        .get(&epic_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+    let key = config.id_format.format(epic_id);
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>{} {}</title>\n",
+        escape(&key),
+        escape(&epic.name)
+    ));
+    html.push_str(
+        "<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table { border-collapse: collapse; width: 100%; }\n\
+         th, td { border: 1px solid #999; padding: 4px 8px; text-align: left; vertical-align: top; }\n\
+         .comment { color: #555; font-size: 0.9em; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    html.push_str(&format!(
+        "<h1>{} {}</h1>\n<p><strong>Status:</strong> {}</p>\n<p><code>{}</code></p>\n<p>{}</p>\n",
+        escape(&key),
+        escape(&epic.name),
+        epic.status,
+        escape(&permalink(&key)),
+        escape(&epic.description)
+    ));
+
+    html.push_str("<h2>Stories</h2>\n");
+    if epic.stories.is_empty() {
+        html.push_str("<p>No stories.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<tr><th>id</th><th>name</th><th>status</th><th>description</th><th>open with</th></tr>\n",
+        );
+        for story_id in &epic.stories {
+            let story = match db_state.stories.get(story_id) {
+                Some(story) => story,
+                None => continue,
+            };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}",
+                escape(&config.id_format.format(*story_id)),
+                escape(&story.name),
+                story.status,
+                escape(&story.description)
+            ));
+            for comment in &story.comments {
+                html.push_str(&format!(
+                    "<p class=\"comment\">{}: {}</p>",
+                    escape(&config.time_display.format(&comment.created_at)),
+                    escape(&comment.text)
+                ));
+            }
+            html.push_str(&format!(
+                "</td><td><code>{}</code></td></tr>\n",
+                escape(&permalink(&config.id_format.format(*story_id)))
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::models::Status;
+
+    #[test]
+    fn render_epic_html_should_include_stories_and_comments() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments <v2>")
+            .with_story("Refunds")
+            .with_story_status(Status::Closed)
+            .with_story_comment("shipped & verified");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let html = render_epic_html(&fixture.build(), epic_id, &Config::default()).unwrap();
+
+        assert_eq!(html.contains("<h1>1 Payments &lt;v2&gt;</h1>"), true);
+        assert_eq!(
+            html.contains("<td>2</td><td>Refunds</td><td>CLOSED</td>"),
+            true
+        );
+        assert_eq!(html.contains("shipped &amp; verified"), true);
+        assert_eq!(html.contains("<code>jira_cli open 2</code>"), true);
+    }
+
+    #[test]
+    fn render_epic_html_should_fail_for_unknown_epic() {
+        let state = DbFixtureBuilder::new().build();
+        assert_eq!(
+            render_epic_html(&state, 999, &Config::default()).is_err(),
+            true
+        );
+    }
+}
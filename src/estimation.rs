@@ -0,0 +1,142 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+const FIBONACCI: &[u32] = &[0, 1, 2, 3, 5, 8, 13, 21];
+const POWERS_OF_TWO: &[u32] = &[0, 1, 2, 4, 8, 16, 32];
+const T_SHIRT: &[(&str, u32)] = &[
+    ("XS", 1),
+    ("S", 2),
+    ("M", 3),
+    ("L", 5),
+    ("XL", 8),
+    ("XXL", 13),
+];
+
+/// The story point values a team estimates with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointScale {
+    #[default]
+    Fibonacci,
+    PowersOfTwo,
+    /// Sizes from XS to XXL, stored as 1, 2, 3, 5, 8 and 13 points.
+    TShirt,
+}
+
+impl PointScale {
+    pub fn values(&self) -> Vec<u32> {
+        match self {
+            Self::Fibonacci => FIBONACCI.to_vec(),
+            Self::PowersOfTwo => POWERS_OF_TWO.to_vec(),
+            Self::TShirt => T_SHIRT.iter().map(|(_, points)| *points).collect(),
+        }
+    }
+
+    /// Accepts a value on the scale, or a size on the t-shirt scale. Values
+    /// off the scale are rejected with the nearest ones around them.
+    pub fn parse(&self, input: &str) -> Result<u32> {
+        let input = input.trim();
+        if *self == Self::TShirt {
+            if let Some((_, points)) = T_SHIRT
+                .iter()
+                .find(|(size, _)| size.eq_ignore_ascii_case(input))
+            {
+                return Ok(*points);
+            }
+        }
+        let points: u32 = input
+            .parse()
+            .map_err(|_| anyhow!("'{}' is not an estimate, pick one of {}", input, self))?;
+        let values = self.values();
+        if values.contains(&points) {
+            return Ok(points);
+        }
+        let below = values.iter().rev().find(|value| **value < points);
+        let above = values.iter().find(|value| **value > points);
+        let suggestions = below
+            .into_iter()
+            .chain(above)
+            .map(|value| self.label(*value))
+            .join(" or ");
+        Err(anyhow!(
+            "{} is not on the {} scale, try {}",
+            points,
+            self.name(),
+            suggestions
+        ))
+    }
+
+    /// `5`, or `L` on the t-shirt scale.
+    pub fn label(&self, points: u32) -> String {
+        match self {
+            Self::TShirt => T_SHIRT
+                .iter()
+                .find(|(_, value)| *value == points)
+                .map(|(size, _)| (*size).to_owned())
+                .unwrap_or_else(|| points.to_string()),
+            _ => points.to_string(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Fibonacci => "fibonacci",
+            Self::PowersOfTwo => "powers of two",
+            Self::TShirt => "t-shirt",
+        }
+    }
+}
+
+/// The values to pick from, e.g. `0, 1, 2, 3, 5, 8, 13, 21`.
+impl Display for PointScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TShirt => write!(
+                f,
+                "{}",
+                T_SHIRT
+                    .iter()
+                    .map(|(size, points)| format!("{} ({})", size, points))
+                    .join(", ")
+            ),
+            _ => write!(f, "{}", self.values().iter().join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_accept_values_on_the_scale() {
+        assert_eq!(PointScale::Fibonacci.parse("8").unwrap(), 8);
+        assert_eq!(PointScale::PowersOfTwo.parse(" 16 ").unwrap(), 16);
+        assert_eq!(PointScale::TShirt.parse("xl").unwrap(), 8);
+        assert_eq!(PointScale::TShirt.parse("3").unwrap(), 3);
+        assert_eq!(PointScale::Fibonacci.parse("M").is_err(), true);
+    }
+
+    #[test]
+    fn parse_should_suggest_the_nearest_values() {
+        assert_eq!(
+            PointScale::Fibonacci.parse("4").unwrap_err().to_string(),
+            "4 is not on the fibonacci scale, try 3 or 5"
+        );
+        assert_eq!(
+            PointScale::PowersOfTwo.parse("40").unwrap_err().to_string(),
+            "40 is not on the powers of two scale, try 32"
+        );
+        assert_eq!(
+            PointScale::TShirt.parse("4").unwrap_err().to_string(),
+            "4 is not on the t-shirt scale, try M or L"
+        );
+        assert_eq!(
+            PointScale::TShirt.to_string().starts_with("XS (1), S (2)"),
+            true
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// How the terminal draws attention to the outcome of an input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    #[default]
+    None,
+    /// Rings the terminal bell.
+    Bell,
+    /// Briefly switches the terminal to inverse video.
+    Flash,
+    /// Shows the message in a colored line above the next page.
+    Banner,
+}
+
+/// Signals for errors (including inputs a page ignored) and for actions that
+/// confirm what they did. Both are off by default.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    pub on_error: Signal,
+    pub on_success: Signal,
+}
+
+/// A signal the UI loop should give, with the message that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feedback {
+    pub signal: Signal,
+    pub is_error: bool,
+    pub message: String,
+}
+
+impl FeedbackConfig {
+    /// Pairs the message with the configured signal, or `None` when that
+    /// kind of outcome is configured to stay quiet.
+    pub fn feedback(&self, is_error: bool, message: String) -> Option<Feedback> {
+        let signal = if is_error {
+            self.on_error
+        } else {
+            self.on_success
+        };
+        if signal == Signal::None {
+            return None;
+        }
+        Some(Feedback {
+            signal,
+            is_error,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feedback_should_follow_the_configured_signals() {
+        let config: FeedbackConfig = serde_json::from_str(r#"{ "on_error": "bell" }"#).unwrap();
+
+        assert_eq!(
+            config.feedback(true, "failed".to_owned()),
+            Some(Feedback {
+                signal: Signal::Bell,
+                is_error: true,
+                message: "failed".to_owned()
+            })
+        );
+        assert_eq!(config.feedback(false, "saved".to_owned()), None);
+    }
+}
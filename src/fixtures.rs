@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+
+use crate::dao::{test_utils::MockDB, JiraDAO};
+use crate::models::{Comment, DBState, Epic, ExternalBlocker, Status, Story};
+
+/// Fluent builder for database states used by tests. Ids are handed out in
+/// the same order the DAO would assign them: each epic, then its stories.
+///
+/// ```ignore
+/// let dao = DbFixtureBuilder::new()
+///     .with_epic("Payments")
+///     .with_epic_status(Status::InProgress)
+///     .with_story("Refunds")
+///     .with_story_status(Status::Closed)
+///     .build_dao();
+/// ```
+pub struct DbFixtureBuilder {
+    state: DBState,
+    current_epic_id: Option<u32>,
+    current_story_id: Option<u32>,
+}
+
+impl Default for DbFixtureBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbFixtureBuilder {
+    pub fn new() -> Self {
+        Self {
+            state: DBState {
+                last_item_id: 0,
+                epics: HashMap::new(),
+                stories: HashMap::new(),
+                queue: vec![],
+                aliases: Default::default(),
+            },
+            current_epic_id: None,
+            current_story_id: None,
+        }
+    }
+
+    pub fn with_epic(mut self, name: &str) -> Self {
+        let epic_id = self.next_id();
+        self.state
+            .epics
+            .insert(epic_id, Epic::new(name.to_owned(), "".to_owned()));
+        self.current_epic_id = Some(epic_id);
+        self.current_story_id = None;
+        self
+    }
+
+    pub fn with_epic_status(mut self, status: Status) -> Self {
+        self.current_epic().status = status;
+        self
+    }
+
+    /// Adds a story to the most recently added epic.
+    pub fn with_story(mut self, name: &str) -> Self {
+        let story_id = self.next_id();
+        self.current_epic().stories.push(story_id);
+        self.state
+            .stories
+            .insert(story_id, Story::new(name.to_owned(), "".to_owned()));
+        self.current_story_id = Some(story_id);
+        self
+    }
+
+    pub fn with_story_status(mut self, status: Status) -> Self {
+        self.current_story().status = status;
+        self
+    }
+
+    pub fn with_story_created_at(mut self, created_at: Option<DateTime<Utc>>) -> Self {
+        self.current_story().created_at = created_at;
+        self
+    }
+
+    pub fn with_story_comment(mut self, text: &str) -> Self {
+        self.current_story()
+            .comments
+            .push(Comment::new(text.to_owned()));
+        self
+    }
+
+    /// Marks the most recently added story as blocked by another one.
+    pub fn with_story_blocked_by(mut self, blocker_id: u32) -> Self {
+        self.current_story().blocked_by.push(blocker_id);
+        self
+    }
+
+    pub fn with_story_external_blocker(mut self, url: &str, title: &str) -> Self {
+        self.current_story()
+            .external_blockers
+            .push(ExternalBlocker {
+                url: url.to_owned(),
+                title: title.to_owned(),
+            });
+        self
+    }
+
+    pub fn last_epic_id(&self) -> Option<u32> {
+        self.current_epic_id
+    }
+
+    pub fn last_story_id(&self) -> Option<u32> {
+        self.current_story_id
+    }
+
+    pub fn build(self) -> DBState {
+        self.state
+    }
+
+    /// Builds an in-memory DAO; every call gets its own database, so tests
+    /// using it can run in parallel.
+    pub fn build_dao(self) -> Rc<JiraDAO> {
+        Rc::new(JiraDAO::new(Box::new(MockDB::with_state(self.state))))
+    }
+
+    fn next_id(&mut self) -> u32 {
+        self.state.last_item_id += 1;
+        self.state.last_item_id
+    }
+
+    fn current_epic(&mut self) -> &mut Epic {
+        let epic_id = self
+            .current_epic_id
+            .expect("with_epic must be called before configuring an epic");
+        self.state.epics.get_mut(&epic_id).unwrap()
+    }
+
+    fn current_story(&mut self) -> &mut Story {
+        let story_id = self
+            .current_story_id
+            .expect("with_story must be called before configuring a story");
+        self.state.stories.get_mut(&story_id).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_should_assign_ids_like_the_dao() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic 1")
+            .with_story("story 1")
+            .with_story("story 2")
+            .with_epic("epic 2")
+            .with_story("story 3")
+            .build();
+
+        assert_eq!(state.last_item_id, 5);
+        assert_eq!(state.epics.get(&1).unwrap().stories, vec![2, 3]);
+        assert_eq!(state.epics.get(&4).unwrap().stories, vec![5]);
+        assert_eq!(state.stories.get(&5).unwrap().name, "story 3");
+    }
+
+    #[test]
+    fn build_should_apply_statuses_and_comments() {
+        let builder = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_epic_status(Status::InProgress)
+            .with_story("story")
+            .with_story_status(Status::Resolved)
+            .with_story_comment("looks good");
+        let (epic_id, story_id) = (builder.last_epic_id(), builder.last_story_id());
+        let dao = builder.build_dao();
+
+        let state = dao.read_db().unwrap();
+        let epic = state.epics.get(&epic_id.unwrap()).unwrap();
+        let story = state.stories.get(&story_id.unwrap()).unwrap();
+        assert_eq!(epic.status, Status::InProgress);
+        assert_eq!(story.status, Status::Resolved);
+        assert_eq!(story.comments[0].text, "looks good");
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_story_should_panic_without_an_epic() {
+        DbFixtureBuilder::new().with_story("orphan");
+    }
+}
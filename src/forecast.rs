@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use itertools::Itertools;
+
+use crate::grooming::{day_seed, next_random};
+use crate::models::DBState;
+
+/// Weeks of finished stories the simulation samples from.
+pub const HISTORY_WEEKS: i64 = 12;
+/// Simulated futures per forecast.
+pub const FORECAST_RUNS: usize = 1000;
+/// Runs still going after this long count as never finishing.
+const MAX_WEEKS: usize = 520;
+
+/// When an epic's remaining stories are likely to be done, as the dates by
+/// which 50, 85 and 95 percent of the simulated runs had finished them.
+/// `None` is further out than ten years.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpicForecast {
+    pub epic_id: u32,
+    pub remaining: usize,
+    pub p50: Option<NaiveDate>,
+    pub p85: Option<NaiveDate>,
+    pub p95: Option<NaiveDate>,
+}
+
+/// How many stories were resolved or closed in each of the last weeks,
+/// oldest first. Weeks before the first recorded status change are left
+/// out, since nothing could have been counted in them.
+pub fn weekly_throughput(state: &DBState, now: DateTime<Utc>) -> Vec<usize> {
+    let finished: Vec<DateTime<Utc>> = state
+        .stories
+        .values()
+        .flat_map(|story| &story.status_changes)
+        .filter(|change| change.to.is_done() && !change.from.is_done())
+        .map(|change| change.at)
+        .collect();
+    let Some(first_change) = state
+        .stories
+        .values()
+        .flat_map(|story| &story.status_changes)
+        .map(|change| change.at)
+        .min()
+    else {
+        return vec![];
+    };
+    let tracked_weeks = ((now - first_change).num_weeks() + 1).min(HISTORY_WEEKS);
+    (0..tracked_weeks)
+        .rev()
+        .map(|weeks_ago| {
+            let end = now - Duration::weeks(weeks_ago);
+            let start = end - Duration::weeks(1);
+            finished
+                .iter()
+                .filter(|at| **at > start && **at <= end)
+                .count()
+        })
+        .collect()
+}
+
+/// The stories left to do, in the order they'd be picked up: the "next up"
+/// queue first, then every epic's stories by epic id. Each entry is the
+/// epic the story belongs to.
+fn backlog(state: &DBState) -> Vec<u32> {
+    let epic_of: HashMap<u32, u32> = state
+        .epics
+        .iter()
+        .flat_map(|(epic_id, epic)| {
+            epic.stories
+                .iter()
+                .map(move |story_id| (*story_id, *epic_id))
+        })
+        .collect();
+    let mut seen = HashSet::new();
+    state
+        .queue
+        .iter()
+        .chain(
+            state
+                .epics
+                .iter()
+                .sorted_by_key(|(id, _)| **id)
+                .flat_map(|(_, epic)| &epic.stories),
+        )
+        .filter(|story_id| seen.insert(**story_id))
+        .filter(|story_id| {
+            state
+                .stories
+                .get(story_id)
+                .is_some_and(|story| !story.status.is_done())
+        })
+        .filter_map(|story_id| epic_of.get(story_id).copied())
+        .collect()
+}
+
+/// Plays the backlog out week by week, each week finishing as many stories
+/// as a week picked at random from `throughput`. The runs are seeded by the
+/// day, so the forecast only moves when the board does or a day passes.
+/// Epics with nothing left to do are skipped.
+pub fn forecast_epics(
+    state: &DBState,
+    throughput: &[usize],
+    now: DateTime<Utc>,
+) -> Vec<EpicForecast> {
+    let backlog = backlog(state);
+    let remaining = backlog.iter().copied().counts();
+    if throughput.iter().all(|count| *count == 0) {
+        return vec![];
+    }
+
+    let mut finished_in: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut seed = day_seed(now.date_naive());
+    for _ in 0..FORECAST_RUNS {
+        let mut left = remaining.clone();
+        let mut position = 0;
+        let mut week = 0;
+        while position < backlog.len() && week < MAX_WEEKS {
+            week += 1;
+            seed = next_random(seed);
+            let done = throughput[(seed % throughput.len() as u64) as usize];
+            for epic_id in backlog.iter().skip(position).take(done) {
+                let count = left.entry(*epic_id).or_default();
+                *count -= 1;
+                if *count == 0 {
+                    finished_in.entry(*epic_id).or_default().push(week);
+                }
+            }
+            position += done;
+        }
+    }
+
+    let date_at = |weeks: &[usize], share: f64| {
+        // runs that never finished are missing from the end
+        let index = (FORECAST_RUNS as f64 * share).ceil() as usize - 1;
+        weeks
+            .get(index)
+            .map(|week| (now + Duration::weeks(*week as i64)).date_naive())
+    };
+    remaining
+        .into_iter()
+        .sorted()
+        .map(|(epic_id, remaining)| {
+            let mut weeks = finished_in.remove(&epic_id).unwrap_or_default();
+            weeks.sort_unstable();
+            EpicForecast {
+                epic_id,
+                remaining,
+                p50: date_at(&weeks, 0.5),
+                p85: date_at(&weeks, 0.85),
+                p95: date_at(&weeks, 0.95),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::models::{Status, StatusChange};
+
+    fn finish(state: &mut DBState, story_id: u32, at: DateTime<Utc>) {
+        let story = state.stories.get_mut(&story_id).unwrap();
+        story.status = Status::Closed;
+        story.status_changes.push(StatusChange {
+            from: Status::Open,
+            to: Status::Closed,
+            at,
+        });
+    }
+
+    #[test]
+    fn weekly_throughput_should_count_finished_stories_since_tracking_began() {
+        let now = Utc::now();
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story("Disputes")
+            .build();
+        finish(&mut state, 2, now - Duration::days(20));
+        finish(&mut state, 3, now - Duration::days(1));
+        finish(&mut state, 4, now - Duration::days(2));
+
+        assert_eq!(weekly_throughput(&state, now), vec![1, 0, 2]);
+        assert_eq!(
+            weekly_throughput(&DbFixtureBuilder::new().build(), now),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn forecast_epics_should_finish_queued_work_first() {
+        let now = Utc::now();
+        let mut builder = DbFixtureBuilder::new().with_epic("Payments");
+        for index in 0..4 {
+            builder = builder.with_story(&format!("payment {}", index));
+        }
+        builder = builder.with_epic("Search").with_story("Facets");
+        let mut state = builder.build();
+        state.queue = vec![7];
+
+        let forecasts = forecast_epics(&state, &[2], now);
+
+        let weeks_out = |weeks: i64| Some((now + Duration::weeks(weeks)).date_naive());
+        assert_eq!(
+            forecasts,
+            vec![
+                EpicForecast {
+                    epic_id: 1,
+                    remaining: 4,
+                    p50: weeks_out(3),
+                    p85: weeks_out(3),
+                    p95: weeks_out(3),
+                },
+                EpicForecast {
+                    epic_id: 6,
+                    remaining: 1,
+                    p50: weeks_out(1),
+                    p85: weeks_out(1),
+                    p95: weeks_out(1),
+                },
+            ]
+        );
+        assert_eq!(forecast_epics(&state, &[0, 0], now), vec![]);
+    }
+
+    #[test]
+    fn forecast_epics_should_spread_dates_with_uneven_weeks() {
+        let now = Utc::now();
+        let mut builder = DbFixtureBuilder::new().with_epic("Payments");
+        for index in 0..6 {
+            builder = builder.with_story(&format!("payment {}", index));
+        }
+        let state = builder.build();
+
+        let forecast = &forecast_epics(&state, &[0, 1, 3], now)[0];
+
+        assert_eq!(forecast.p50 <= forecast.p85, true);
+        assert_eq!(forecast.p85 <= forecast.p95, true);
+        assert_eq!(forecast.p50 > Some(now.date_naive()), true);
+    }
+}
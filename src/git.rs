@@ -0,0 +1,221 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+const HOOK_MARKER: &str = "# installed by jira_cli";
+
+const COMMIT_HOOK: &str = r#"#!/bin/sh
+# installed by jira_cli: prefixes commit messages with the active story key
+key_file="$(git rev-parse --git-path jira_cli_story)"
+[ -f "$key_file" ] || exit 0
+key="$(cat "$key_file")"
+[ -n "$key" ] || exit 0
+case "$2" in merge|squash) exit 0 ;; esac
+head -n 1 "$1" | grep -qF "[$key]" && exit 0
+tmp="$1.jira_cli"
+{ printf '[%s] ' "$key"; cat "$1"; } > "$tmp" && mv "$tmp" "$1"
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub hash: String,
+    pub subject: String,
+}
+
+/// Branch name for a story, e.g. `story/12-fix-login`.
+pub fn branch_name(key: &str, title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let key = key.to_ascii_lowercase();
+    if slug.is_empty() {
+        format!("story/{}", key)
+    } else {
+        format!("story/{}-{}", key, slug)
+    }
+}
+
+pub fn create_branch(repo_path: &Path, name: &str) -> Result<()> {
+    run_git(repo_path, &["checkout", "-b", name])?;
+    Ok(())
+}
+
+/// Commits on the current branch whose subject mentions the story key as a
+/// whole word, newest first.
+pub fn commits_mentioning(repo_path: &Path, key: &str) -> Result<Vec<CommitSummary>> {
+    let log = run_git(repo_path, &["log", "--format=%h%x09%s"])?;
+    Ok(log
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(_, subject)| mentions_key(subject, key))
+        .map(|(hash, subject)| CommitSummary {
+            hash: hash.to_owned(),
+            subject: subject.to_owned(),
+        })
+        .collect())
+}
+
+/// Installs a prepare-commit-msg hook that prefixes messages with the key
+/// saved by [`set_active_story`]. A hook not written by jira_cli is never
+/// overwritten.
+pub fn install_commit_hook(repo_path: &Path) -> Result<PathBuf> {
+    let hook_path = git_path(repo_path, "hooks/prepare-commit-msg")?;
+    if hook_path.exists() && !fs::read_to_string(&hook_path)?.contains(HOOK_MARKER) {
+        return Err(anyhow!(
+            "{} already exists and was not installed by jira_cli",
+            hook_path.display()
+        ));
+    }
+    if let Some(hooks_dir) = hook_path.parent() {
+        fs::create_dir_all(hooks_dir)?;
+    }
+    fs::write(&hook_path, COMMIT_HOOK)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(hook_path)
+}
+
+pub fn set_active_story(repo_path: &Path, key: &str) -> Result<()> {
+    fs::write(git_path(repo_path, "jira_cli_story")?, key)?;
+    Ok(())
+}
+
+fn git_path(repo_path: &Path, name: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(run_git(repo_path, &["rev-parse", "--git-path", name])?.trim());
+    Ok(repo_path.join(path))
+}
+
+fn mentions_key(text: &str, key: &str) -> bool {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '-';
+    text.split(|c: char| !is_word_char(c))
+        .any(|word| word.eq_ignore_ascii_case(key))
+}
+
+fn run_git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .with_context(|| anyhow!("failed to run git"))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert_eq!(status.success(), true);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn commit_hook_should_prefix_messages_with_the_active_story() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        install_commit_hook(dir.path()).unwrap();
+        set_active_story(dir.path(), "PROJ-12").unwrap();
+
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "fix login"],
+        );
+        git(
+            dir.path(),
+            &[
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "[PROJ-12] already tagged",
+            ],
+        );
+
+        let log = run_git(dir.path(), &["log", "--format=%s"]).unwrap();
+        assert_eq!(log, "[PROJ-12] already tagged\n[PROJ-12] fix login\n");
+    }
+
+    #[test]
+    fn install_commit_hook_should_keep_foreign_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let hook_path = dir.path().join(".git/hooks/prepare-commit-msg");
+        fs::write(&hook_path, "#!/bin/sh\nexit 0\n").unwrap();
+
+        assert_eq!(install_commit_hook(dir.path()).is_err(), true);
+        assert_eq!(
+            fs::read_to_string(&hook_path).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+    }
+
+    #[test]
+    fn branch_name_should_slugify_the_title() {
+        assert_eq!(branch_name("12", "Fix login!"), "story/12-fix-login");
+        assert_eq!(
+            branch_name("PROJ-7", "  Add  CSV export "),
+            "story/proj-7-add-csv-export"
+        );
+        assert_eq!(branch_name("3", "???"), "story/3");
+    }
+
+    #[test]
+    fn mentions_key_should_match_whole_words_only() {
+        assert_eq!(mentions_key("PROJ-12: fix login", "proj-12"), true);
+        assert_eq!(mentions_key("fix login (#12)", "12"), true);
+        assert_eq!(mentions_key("bump to 112", "12"), false);
+        assert_eq!(mentions_key("PROJ-123 refunds", "PROJ-12"), false);
+    }
+
+    #[test]
+    fn commits_mentioning_should_read_the_repository_log() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "12 fix login"],
+        );
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "unrelated"],
+        );
+
+        let commits = commits_mentioning(dir.path(), "12").unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].subject, "12 fix login");
+
+        create_branch(dir.path(), "story/12-fix-login").unwrap();
+        assert_eq!(
+            run_git(dir.path(), &["branch", "--show-current"])
+                .unwrap()
+                .trim(),
+            "story/12-fix-login"
+        );
+    }
+}
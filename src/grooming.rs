@@ -0,0 +1,97 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::models::{DBState, Status};
+use crate::watchlist::last_activity;
+
+/// Open stories that weren't created, moved or commented on in this long
+/// are due for a look.
+pub const STALE_AFTER_DAYS: i64 = 14;
+
+/// Picks up to `count` stale open stories for today's grooming. The pick is
+/// random but stays the same for the whole day, so leaving and coming back
+/// shows the same stories.
+pub fn pick_for_grooming(db_state: &DBState, count: usize, now: DateTime<Utc>) -> Vec<u32> {
+    let stale_before = now - Duration::days(STALE_AFTER_DAYS);
+    let mut candidates: Vec<u32> = db_state
+        .stories
+        .iter()
+        .filter(|(_, story)| story.status == Status::Open)
+        .filter(|(_, story)| last_activity(story).is_none_or(|at| at < stale_before))
+        .map(|(id, _)| *id)
+        .collect();
+    candidates.sort_unstable();
+
+    let mut seed = day_seed(now.date_naive());
+    // Fisher-Yates, stopping once the first `count` slots are settled
+    let picked = count.min(candidates.len());
+    for index in 0..picked {
+        seed = next_random(seed);
+        let swap_with = index + (seed % (candidates.len() - index) as u64) as usize;
+        candidates.swap(index, swap_with);
+    }
+    candidates.truncate(picked);
+    candidates
+}
+
+pub(crate) fn day_seed(day: NaiveDate) -> u64 {
+    day.signed_duration_since(NaiveDate::default()).num_days() as u64
+}
+
+/// splitmix64, enough to spread consecutive day numbers apart.
+pub(crate) fn next_random(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use chrono::TimeZone;
+
+    fn noon(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn pick_for_grooming_should_skip_done_and_recently_active_stories() {
+        let month_ago = Some(Utc::now() - Duration::days(30));
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("old")
+            .with_story_created_at(month_ago)
+            .with_story("closed")
+            .with_story_created_at(month_ago)
+            .with_story_status(Status::Closed)
+            .with_story("discussed")
+            .with_story_created_at(month_ago)
+            .with_story_comment("today")
+            .with_story("new")
+            .build();
+
+        let picked = pick_for_grooming(&state, 5, Utc::now());
+
+        assert_eq!(picked, vec![2]);
+    }
+
+    #[test]
+    fn pick_for_grooming_should_be_stable_within_a_day() {
+        let mut builder = DbFixtureBuilder::new().with_epic("epic");
+        for index in 0..20 {
+            builder = builder
+                .with_story(&format!("story {}", index))
+                .with_story_created_at(None);
+        }
+        let state = builder.build();
+
+        let morning = pick_for_grooming(&state, 3, noon(10) - Duration::hours(3));
+        let evening = pick_for_grooming(&state, 3, noon(10) + Duration::hours(3));
+        let next_day = pick_for_grooming(&state, 3, noon(11));
+
+        assert_eq!(morning.len(), 3);
+        assert_eq!(morning, evening);
+        assert_ne!(morning, next_day);
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// How item ids are shown in tables and accepted as input.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "style", rename_all = "snake_case")]
+pub enum IdFormat {
+    #[default]
+    Plain,
+    ZeroPadded {
+        width: usize,
+    },
+    Prefixed {
+        prefix: String,
+    },
+}
+
+impl IdFormat {
+    pub fn format(&self, id: u32) -> String {
+        match self {
+            Self::Plain => id.to_string(),
+            Self::ZeroPadded { width } => format!("{:0width$}", id, width = width),
+            Self::Prefixed { prefix } => format!("{}-{}", prefix, id),
+        }
+    }
+
+    /// Accepts the formatted id as well as the bare number, so users can
+    /// always fall back to typing the plain id.
+    pub fn parse(&self, input: &str) -> Option<u32> {
+        if let Self::Prefixed { prefix } = self {
+            let prefix_len = prefix.len() + 1;
+            if input.len() > prefix_len
+                && input.is_char_boundary(prefix_len)
+                && input[..prefix_len].eq_ignore_ascii_case(&format!("{}-", prefix))
+            {
+                return input[prefix_len..].parse::<u32>().ok();
+            }
+        }
+        input.parse::<u32>().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_should_render_each_style() {
+        assert_eq!(IdFormat::Plain.format(42), "42");
+        assert_eq!(IdFormat::ZeroPadded { width: 4 }.format(42), "0042");
+        assert_eq!(IdFormat::ZeroPadded { width: 1 }.format(42), "42");
+        let prefixed = IdFormat::Prefixed {
+            prefix: "PROJ".to_owned(),
+        };
+        assert_eq!(prefixed.format(42), "PROJ-42");
+    }
+
+    #[test]
+    fn parse_should_accept_formatted_and_plain_ids() {
+        assert_eq!(IdFormat::Plain.parse("42"), Some(42));
+        assert_eq!(IdFormat::ZeroPadded { width: 4 }.parse("0042"), Some(42));
+
+        let prefixed = IdFormat::Prefixed {
+            prefix: "PROJ".to_owned(),
+        };
+        assert_eq!(prefixed.parse("PROJ-42"), Some(42));
+        assert_eq!(prefixed.parse("proj-42"), Some(42));
+        assert_eq!(prefixed.parse("42"), Some(42));
+        assert_eq!(prefixed.parse("PROJ-"), None);
+        assert_eq!(prefixed.parse("OTHER-42"), None);
+        assert_eq!(prefixed.parse("q983f2j"), None);
+    }
+}
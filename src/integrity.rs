@@ -0,0 +1,313 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+
+use crate::models::{Epic, Story};
+
+/// Something wrong with a database file, with the line it was found on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// The database as written, keeping ids that appear more than once, which
+/// deserializing straight into a `DBState` would silently merge.
+#[derive(Deserialize)]
+struct RawState {
+    last_item_id: u32,
+    epics: Entries<Epic>,
+    stories: Entries<Story>,
+    #[serde(default)]
+    queue: Vec<u32>,
+}
+
+struct Entries<T>(Vec<(u32, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Entries<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for EntriesVisitor<T> {
+            type Value = Entries<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of ids to items")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = vec![];
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+/// Checks a serialized database for duplicate ids and for references to
+/// items that don't exist. Returns every problem found, in file order for
+/// each kind of check.
+pub fn check_database(content: &str) -> Vec<Problem> {
+    let state: RawState = match serde_json::from_str(content) {
+        Ok(state) => state,
+        Err(error) => {
+            let message = error.to_string();
+            let message = match message.rfind(" at line ") {
+                Some(end) => message[..end].to_owned(),
+                None => message,
+            };
+            return vec![Problem {
+                line: error.line(),
+                message,
+            }];
+        }
+    };
+    let locate = Locator { content };
+    let mut problems = vec![];
+
+    let mut seen_epics = HashSet::new();
+    for (epic_id, _) in &state.epics.0 {
+        if !seen_epics.insert(*epic_id) {
+            problems.push(Problem {
+                line: locate.entry("epics", *epic_id, 2),
+                message: format!("epic id {} is used more than once", epic_id),
+            });
+        }
+    }
+    let mut seen_stories = HashSet::new();
+    for (story_id, _) in &state.stories.0 {
+        if !seen_stories.insert(*story_id) {
+            problems.push(Problem {
+                line: locate.entry("stories", *story_id, 2),
+                message: format!("story id {} is used more than once", story_id),
+            });
+        } else if seen_epics.contains(story_id) {
+            problems.push(Problem {
+                line: locate.entry("stories", *story_id, 1),
+                message: format!("id {} is used by both an epic and a story", story_id),
+            });
+        }
+    }
+
+    let mut owners: HashMap<u32, u32> = HashMap::new();
+    let mut checked = HashSet::new();
+    for (epic_id, epic) in &state.epics.0 {
+        // a duplicate has been reported already, look at the first one only
+        if !checked.insert(*epic_id) {
+            continue;
+        }
+        let line = locate.entry("epics", *epic_id, 1);
+        for story_id in &epic.stories {
+            if !seen_stories.contains(story_id) {
+                problems.push(Problem {
+                    line,
+                    message: format!(
+                        "epic {} lists story {}, which does not exist",
+                        epic_id, story_id
+                    ),
+                });
+            } else if let Some(owner) = owners.insert(*story_id, *epic_id) {
+                problems.push(Problem {
+                    line,
+                    message: format!(
+                        "story {} is listed by both epic {} and epic {}",
+                        story_id, owner, epic_id
+                    ),
+                });
+            }
+        }
+        if let Some(source_id) = epic.split_from.filter(|id| !seen_epics.contains(id)) {
+            problems.push(Problem {
+                line,
+                message: format!(
+                    "epic {} was split from epic {}, which does not exist",
+                    epic_id, source_id
+                ),
+            });
+        }
+        for split_id in epic.split_into.iter().filter(|id| !seen_epics.contains(id)) {
+            problems.push(Problem {
+                line,
+                message: format!(
+                    "epic {} was split into epic {}, which does not exist",
+                    epic_id, split_id
+                ),
+            });
+        }
+        for story_id in epic.mirrors.iter().filter(|id| !seen_stories.contains(id)) {
+            problems.push(Problem {
+                line,
+                message: format!(
+                    "epic {} mirrors story {}, which does not exist",
+                    epic_id, story_id
+                ),
+            });
+        }
+    }
+
+    let mut checked = HashSet::new();
+    for (story_id, story) in &state.stories.0 {
+        if !checked.insert(*story_id) {
+            continue;
+        }
+        let line = locate.entry("stories", *story_id, 1);
+        if !owners.contains_key(story_id) {
+            problems.push(Problem {
+                line,
+                message: format!("story {} does not belong to any epic", story_id),
+            });
+        }
+        for blocker_id in story
+            .blocked_by
+            .iter()
+            .filter(|id| !seen_stories.contains(id))
+        {
+            problems.push(Problem {
+                line,
+                message: format!(
+                    "story {} is blocked by story {}, which does not exist",
+                    story_id, blocker_id
+                ),
+            });
+        }
+    }
+
+    for story_id in state.queue.iter().filter(|id| !seen_stories.contains(id)) {
+        problems.push(Problem {
+            line: locate.key("queue"),
+            message: format!(
+                "the next up queue lists story {}, which does not exist",
+                story_id
+            ),
+        });
+    }
+
+    let highest_id = seen_epics.iter().chain(&seen_stories).max().copied();
+    if let Some(highest_id) = highest_id.filter(|id| *id > state.last_item_id) {
+        problems.push(Problem {
+            line: locate.key("last_item_id"),
+            message: format!(
+                "last_item_id is {} but id {} is already in use",
+                state.last_item_id, highest_id
+            ),
+        });
+    }
+
+    problems
+}
+
+/// Finds the lines items were written on. This is a text search rather
+/// than a parse, which is enough for files this crate wrote itself.
+struct Locator<'a> {
+    content: &'a str,
+}
+
+impl Locator<'_> {
+    fn key(&self, name: &str) -> usize {
+        self.content
+            .find(&format!("\"{}\"", name))
+            .map_or(1, |offset| self.line_at(offset))
+    }
+
+    /// The line of the `occurrence`-th (from 1) entry with this id in a
+    /// section, falling back to the section itself.
+    fn entry(&self, section: &str, id: u32, occurrence: usize) -> usize {
+        let Some(section_start) = self.content.find(&format!("\"{}\"", section)) else {
+            return 1;
+        };
+        let key = format!("\"{}\"", id);
+        self.content[section_start..]
+            .match_indices(&key)
+            .map(|(offset, _)| section_start + offset)
+            .filter(|offset| {
+                self.content[offset + key.len()..]
+                    .trim_start()
+                    .starts_with(':')
+            })
+            .nth(occurrence - 1)
+            .map_or_else(
+                || self.line_at(section_start),
+                |offset| self.line_at(offset),
+            )
+    }
+
+    fn line_at(&self, offset: usize) -> usize {
+        self.content[..offset].matches('\n').count() + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn check_database_should_accept_a_consistent_board() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("build")
+            .with_story("release")
+            .with_story_blocked_by(2)
+            .build();
+        let content = serde_json::to_string_pretty(&state).unwrap();
+
+        assert_eq!(check_database(&content), vec![]);
+    }
+
+    #[test]
+    fn check_database_should_report_every_problem_with_its_line() {
+        let content = r#"{
+  "last_item_id": 3,
+  "epics": {
+    "1": {"name": "a", "description": "", "status": "Open", "stories": [2, 9]},
+    "4": {"name": "b", "description": "", "status": "Open", "stories": [2], "mirrors": [6]}
+  },
+  "stories": {
+    "2": {"name": "s", "description": "", "status": "Open", "blocked_by": [8]},
+    "3": {"name": "t", "description": "", "status": "Open"},
+    "3": {"name": "u", "description": "", "status": "Open"}
+  },
+  "queue": [7]
+}"#;
+
+        let problems = check_database(content)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            problems,
+            vec![
+                "line 10: story id 3 is used more than once",
+                "line 4: epic 1 lists story 9, which does not exist",
+                "line 5: story 2 is listed by both epic 1 and epic 4",
+                "line 5: epic 4 mirrors story 6, which does not exist",
+                "line 8: story 2 is blocked by story 8, which does not exist",
+                "line 9: story 3 does not belong to any epic",
+                "line 12: the next up queue lists story 7, which does not exist",
+                "line 2: last_item_id is 3 but id 4 is already in use",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_database_should_report_syntax_errors_with_their_line() {
+        let problems = check_database("{\n  \"last_item_id\": 1,\n  \"epics\": {,\n}");
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, 3);
+    }
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dao::Database;
+use crate::models::DBState;
+
+/// A write that was started but not confirmed as persisted.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    pub started_at: DateTime<Utc>,
+    pub state: DBState,
+}
+
+/// Wraps a database so every persist is first recorded in a journal file,
+/// which is removed once the write succeeds. A journal left behind means the
+/// app stopped mid-write and the intent can be replayed on the next start.
+pub struct JournaledDatabase {
+    database: Box<dyn Database + Send>,
+    journal_path: PathBuf,
+}
+
+impl JournaledDatabase {
+    pub fn new(database: Box<dyn Database + Send>, journal_path: impl Into<PathBuf>) -> Self {
+        Self {
+            database,
+            journal_path: journal_path.into(),
+        }
+    }
+}
+
+impl Database for JournaledDatabase {
+    fn retrieve(&self) -> Result<DBState> {
+        self.database.retrieve()
+    }
+
+    fn persist(&self, state: &DBState) -> Result<()> {
+        write_intent(
+            &self.journal_path,
+            &Intent {
+                started_at: Utc::now(),
+                state: state.clone(),
+            },
+        )?;
+        self.database.persist(state)?;
+        discard_intent(&self.journal_path)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.database.flush()
+    }
+}
+
+pub fn read_pending_intent(journal_path: &Path) -> Result<Option<Intent>> {
+    if !journal_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(journal_path)?;
+    let intent = serde_json::from_str(&content)
+        .with_context(|| anyhow!("invalid journal in {}", journal_path.display()))?;
+    Ok(Some(intent))
+}
+
+pub fn replay_intent(database: &dyn Database, journal_path: &Path, intent: &Intent) -> Result<()> {
+    database.persist(&intent.state)?;
+    discard_intent(journal_path)
+}
+
+pub fn discard_intent(journal_path: &Path) -> Result<()> {
+    if journal_path.exists() {
+        fs::remove_file(journal_path)?;
+    }
+    Ok(())
+}
+
+fn write_intent(journal_path: &Path, intent: &Intent) -> Result<()> {
+    // written next to the journal and renamed, so a crash never leaves half a record
+    let tmp_path = journal_path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(intent)?)?;
+    fs::rename(&tmp_path, journal_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::test_utils::MockDB;
+    use crate::fixtures::DbFixtureBuilder;
+
+    struct FailingDB;
+
+    impl Database for FailingDB {
+        fn retrieve(&self) -> Result<DBState> {
+            Err(anyhow!("unavailable"))
+        }
+
+        fn persist(&self, _state: &DBState) -> Result<()> {
+            Err(anyhow!("disk full"))
+        }
+    }
+
+    #[test]
+    fn persist_should_clear_the_journal_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("db.journal");
+        let sut = JournaledDatabase::new(Box::new(MockDB::new()), &journal_path);
+
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        sut.persist(&state).unwrap();
+
+        assert_eq!(sut.retrieve().unwrap(), state);
+        assert_eq!(read_pending_intent(&journal_path).unwrap(), None);
+    }
+
+    #[test]
+    fn persist_should_leave_the_intent_when_the_write_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("db.journal");
+        let sut = JournaledDatabase::new(Box::new(FailingDB), &journal_path);
+
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        assert_eq!(sut.persist(&state).is_err(), true);
+
+        let intent = read_pending_intent(&journal_path).unwrap().unwrap();
+        assert_eq!(intent.state, state);
+    }
+
+    #[test]
+    fn replay_intent_should_persist_the_state_and_clear_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("db.journal");
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        JournaledDatabase::new(Box::new(FailingDB), &journal_path)
+            .persist(&state)
+            .unwrap_err();
+
+        let database = MockDB::new();
+        let intent = read_pending_intent(&journal_path).unwrap().unwrap();
+        replay_intent(&database, &journal_path, &intent).unwrap();
+
+        assert_eq!(database.retrieve().unwrap(), state);
+        assert_eq!(journal_path.exists(), false);
+    }
+}
@@ -1,4 +1,5 @@
-use std::fs;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
 
 use anyhow::{Ok, Result};
 
@@ -10,14 +11,18 @@ pub struct JSONFileJiraDAOAdapter {
 }
 
 impl Database for JSONFileJiraDAOAdapter {
+    /// Parses straight from the file, so a large board is never held in
+    /// memory twice, once as text and once parsed.
     fn retrieve(&self) -> Result<DBState> {
-        let content = fs::read_to_string(&self.path)?;
-        let state = serde_json::from_str(&content)?;
+        let file = File::open(&self.path)?;
+        let state = serde_json::from_reader(BufReader::new(file))?;
         Ok(state)
     }
 
     fn persist(&self, state: &DBState) -> Result<()> {
-        fs::write(&self.path, &serde_json::to_vec(state)?)?;
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        serde_json::to_writer(&mut writer, state)?;
+        writer.flush()?;
         Ok(())
     }
 }
@@ -75,12 +80,27 @@ mod tests {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
+                comments: vec![],
+                blocked_by: vec![],
+                acceptance_criteria: vec![],
+                kind: None,
+                remind_at: None,
+                ready: false,
+                external_blockers: vec![],
+                created_at: None,
+                status_changes: vec![],
+                points: None,
             };
             let epic = Epic {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
                 stories: vec![2],
+                split_from: None,
+                split_into: vec![],
+                merged_from: vec![],
+                scope_changes: vec![],
+                mirrors: vec![],
             };
 
             let mut stories = HashMap::new();
@@ -93,6 +113,8 @@ mod tests {
                 last_item_id: 2,
                 epics,
                 stories,
+                queue: vec![],
+                aliases: Default::default(),
             };
 
             assert_eq!(db.persist(&state).is_ok(), true);
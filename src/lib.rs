@@ -0,0 +1,107 @@
+//! Headless access to jira_cli boards.
+//!
+//! [`JiraBoard`] and the model types re-exported here are the supported API
+//! and follow semver. The models are `#[non_exhaustive]`, so fields and
+//! statuses can be added in a minor release: build them with their `new`
+//! constructors. The remaining modules exist for the `jira_cli` binary and
+//! may change in any release.
+
+mod board;
+
+pub use board::JiraBoard;
+pub use models::{Comment, Epic, Status, Story};
+
+#[doc(hidden)]
+pub mod activity_log;
+#[doc(hidden)]
+pub mod archive;
+#[doc(hidden)]
+pub mod backup;
+#[doc(hidden)]
+pub mod burnup;
+#[doc(hidden)]
+pub mod cached_database;
+#[doc(hidden)]
+pub mod calendar;
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod clipboard;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod csv_import;
+#[doc(hidden)]
+pub mod dao;
+#[doc(hidden)]
+pub mod dependencies;
+#[doc(hidden)]
+pub mod doctor;
+#[doc(hidden)]
+pub mod dot_graph;
+#[doc(hidden)]
+pub mod epic_report;
+#[doc(hidden)]
+pub mod estimation;
+#[doc(hidden)]
+pub mod feedback;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod fixtures;
+#[doc(hidden)]
+pub mod forecast;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod grooming;
+#[doc(hidden)]
+pub mod id_format;
+#[doc(hidden)]
+pub mod integrity;
+#[doc(hidden)]
+pub mod journal;
+#[doc(hidden)]
+pub mod json_file_database_adapter;
+#[doc(hidden)]
+pub mod memory_database;
+#[doc(hidden)]
+pub mod models;
+#[doc(hidden)]
+pub mod natural_date;
+#[doc(hidden)]
+pub mod navigator;
+#[doc(hidden)]
+pub mod outline_import;
+#[doc(hidden)]
+pub mod plugins;
+#[doc(hidden)]
+pub mod recipes;
+#[doc(hidden)]
+pub mod reminders;
+#[doc(hidden)]
+pub mod renumber;
+#[doc(hidden)]
+pub mod report_definition;
+#[doc(hidden)]
+pub mod scripting;
+#[doc(hidden)]
+pub mod sla;
+#[doc(hidden)]
+pub mod stats;
+#[doc(hidden)]
+pub mod story_template;
+#[doc(hidden)]
+pub mod time_display;
+#[doc(hidden)]
+pub mod timeline;
+#[doc(hidden)]
+pub mod title_suggestion;
+#[doc(hidden)]
+pub mod tutorial;
+#[doc(hidden)]
+pub mod ui;
+#[doc(hidden)]
+pub mod watchlist;
+#[doc(hidden)]
+pub mod workflow;
+#[doc(hidden)]
+pub mod write_behind;
@@ -1,32 +1,676 @@
-use std::rc::Rc;
+use std::{
+    collections::HashMap, env, fs, io::Write, path::Path, process, rc::Rc, thread, time::Duration,
+};
 
-use dao::JiraDAO;
-use json_file_database_adapter::JSONFileJiraDAOAdapter;
-use navigator::Navigator;
-use ui::get_user_input;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use itertools::Itertools;
 
-use crate::ui::wait_for_key_press;
-
-mod dao;
-mod json_file_database_adapter;
-mod models;
-mod navigator;
-mod ui;
+use jira_cli::backup::BackupPolicy;
+use jira_cli::cached_database::CachedFileDatabase;
+use jira_cli::cli::{self, Command};
+use jira_cli::config::{
+    Config, DEFAULT_CONFIG_PATH, DEFAULT_CSV_PROFILES_PATH, DEFAULT_WATCHLIST_PATH,
+};
+use jira_cli::csv_import::{self, CsvField, CsvMapping, CsvProfiles};
+use jira_cli::dao::{Database, JiraDAO};
+use jira_cli::doctor::{self, Severity};
+use jira_cli::feedback::{Feedback, Signal};
+use jira_cli::journal::{self, JournaledDatabase};
+use jira_cli::json_file_database_adapter::JSONFileJiraDAOAdapter;
+use jira_cli::models::Story;
+use jira_cli::navigator::Navigator;
+use jira_cli::plugins;
+use jira_cli::renumber::{self, RenumberScheme};
+use jira_cli::report_definition::{report_definition_names, reports_dir, ReportDefinition};
+use jira_cli::scripting::Scripts;
+use jira_cli::tutorial::{self, Tour};
+use jira_cli::ui::{
+    get_command, get_user_input, wait_for_key_press, Action, Renderer, Span, StdoutRenderer,
+};
+use jira_cli::watchlist::Watchlist;
+use jira_cli::write_behind::WriteBehindDatabase;
+use jira_cli::{activity_log, archive, dot_graph, git, outline_import, stats};
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let parsed = cli::take_workspace(&args)
+        .and_then(|(workspace, args)| Ok((workspace, cli::parse_args(args)?)));
+    let (workspace, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            println!("{}", error);
+            process::exit(2);
+        }
+    };
+
+    if command == Command::Doctor {
+        let healthy = run_doctor(workspace.as_deref());
+        process::exit(if healthy { 0 } else { 1 });
+    }
+
+    let config = match Config::load(Path::new(DEFAULT_CONFIG_PATH)) {
+        Ok(config) => Rc::new(config),
+        Err(error) => {
+            println!("Error: {:#}", error);
+            process::exit(1);
+        }
+    };
+    let (dao, backups) = match open_database(&config, workspace.as_deref()) {
+        Ok(opened) => opened,
+        Err(error) => {
+            println!("Error: {:#}", error);
+            process::exit(1);
+        }
+    };
+
+    if matches!(command, Command::Interactive { .. }) {
+        warn_about_problems(workspace.as_deref());
+    }
+
+    let result = match command {
+        Command::Interactive { focus_epic } => {
+            run_interactive(Rc::clone(&dao), Rc::clone(&config), backups, focus_epic)
+        }
+        Command::Tutorial => run_tutorial(Rc::clone(&config)),
+        Command::Open { item } => run_open(Rc::clone(&dao), Rc::clone(&config), backups, &item),
+        Command::ExportArchive { archive_path } => {
+            archive::export_archive(&dao, Path::new(&archive_path))
+        }
+        Command::ImportArchive {
+            archive_path,
+            strict,
+        } => with_backup(&dao, &backups, "import-archive", || {
+            archive::import_archive(&dao, Path::new(&archive_path), strict)
+        }),
+        Command::ImportOutline { outline_path } => {
+            with_backup(&dao, &backups, "import-outline", || {
+                import_outline(&dao, &outline_path)
+            })
+        }
+        Command::ImportCsv { csv_path, profile } => {
+            with_backup(&dao, &backups, "import-csv", || {
+                import_csv(&dao, &csv_path, profile)
+            })
+        }
+        Command::ExportDot { dot_path } => {
+            dot_graph::export_dot(&dao, &config, Path::new(&dot_path))
+        }
+        Command::ImportDot { dot_path } => with_backup(&dao, &backups, "import-dot", || {
+            import_dot(&dao, &config, &dot_path)
+        }),
+        Command::InstallCommitHook { focus_epic } => install_commit_hook(&dao, &config, focus_epic),
+        Command::Stats { json } => print_stats(&dao, &config, json),
+        Command::Renumber { by_epic, dry_run } => {
+            renumber_items(&dao, &config, &backups, by_epic, dry_run)
+        }
+        Command::ListPlugins => list_plugins(&config),
+        Command::RunPlugin { name, item } => {
+            with_backup(&dao, &backups, &format!("plugin-{}", name), || {
+                run_plugin(&dao, &config, &name, item)
+            })
+        }
+        Command::Report { name } => run_report(&dao, &config, name),
+        Command::RunReport { name } => run_report_definition(&dao, &config, &name),
+        Command::Vacuum => vacuum_activity_log(&config, &backups),
+        Command::CreateStory {
+            template,
+            epic,
+            vars,
+        } => create_story_from_template(&dao, &config, &template, &epic, &vars),
+        Command::Doctor => unreachable!("handled before the board is opened"),
+    };
+    // flushed even when the command failed, to keep what it wrote before
+    let flushed = dao.flush();
+    let mut failed = false;
+    for error in [result.err(), flushed.err()].into_iter().flatten() {
+        println!("Error: {:#}", error);
+        failed = true;
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Opens the workspace's board, with its journal next to it, e.g.
+/// `data/db.journal` for `data/db.json`, and its backups in the same folder.
+/// Nothing is read yet: the board is parsed when a page first needs it.
+fn open_database(config: &Config, workspace: Option<&str>) -> Result<(Rc<JiraDAO>, BackupPolicy)> {
+    let path = config.database_path(workspace)?;
+    let backups = BackupPolicy::for_database(Path::new(&path), workspace);
+    let journal_path = Path::new(&path).with_extension("journal");
+    let database_adapter = CachedFileDatabase::new(JSONFileJiraDAOAdapter { path });
+    recover_journal(&database_adapter, &journal_path)?;
+    let journaled_database = JournaledDatabase::new(Box::new(database_adapter), &journal_path);
+    let dao = Rc::new(match config.write_behind_ms {
+        Some(delay_ms) => JiraDAO::new(Box::new(WriteBehindDatabase::new(
+            Box::new(journaled_database),
+            Duration::from_millis(delay_ms),
+        ))),
+        None => JiraDAO::new(Box::new(journaled_database)),
+    });
+    Ok((dao, backups))
+}
+
+/// Prints every check with the fixes for the failed ones. Returns whether
+/// nothing failed; warnings don't count.
+fn run_doctor(workspace: Option<&str>) -> bool {
+    let findings = doctor::diagnose(Path::new(DEFAULT_CONFIG_PATH), workspace, true);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    findings
+        .iter()
+        .all(|finding| finding.severity != Severity::Error)
+}
+
+/// The quick checks before the board starts, silent unless something is off.
+fn warn_about_problems(workspace: Option<&str>) {
+    let problems: Vec<_> = doctor::diagnose(Path::new(DEFAULT_CONFIG_PATH), workspace, false)
+        .into_iter()
+        .filter(|finding| finding.severity != Severity::Ok)
+        .collect();
+    if problems.is_empty() {
+        return;
+    }
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    println!("Run `jira_cli doctor` for a full check. Press any key to continue...");
+    wait_for_key_press();
+}
+
+/// Backs the board up before a risky command and, once it succeeded, says
+/// how to go back.
+fn with_backup(
+    dao: &JiraDAO,
+    backups: &BackupPolicy,
+    operation: &str,
+    run: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let restore = backups.back_up(dao, operation)?;
+    run()?;
+    println!(
+        "The board was backed up first, restore it with:\n    {}",
+        restore
+    );
+    Ok(())
+}
+
+/// Offers to finish a write that was interrupted, e.g. by a power loss.
+fn recover_journal(database: &dyn Database, journal_path: &Path) -> Result<()> {
+    let intent = match journal::read_pending_intent(journal_path)? {
+        Some(intent) => intent,
+        None => return Ok(()),
+    };
+    println!(
+        "A change started at {} was not saved completely. Replay it? [Y/n]: ",
+        intent.started_at
+    );
+    if get_user_input().trim().eq_ignore_ascii_case("n") {
+        journal::discard_intent(journal_path)?;
+        println!("Change discarded.");
+    } else {
+        journal::replay_intent(database, journal_path, &intent)?;
+        println!("Change replayed.");
+    }
+    Ok(())
+}
+
+fn import_outline(dao: &JiraDAO, outline_path: &str) -> Result<()> {
+    let epics = outline_import::parse_outline(&fs::read_to_string(outline_path)?)?;
+    let story_count: usize = epics.iter().map(|(_, stories)| stories.len()).sum();
+    let epic_ids = dao.create_epics_with_stories(epics)?;
+    println!(
+        "Imported {} epics and {} stories",
+        epic_ids.len(),
+        story_count
+    );
+    Ok(())
+}
+
+/// Imports the rows of a CSV file as stories. A saved profile maps the
+/// columns right away; otherwise each column is asked for, starting from a
+/// guess, and the mapping can be saved under the profile name.
+fn import_csv(dao: &JiraDAO, csv_path: &str, profile: Option<String>) -> Result<()> {
+    let rows = csv_import::parse_csv(&fs::read_to_string(csv_path)?)?;
+    let header = rows
+        .first()
+        .ok_or_else(|| anyhow!("{} is empty", csv_path))?;
+    let mut profiles = CsvProfiles::load(DEFAULT_CSV_PROFILES_PATH)?;
+    let saved = profile
+        .as_ref()
+        .and_then(|name| profiles.profiles.get(name))
+        .cloned();
+    let mapping = match saved {
+        Some(mapping) => mapping,
+        None => {
+            let mapping = map_csv_columns(&rows)?;
+            let name = match profile {
+                Some(name) => name,
+                None => {
+                    println!("Save this mapping as a profile? Name (empty to skip): ");
+                    get_user_input().trim().to_owned()
+                }
+            };
+            if !name.is_empty() {
+                profiles.save(&name, mapping.clone())?;
+                println!("Saved profile '{}'", name);
+            }
+            mapping
+        }
+    };
+    let skipped = header
+        .iter()
+        .filter(|column| !mapping.contains_key(*column))
+        .join(", ");
+    if !skipped.is_empty() {
+        println!("Skipping columns: {}", skipped);
+    }
+
+    let epics = csv_import::apply_mapping(&rows, &mapping)?;
+    let story_count: usize = epics.iter().map(|(_, stories)| stories.len()).sum();
+    let epic_ids = dao.create_epics_with_stories(epics)?;
+    println!(
+        "Imported {} epics and {} stories",
+        epic_ids.len(),
+        story_count
+    );
+    Ok(())
+}
+
+/// Previews the first rows and asks which field each column holds.
+fn map_csv_columns(rows: &[Vec<String>]) -> Result<CsvMapping> {
+    let header = &rows[0];
+    for row in rows.iter().take(4) {
+        println!("  {}", row.join(" | "));
+    }
+    let fields = CsvField::ALL.iter().map(|field| field.label()).join(", ");
+    println!("Map each column to one of: {}, or skip", fields);
+    let mut mapping = csv_import::guess_mapping(header);
+    for column in header {
+        let guess = mapping.get(column).map_or("skip", |field| field.label());
+        println!("{} [{}]: ", column, guess);
+        let answer = get_user_input();
+        match answer.trim() {
+            "" => {}
+            "skip" => {
+                mapping.remove(column);
+            }
+            answer => {
+                let field = CsvField::from_label(answer)
+                    .ok_or_else(|| anyhow!("'{}' is not a field", answer))?;
+                mapping.retain(|_, mapped| *mapped != field);
+                mapping.insert(column.clone(), field);
+            }
+        }
+    }
+    Ok(mapping)
+}
+
+fn import_dot(dao: &JiraDAO, config: &Config, dot_path: &str) -> Result<()> {
+    let graph = dot_graph::parse_dot(&fs::read_to_string(dot_path)?, config)?;
+    let (added, removed) = dot_graph::import_dot(dao, &graph)?;
+    println!(
+        "Linked {} and unlinked {} blockers across {} stories",
+        added,
+        removed,
+        graph.stories.len()
+    );
+    Ok(())
+}
+
+/// Creates a story from a configured template and prints its key, for
+/// scripts to pick up.
+fn create_story_from_template(
+    dao: &JiraDAO,
+    config: &Config,
+    template: &str,
+    epic: &str,
+    vars: &[(String, String)],
+) -> Result<()> {
+    let story = config
+        .story_templates
+        .get(template)
+        .ok_or_else(|| {
+            anyhow!(
+                "unknown template '{}', configured: {}",
+                template,
+                config.story_templates.keys().sorted().join(", ")
+            )
+        })?
+        .instantiate(vars)?;
+    let epic_id = config
+        .id_format
+        .parse(epic)
+        .ok_or_else(|| anyhow!("'{}' is not a valid epic id", epic))?;
+    let epic_id = dao.read_db()?.resolve(epic_id).unwrap_or(epic_id);
+    dao.get_epic(epic_id)
+        .map_err(|_| anyhow!("could not find epic {}", epic))?;
+    let story_id = dao.create_story(story, epic_id)?;
+    println!("{}", config.id_format.format(story_id));
+    Ok(())
+}
+
+fn vacuum_activity_log(config: &Config, backups: &BackupPolicy) -> Result<()> {
+    let path = backups.activity_log_path();
+    let compaction = activity_log::vacuum(&path, &config.activity_retention, Utc::now())?;
+    println!(
+        "Compacted {} entries of {}; it now has {} monthly summaries and takes {} bytes.",
+        compaction.compacted,
+        path.display(),
+        compaction.months,
+        compaction.bytes
+    );
+    Ok(())
+}
+
+fn print_stats(dao: &JiraDAO, config: &Config, json: bool) -> Result<()> {
+    let stats = stats::board_stats(&dao.read_db()?, config);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        println!("{}", stats.to_text());
+    }
+    Ok(())
+}
+
+fn list_plugins(config: &Config) -> Result<()> {
+    let dir = plugins::plugins_dir(config).ok_or_else(|| anyhow!("no plugins directory"))?;
+    let found = plugins::discover_plugins(&dir)?;
+    if found.is_empty() {
+        println!("No plugins in {}.", dir.display());
+    }
+    for plugin in found {
+        println!("{}", plugin.name);
+    }
+    Ok(())
+}
+
+fn run_plugin(dao: &JiraDAO, config: &Config, name: &str, item: Option<String>) -> Result<()> {
+    let dir = plugins::plugins_dir(config).ok_or_else(|| anyhow!("no plugins directory"))?;
+    let plugin = plugins::find_plugin(&dir, name)?;
+    let item_id = item
+        .map(|key| {
+            config
+                .id_format
+                .parse(&key)
+                .ok_or_else(|| anyhow!("'{}' is not a valid id", key))
+        })
+        .transpose()?;
+    let applied = plugins::run_on_board(dao, config, &plugin, item_id)?;
+    println!("{} applied {} changes.", plugin.name, applied);
+    Ok(())
+}
+
+fn load_scripts(config: &Config) -> Result<Option<Scripts>> {
+    config
+        .script
+        .as_ref()
+        .map(|path| Scripts::load(Path::new(path)))
+        .transpose()
+}
+
+/// Runs a report of the configured script, or lists those and the reports
+/// defined in the reports folder.
+fn run_report(dao: &JiraDAO, config: &Config, name: Option<String>) -> Result<()> {
+    let scripts = load_scripts(config)?;
+    let Some(name) = name else {
+        let mut names = scripts
+            .map(|scripts| scripts.report_names())
+            .unwrap_or_default();
+        names.extend(
+            report_definition_names(&reports_dir(config))?
+                .into_iter()
+                .map(|name| format!("run {}", name)),
+        );
+        if names.is_empty() {
+            println!("No reports are defined.");
+        }
+        for name in names {
+            println!("{}", name);
+        }
+        return Ok(());
+    };
+    let scripts = scripts.ok_or_else(|| anyhow!("no script is configured"))?;
+    println!("{}", scripts.run_report(&name, &dao.read_db()?)?);
+    Ok(())
+}
+
+fn run_report_definition(dao: &JiraDAO, config: &Config, name: &str) -> Result<()> {
+    let definition = ReportDefinition::load(&reports_dir(config), name)?;
+    println!("{}", definition.run(&dao.read_db()?, config)?);
+    Ok(())
+}
+
+/// Shows which ids would change and, unless it is a dry run, applies the
+/// change in a single write once the user confirms.
+fn renumber_items(
+    dao: &JiraDAO,
+    config: &Config,
+    backups: &BackupPolicy,
+    by_epic: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let scheme = if by_epic {
+        RenumberScheme::ByEpic
+    } else {
+        RenumberScheme::Compact
+    };
+    let db_state = dao.read_db()?;
+    let plan = renumber::plan_renumbering(&db_state, scheme);
+    if plan.is_empty() {
+        println!("All ids are already in order, nothing to renumber.");
+        return Ok(());
+    }
+
+    for renumbering in &plan {
+        println!(
+            "  {:>10} -> {:<10} {} {}",
+            config.id_format.format(renumbering.old_id),
+            config.id_format.format(renumbering.new_id),
+            if renumbering.is_epic {
+                "epic "
+            } else {
+                "story"
+            },
+            renumbering.name
+        );
+    }
+    if dry_run {
+        return Ok(());
+    }
+    println!("Renumber these {} items? [Y/n]: ", plan.len());
+    if !get_user_input().trim().eq("Y") {
+        println!("Nothing changed.");
+        return Ok(());
+    }
+    let restore = backups.back_up(dao, "renumber")?;
+    dao.replace_db(&renumber::apply_renumbering(&db_state, &plan))?;
+    let new_ids: HashMap<u32, u32> = plan
+        .iter()
+        .filter(|renumbering| !renumbering.is_epic)
+        .map(|renumbering| (renumbering.old_id, renumbering.new_id))
+        .collect();
+    Watchlist::load(DEFAULT_WATCHLIST_PATH)?.renumber(backups.workspace.as_deref(), &new_ids)?;
+    println!("Renumbered {} items.", plan.len());
+    println!(
+        "The board was backed up first, restore it with:\n    {}",
+        restore
+    );
+    Ok(())
+}
+
+/// Installs the commit hook and picks the story whose key it adds, from the
+/// whole board or from a single epic.
+fn install_commit_hook(dao: &JiraDAO, config: &Config, focus_epic: Option<String>) -> Result<()> {
+    let db_state = dao.read_db()?;
+    let story_ids: Vec<u32> = match focus_epic {
+        Some(epic_key) => {
+            let epic_id = config
+                .id_format
+                .parse(&epic_key)
+                .ok_or_else(|| anyhow!("'{}' is not a valid epic id", epic_key))?;
+            db_state
+                .epics
+                .get(&epic_id)
+                .ok_or_else(|| anyhow!("could not find epic {}", epic_key))?
+                .stories
+                .clone()
+        }
+        None => db_state.stories.keys().copied().sorted().collect(),
+    };
+    let candidates: Vec<(u32, &Story)> = story_ids
+        .into_iter()
+        .filter_map(|id| db_state.stories.get(&id).map(|story| (id, story)))
+        .filter(|(_, story)| !story.status.is_done())
+        .collect();
+    if candidates.is_empty() {
+        return Err(anyhow!("there are no open stories to pick from"));
+    }
+
+    println!("Which story are you working on?");
+    for (index, (id, story)) in candidates.iter().enumerate() {
+        println!(
+            "  {}) {} {} [{}]",
+            index + 1,
+            config.id_format.format(*id),
+            story.name,
+            story.status
+        );
+    }
+    let choice = get_user_input();
+    let (story_id, _) = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|index| candidates.get(index))
+        .ok_or_else(|| anyhow!("'{}' is not one of the listed stories", choice))?;
+
+    let repo = config.git_repo.as_deref().unwrap_or(".");
+    let hook_path = git::install_commit_hook(Path::new(repo))?;
+    let key = config.id_format.format(*story_id);
+    git::set_active_story(Path::new(repo), &key)?;
+    println!(
+        "Installed {}; commits will be prefixed with [{}]",
+        hook_path.display(),
+        key
+    );
+    Ok(())
+}
+
+fn run_interactive(
+    dao: Rc<JiraDAO>,
+    config: Rc<Config>,
+    backups: BackupPolicy,
+    focus_epic: Option<String>,
+) -> Result<()> {
+    let mut navigator = board_navigator(Rc::clone(&dao), &config, backups)?;
+    if let Some(epic_key) = focus_epic {
+        let epic_id = config
+            .id_format
+            .parse(&epic_key)
+            .ok_or_else(|| anyhow!("'{}' is not a valid epic id", epic_key))?;
+        navigator.focus_epic(epic_id)?;
+    }
+    navigator.show_due_reminders()?;
+    let switch = run_navigator(navigator, &config);
+    follow_workspace_switches(&dao, &config, switch)
+}
+
+fn board_navigator(
+    dao: Rc<JiraDAO>,
+    config: &Rc<Config>,
+    backups: BackupPolicy,
+) -> Result<Navigator> {
+    // shown until the first page is drawn, which is what reads the board
+    clearscreen::clear().unwrap();
+    println!("Loading the board...");
+    let mut navigator = Navigator::new(dao, Rc::clone(config));
+    let workspace = backups.workspace.clone();
+    navigator.set_backups(backups);
+    navigator.set_watchlist(Watchlist::load(DEFAULT_WATCHLIST_PATH)?, workspace);
+    if let Some(scripts) = load_scripts(config)? {
+        navigator.set_scripts(scripts);
+    }
+    Ok(navigator)
+}
+
+/// Reopens the board on the chosen workspace for as long as the user keeps
+/// switching, saving each board before leaving it.
+fn follow_workspace_switches(
+    dao: &JiraDAO,
+    config: &Rc<Config>,
+    mut switch: Option<String>,
+) -> Result<()> {
+    dao.flush()?;
+    while let Some(workspace) = switch {
+        let (dao, backups) = open_database(config, Some(&workspace))?;
+        let mut navigator = board_navigator(Rc::clone(&dao), config, backups)?;
+        navigator.show_due_reminders()?;
+        switch = run_navigator(navigator, config);
+        dao.flush()?;
+    }
+    Ok(())
+}
+
+fn run_open(
+    dao: Rc<JiraDAO>,
+    config: Rc<Config>,
+    backups: BackupPolicy,
+    item_key: &str,
+) -> Result<()> {
+    let item_id = config
+        .id_format
+        .parse(item_key)
+        .ok_or_else(|| anyhow!("'{}' is not a valid id", item_key))?;
+    let mut navigator = board_navigator(Rc::clone(&dao), &config, backups)?;
+    navigator.open_item(item_id)?;
+    let switch = run_navigator(navigator, &config);
+    follow_workspace_switches(&dao, &config, switch)
+}
+
+/// Runs the guided tour against a throwaway copy of the sample board.
+fn run_tutorial(config: Rc<Config>) -> Result<()> {
+    let path = env::temp_dir().join("jira_cli_tutorial.json");
     let database_adapter = JSONFileJiraDAOAdapter {
-        path: "./data/db.json".to_owned(),
+        path: path.to_string_lossy().into_owned(),
     };
     let dao = JiraDAO::new(Box::new(database_adapter));
-    let mut navigator = Navigator::new(Rc::new(dao));
+    dao.replace_db(&tutorial::sample_state())?;
 
+    let mut navigator = Navigator::new(Rc::new(dao), Rc::clone(&config));
+    navigator.start_tour(Tour::getting_started());
+    run_navigator(navigator, &config);
+    Ok(())
+}
+
+/// Runs the board until every page is closed. Returns the workspace to
+/// switch to when that is why they were closed.
+fn run_navigator(mut navigator: Navigator, config: &Config) -> Option<String> {
+    let input_mode = config.input_mode.resolve();
+    let mut renderer = StdoutRenderer;
+    let mut feedback_banner = None;
     loop {
         clearscreen::clear().unwrap();
         let page = match navigator.get_current_page() {
             Some(page) => page,
             None => break,
         };
-        if let Err(error) = page.draw_page() {
+        if let Some(banner) = feedback_banner.take() {
+            renderer.write_spans(&[banner]);
+            renderer.write_line("");
+        }
+        match navigator.get_focus_header() {
+            Ok(Some(header)) => println!("{}\n", header),
+            Ok(None) => {}
+            Err(error) => println!("Error rendering focus header: {}\n", error),
+        }
+        if let Some(banner) = navigator.get_undo_banner() {
+            renderer.write_spans(&[Span::bold(&banner)]);
+            renderer.write_line("");
+        }
+        if let Err(error) = page.draw_page(&mut renderer) {
             println!(
                 "Error rendering page: {}\nPress any key to continue...",
                 error
@@ -34,8 +678,21 @@ fn main() {
             wait_for_key_press();
             break;
         }
-        let input = get_user_input();
-        match page.handle_input(&input) {
+        match navigator.get_footer() {
+            Ok(footer) => renderer.write_spans(&[Span::dim(&footer)]),
+            Err(error) => println!("Error rendering footer: {}", error),
+        }
+        if let Some(instruction) = navigator.get_tour_instruction() {
+            println!("\n>> {}", instruction);
+        }
+        // Ctrl-C leaves like closing every page, so pending writes are saved
+        let Some(input) = get_command(input_mode) else {
+            if let Err(error) = navigator.handle_action(Action::Exit) {
+                println!("Error: {:#}", error);
+            }
+            break;
+        };
+        match navigator.handle_input(&input) {
             Err(error) => {
                 println!(
                     "Error getting user input: {}\nPress any key to continue...",
@@ -52,5 +709,30 @@ fn main() {
                 }
             }
         }
+        if let Some(feedback) = navigator.take_feedback() {
+            feedback_banner = give_feedback(feedback);
+        }
+    }
+    navigator.take_workspace_switch()
+}
+
+/// Rings or flashes right away; a banner is returned to be drawn above the
+/// next page.
+fn give_feedback(feedback: Feedback) -> Option<Span> {
+    let mut stdout = std::io::stdout();
+    match feedback.signal {
+        Signal::None => None,
+        Signal::Bell => {
+            let _ = write!(stdout, "\x07").and_then(|_| stdout.flush());
+            None
+        }
+        Signal::Flash => {
+            let _ = write!(stdout, "\x1b[?5h").and_then(|_| stdout.flush());
+            thread::sleep(Duration::from_millis(120));
+            let _ = write!(stdout, "\x1b[?5l").and_then(|_| stdout.flush());
+            None
+        }
+        Signal::Banner if feedback.is_error => Some(Span::error(&feedback.message)),
+        Signal::Banner => Some(Span::success(&feedback.message)),
     }
 }
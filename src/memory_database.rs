@@ -0,0 +1,31 @@
+use std::cell::RefCell;
+
+use anyhow::{Ok, Result};
+
+use crate::dao::Database;
+use crate::models::DBState;
+
+/// Keeps the board in memory only, for boards that don't outlive the
+/// process, e.g. [`crate::JiraBoard::in_memory`].
+pub struct MemoryDatabase {
+    state: RefCell<DBState>,
+}
+
+impl MemoryDatabase {
+    pub fn new(state: DBState) -> Self {
+        Self {
+            state: RefCell::new(state),
+        }
+    }
+}
+
+impl Database for MemoryDatabase {
+    fn retrieve(&self) -> Result<DBState> {
+        Ok(self.state.borrow().clone())
+    }
+
+    fn persist(&self, state: &DBState) -> Result<()> {
+        *self.state.borrow_mut() = state.clone();
+        Ok(())
+    }
+}
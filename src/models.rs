@@ -1,8 +1,14 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    ops::Range,
+};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[non_exhaustive]
 pub enum Status {
     Open,
     InProgress,
@@ -10,6 +16,20 @@ pub enum Status {
     Resolved,
 }
 
+impl Status {
+    /// Every status, in the order menus list them.
+    pub const ALL: [Status; 4] = [
+        Status::Open,
+        Status::InProgress,
+        Status::Resolved,
+        Status::Closed,
+    ];
+
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Resolved | Self::Closed)
+    }
+}
+
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -30,11 +50,29 @@ impl Display for Status {
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[non_exhaustive]
 pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
     pub stories: Vec<u32>,
+    /// The epic this one was split off from.
+    #[serde(default)]
+    pub split_from: Option<u32>,
+    /// Epics that were split off from this one, oldest first.
+    #[serde(default)]
+    pub split_into: Vec<u32>,
+    /// Names of the epics that were merged into this one and deleted.
+    #[serde(default)]
+    pub merged_from: Vec<String>,
+    /// Stories added to and removed from the epic, oldest first. Boards from
+    /// before this was recorded have none.
+    #[serde(default)]
+    pub scope_changes: Vec<ScopeChange>,
+    /// Stories of other epics listed in this one too, as references: they
+    /// still belong to, count towards and are changed in their own epic.
+    #[serde(default)]
+    pub mirrors: Vec<u32>,
 }
 
 impl Epic {
@@ -44,15 +82,107 @@ impl Epic {
             description,
             status: Status::Open,
             stories: vec![],
+            split_from: None,
+            split_into: vec![],
+            merged_from: vec![],
+            scope_changes: vec![],
+            mirrors: vec![],
         }
     }
+
+    /// Adds the story and records when, so the burnup chart can tell scope
+    /// that came in later.
+    pub fn add_story(&mut self, story_id: u32) {
+        self.stories.push(story_id);
+        self.scope_changes.push(ScopeChange {
+            story_id,
+            added: true,
+            at: Utc::now(),
+        });
+    }
+
+    /// Removes the story, if the epic has it, and records when.
+    pub fn remove_story(&mut self, story_id: u32) {
+        let Some(index) = self.stories.iter().position(|id| *id == story_id) else {
+            return;
+        };
+        self.stories.remove(index);
+        self.scope_changes.push(ScopeChange {
+            story_id,
+            added: false,
+            at: Utc::now(),
+        });
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[non_exhaustive]
+pub struct Comment {
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    /// Number of the comment this one answers, counted from 1 and shown as
+    /// `C-<n>`.
+    #[serde(default)]
+    pub reply_to: Option<usize>,
+    /// When the text was last changed, if ever.
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            created_at: Utc::now(),
+            reply_to: None,
+            edited_at: None,
+        }
+    }
+
+    pub fn reply(text: String, reply_to: usize) -> Self {
+        Self {
+            reply_to: Some(reply_to),
+            ..Self::new(text)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[non_exhaustive]
 pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// Ids of the stories that have to be done before this one.
+    #[serde(default)]
+    pub blocked_by: Vec<u32>,
+    /// Numbered from 1 in this order and referenced as `AC-<n>`.
+    #[serde(default)]
+    pub acceptance_criteria: Vec<AcceptanceCriterion>,
+    /// Picks the workflow from the config, e.g. `bug` or `chore`. Stories
+    /// without one can move between any statuses.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// When to bring the story back up. Cleared once dismissed.
+    #[serde(default)]
+    pub remind_at: Option<DateTime<Utc>>,
+    /// Refined enough to be picked up; see [`Story::refinement_gaps`].
+    #[serde(default)]
+    pub ready: bool,
+    /// Tickets outside the board the story waits on.
+    #[serde(default)]
+    pub external_blockers: Vec<ExternalBlocker>,
+    /// Unknown for stories created before it was recorded.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Oldest first; see [`Story::set_status`].
+    #[serde(default)]
+    pub status_changes: Vec<StatusChange>,
+    /// Story points, on the scale configured when they were given.
+    #[serde(default)]
+    pub points: Option<u32>,
 }
 
 impl Story {
@@ -61,8 +191,112 @@ impl Story {
             name,
             description,
             status: Status::Open,
+            comments: vec![],
+            blocked_by: vec![],
+            acceptance_criteria: vec![],
+            kind: None,
+            remind_at: None,
+            ready: false,
+            external_blockers: vec![],
+            created_at: Some(Utc::now()),
+            status_changes: vec![],
+            points: None,
+        }
+    }
+
+    /// Changes the status and records when it happened, so the epic
+    /// timeline can show it.
+    pub fn set_status(&mut self, status: Status) {
+        if status == self.status {
+            return;
+        }
+        self.status_changes.push(StatusChange {
+            from: self.status.clone(),
+            to: status.clone(),
+            at: Utc::now(),
+        });
+        self.status = status;
+    }
+
+    /// What the story still lacks before it can be marked ready.
+    pub fn refinement_gaps(&self) -> Vec<&'static str> {
+        let mut gaps = vec![];
+        if self.description.trim().is_empty() {
+            gaps.push("a description");
+        }
+        if self.acceptance_criteria.is_empty() {
+            gaps.push("acceptance criteria");
         }
+        gaps
     }
+
+    /// Numbers of the acceptance criteria that are not done yet.
+    pub fn open_criteria(&self) -> Vec<usize> {
+        self.acceptance_criteria
+            .iter()
+            .enumerate()
+            .filter(|(_, criterion)| !criterion.done)
+            .map(|(index, _)| index + 1)
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct StatusChange {
+    pub from: Status,
+    pub to: Status,
+    pub at: DateTime<Utc>,
+}
+
+/// A story joining or leaving an epic.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ScopeChange {
+    pub story_id: u32,
+    /// Whether the story was added; `false` when it was removed.
+    pub added: bool,
+    pub at: DateTime<Utc>,
+}
+
+/// A ticket in another tracker, linked by its URL.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ExternalBlocker {
+    pub url: String,
+    pub title: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct AcceptanceCriterion {
+    pub text: String,
+    pub done: bool,
+}
+
+impl AcceptanceCriterion {
+    pub fn new(text: String) -> Self {
+        Self { text, done: false }
+    }
+}
+
+/// Finds `AC-<n>` mentions in a text, returning where each one is and the
+/// criterion number it refers to.
+pub fn criterion_references(text: &str) -> Vec<(Range<usize>, usize)> {
+    let is_word_byte = |byte: Option<&u8>| byte.is_some_and(|byte| byte.is_ascii_alphanumeric());
+    let bytes = text.as_bytes();
+    text.match_indices("AC-")
+        .filter(|(start, _)| *start == 0 || !is_word_byte(bytes.get(start - 1)))
+        .filter_map(|(start, _)| {
+            let digits_start = start + 3;
+            let digits_len = bytes[digits_start..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            let end = digits_start + digits_len;
+            if digits_len == 0 || is_word_byte(bytes.get(end)) {
+                return None;
+            }
+            let number = text[digits_start..end].parse().ok()?;
+            Some((start..end, number))
+        })
+        .collect()
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -70,4 +304,96 @@ pub struct DBState {
     pub last_item_id: u32,
     pub epics: HashMap<u32, Epic>,
     pub stories: HashMap<u32, Story>,
+    /// Story ids in the order the user intends to work on them.
+    #[serde(default)]
+    pub queue: Vec<u32>,
+    /// Ids items had before a merge or renumbering, pointing to the id they
+    /// have now, so old keys and permalinks still open them.
+    #[serde(default)]
+    pub aliases: BTreeMap<u32, u32>,
+}
+
+impl DBState {
+    /// The epic the story belongs to.
+    pub fn epic_of(&self, story_id: u32) -> Option<u32> {
+        self.epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(epic_id, _)| *epic_id)
+    }
+
+    pub fn has_item(&self, id: u32) -> bool {
+        self.epics.contains_key(&id) || self.stories.contains_key(&id)
+    }
+
+    /// The item `id` stands for: itself when it exists, otherwise the item
+    /// its aliases lead to.
+    pub fn resolve(&self, id: u32) -> Option<u32> {
+        let mut current = id;
+        // every hop uses up an alias, so a longer walk is a cycle
+        for _ in 0..=self.aliases.len() {
+            if self.has_item(current) {
+                return Some(current);
+            }
+            current = *self.aliases.get(&current)?;
+        }
+        None
+    }
+
+    /// Aliases that no longer lead anywhere useful: their id was given to
+    /// another item, or the item they pointed to was deleted.
+    pub fn dead_aliases(&self) -> Vec<u32> {
+        self.aliases
+            .iter()
+            .filter(|(id, target)| self.has_item(**id) || self.resolve(**target).is_none())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn criterion_references_should_find_whole_mentions() {
+        let text = "AC-2 done, AC-10 too; not XAC-3, AC-4b or AC-";
+        let references = criterion_references(text);
+
+        assert_eq!(
+            references
+                .iter()
+                .map(|(range, number)| (&text[range.clone()], *number))
+                .collect::<Vec<_>>(),
+            vec![("AC-2", 2), ("AC-10", 10)]
+        );
+    }
+
+    #[test]
+    fn refinement_gaps_should_list_missing_fields() {
+        let mut story = Story::new("".to_owned(), " ".to_owned());
+        assert_eq!(
+            story.refinement_gaps(),
+            vec!["a description", "acceptance criteria"]
+        );
+
+        story.description = "Refund card payments".to_owned();
+        story.acceptance_criteria = vec![AcceptanceCriterion::new("a".to_owned())];
+        assert_eq!(story.refinement_gaps(), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn open_criteria_should_number_from_one() {
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.acceptance_criteria = vec![
+            AcceptanceCriterion::new("a".to_owned()),
+            AcceptanceCriterion {
+                text: "b".to_owned(),
+                done: true,
+            },
+            AcceptanceCriterion::new("c".to_owned()),
+        ];
+
+        assert_eq!(story.open_criteria(), vec![1, 3]);
+    }
 }
@@ -0,0 +1,148 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::calendar::Calendar;
+
+/// Hour of the day that days without a time fall on, as with `YYYY-MM-DD`.
+const START_OF_DAY: u32 = 9;
+/// Hour that `eod` and `eow` stand for.
+const END_OF_DAY: u32 = 17;
+
+/// Reads phrases such as `tomorrow`, `next friday`, `in 2 weeks`, `eod` or
+/// `monday at 14:30`, relative to `now` on the wall clock. A weekday means
+/// its next occurrence after today, with or without `next`. `in 3 working
+/// days` skips the days off in the calendar.
+pub fn parse_natural(
+    input: &str,
+    now: NaiveDateTime,
+    calendar: &Calendar,
+) -> Option<NaiveDateTime> {
+    let input = input.trim().to_lowercase();
+    if let Some(offset) = input.strip_prefix("in ") {
+        if let Some(count) = parse_working_days(offset) {
+            return Some(
+                calendar
+                    .add_working_days(now.date(), count)?
+                    .and_time(now.time()),
+            );
+        }
+        return now.checked_add_signed(parse_offset(offset)?);
+    }
+    let (day, time) = match input.split_once(" at ") {
+        Some((day, time)) => (
+            day.trim(),
+            Some(NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()?),
+        ),
+        None => (input.as_str(), None),
+    };
+    let today = now.date();
+    let (date, hour) = match day {
+        "today" => (today, START_OF_DAY),
+        "eod" | "end of day" => (today, END_OF_DAY),
+        "tomorrow" => (today + Duration::days(1), START_OF_DAY),
+        "eow" | "end of week" => (this_or_next(today, Weekday::Fri), END_OF_DAY),
+        "next week" => (after(today, Weekday::Mon), START_OF_DAY),
+        _ => {
+            let name = day
+                .strip_prefix("next ")
+                .or_else(|| day.strip_prefix("on "))
+                .unwrap_or(day);
+            (after(today, name.parse().ok()?), START_OF_DAY)
+        }
+    };
+    Some(date.and_time(time.or_else(|| NaiveTime::from_hms_opt(hour, 0, 0))?))
+}
+
+/// `2 weeks`, `an hour`, `30 minutes` and the like. `None` as well for
+/// amounts too large to add to a date.
+fn parse_offset(input: &str) -> Option<Duration> {
+    let mut words = input.split_whitespace();
+    let amount: i64 = match words.next()? {
+        "a" | "an" => 1,
+        amount => amount.parse().ok()?,
+    };
+    let unit = words.next()?;
+    if words.next().is_some() {
+        return None;
+    }
+    match unit.strip_suffix('s').unwrap_or(unit) {
+        "minute" | "min" => Duration::try_minutes(amount),
+        "hour" | "hr" => Duration::try_hours(amount),
+        "day" => Duration::try_days(amount),
+        "week" => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// `3 working days`, `a business day` and the like.
+fn parse_working_days(input: &str) -> Option<u32> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let [amount, "working" | "business", "day" | "days"] = words[..] else {
+        return None;
+    };
+    match amount {
+        "a" | "one" => Some(1),
+        amount => amount.parse().ok(),
+    }
+}
+
+/// The first `weekday` after `date`.
+fn after(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    this_or_next(date + Duration::days(1), weekday)
+}
+
+/// `date` itself when it is a `weekday`, otherwise the first one after it.
+fn this_or_next(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_ahead =
+        (7 + weekday.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+    date + Duration::days(days_ahead.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday afternoon.
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 5, 10)
+            .unwrap()
+            .and_hms_opt(15, 20, 0)
+            .unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(2023, 5, day)?.and_hms_opt(hour, minute, 0)
+    }
+
+    #[test]
+    fn parse_natural_should_read_days() {
+        let parse = |input| parse_natural(input, now(), &Calendar::default());
+
+        assert_eq!(parse("eod"), at(10, 17, 0));
+        assert_eq!(parse("Tomorrow"), at(11, 9, 0));
+        assert_eq!(parse("tomorrow at 14:30"), at(11, 14, 30));
+        assert_eq!(parse("friday"), at(12, 9, 0));
+        assert_eq!(parse("next friday"), at(12, 9, 0));
+        assert_eq!(parse("next wed"), at(17, 9, 0));
+        assert_eq!(parse("on monday at 08:00"), at(15, 8, 0));
+        assert_eq!(parse("eow"), at(12, 17, 0));
+        assert_eq!(parse("next week"), at(15, 9, 0));
+        assert_eq!(parse("tomorrow at noon"), None);
+        assert_eq!(parse("someday"), None);
+    }
+
+    #[test]
+    fn parse_natural_should_read_offsets() {
+        let parse = |input| parse_natural(input, now(), &Calendar::default());
+
+        assert_eq!(parse("in 2 weeks"), at(24, 15, 20));
+        assert_eq!(parse("in an hour"), at(10, 16, 20));
+        assert_eq!(parse("in 30 mins"), at(10, 15, 50));
+        assert_eq!(parse("in 3 days"), at(13, 15, 20));
+        assert_eq!(parse("in 2 fortnights"), None);
+        assert_eq!(parse("in 2 days or so"), None);
+        assert_eq!(parse("in 99999999999 weeks"), None);
+        assert_eq!(parse("in 999999999999999 days"), None);
+        assert_eq!(parse("in 3 working days"), at(15, 15, 20));
+        assert_eq!(parse("in a business day"), at(11, 15, 20));
+    }
+}
@@ -1,198 +1,2481 @@
 use anyhow::{anyhow, Context, Ok, Result};
-use std::rc::Rc;
+use chrono::Utc;
+use std::{fs, path::Path, rc::Rc};
 
 use crate::{
-    dao::JiraDAO,
-    ui::{Action, EpicDetail, HomePage, Page, Prompts, StoryDetail},
+    backup::BackupPolicy,
+    clipboard::{copy_to_clipboard, CopyToClipboard},
+    config::Config,
+    dao::{Database, JiraDAO},
+    epic_report::render_epic_html,
+    feedback::Feedback,
+    git,
+    grooming::pick_for_grooming,
+    json_file_database_adapter::JSONFileJiraDAOAdapter,
+    models::{Comment, DBState, Status},
+    plugins,
+    recipes::{available, RecipeRun},
+    reminders::{due_reminders, is_phrase, parse_reminder},
+    scripting::Scripts,
+    tutorial::Tour,
+    ui::{
+        Action, AliasesPage, DependencyPage, EpicBurnup, EpicDetail, EpicMergePreview,
+        EpicTimeline, ForecastPage, GroomOutcome, GroomingPage, HomePage, Page, Prompts, QueuePage,
+        RecipesPage, RefinementPage, RemindersPage, StatusFilter, StoryDetail, WatchlistPage,
+    },
+    watchlist::{collect_watchlist, Watchlist},
 };
 
 pub struct Navigator {
     pages: Vec<Box<dyn Page>>,
     prompts: Prompts,
     dao: Rc<JiraDAO>,
+    config: Rc<Config>,
+    focused_epic_id: Option<u32>,
+    tour: Option<Tour>,
+    last_error: Option<String>,
+    notice: Option<String>,
+    clipboard: Box<CopyToClipboard>,
+    undo: Option<Undo>,
+    feedback: Option<Feedback>,
+    scripts: Option<Scripts>,
+    workspace_switch: Option<String>,
+    backups: Option<BackupPolicy>,
+    watchlist: Option<Watchlist>,
+    /// The workspace of the board on screen, `None` for the default one.
+    workspace: Option<String>,
+    recipe: Option<RecipeRun>,
+}
+
+/// Stories picked for grooming when `grooming_count` isn't configured.
+const DEFAULT_GROOMING_COUNT: usize = 3;
+
+/// How many items a change may touch before it counts as a bulk change,
+/// when `bulk_limit` isn't configured.
+const DEFAULT_BULK_LIMIT: usize = 10;
+
+/// The board as it was before the last deletion or edit, offered back
+/// until the next key press.
+struct Undo {
+    /// `Deleted` or `Edited`, for the banner.
+    verb: &'static str,
+    description: String,
+    snapshot: DBState,
 }
 
 impl Navigator {
-    pub fn new(dao: Rc<JiraDAO>) -> Self {
-        Self {
-            pages: vec![Box::new(HomePage {
-                dao: Rc::clone(&dao),
-            })],
+    pub fn new(dao: Rc<JiraDAO>, config: Rc<Config>) -> Self {
+        let mut navigator = Self {
+            pages: vec![],
             prompts: Prompts::new(),
+            dao: Rc::clone(&dao),
+            config: Rc::clone(&config),
+            focused_epic_id: None,
+            tour: None,
+            last_error: None,
+            notice: None,
+            clipboard: Box::new(copy_to_clipboard),
+            undo: None,
+            feedback: None,
+            scripts: None,
+            workspace_switch: None,
+            backups: None,
+            watchlist: None,
+            workspace: None,
+            recipe: None,
+        };
+        navigator.push_page(Box::new(HomePage {
             dao,
-        }
+            config,
+            status_filter: StatusFilter::default(),
+        }));
+        navigator
     }
 
     pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
         self.pages.last()
     }
 
+    /// Forwards the input to the current page, which may update its own state.
+    /// `#<id>` is handled here instead, so it jumps to an item from any page.
+    pub fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        if self.undo.is_some() {
+            if input == "u" {
+                return Ok(Some(Action::UndoDeletion));
+            }
+            self.undo = None;
+        }
+        let result = match (input.strip_prefix('#'), self.pages.last_mut()) {
+            (Some(id), _) => match self.config.id_format.parse(id.trim()) {
+                Some(item_id) => Ok(Some(Action::JumpToItem { item_id })),
+                None => Err(anyhow!("'{}' is not an item id", id.trim())),
+            },
+            (None, Some(page)) => page.handle_input(input),
+            (None, None) => Ok(None),
+        };
+        match &result {
+            Err(error) => {
+                self.last_error = Some(error.to_string());
+                self.feedback = self.config.feedback.feedback(true, error.to_string());
+            }
+            Result::Ok(None) => {
+                let message = format!("'{}' does nothing here", input);
+                self.feedback = self.config.feedback.feedback(true, message);
+            }
+            Result::Ok(_) => {}
+        }
+        // the page already did what the input asked for
+        result.map(|action| action.filter(|action| *action != Action::Redraw))
+    }
+
+    /// The signal to give for the last input or action, if any. Taking it
+    /// clears it.
+    pub fn take_feedback(&mut self) -> Option<Feedback> {
+        self.feedback.take()
+    }
+
+    /// Locks the navigation stack to a single epic: the home page is dropped,
+    /// so leaving the epic page exits the app.
+    pub fn focus_epic(&mut self, epic_id: u32) -> Result<()> {
+        if !self.dao.read_db()?.epics.contains_key(&epic_id) {
+            return Err(anyhow!("could not find epic {}", epic_id));
+        }
+        self.replace_pages(vec![Box::new(EpicDetail {
+            dao: Rc::clone(&self.dao),
+            config: Rc::clone(&self.config),
+            status_filter: StatusFilter::default(),
+            epic_id,
+        })]);
+        self.focused_epic_id = Some(epic_id);
+        Ok(())
+    }
+
+    /// Builds the stack a user would have walked through to reach the item:
+    /// home, then the epic, then the story.
+    pub fn open_item(&mut self, item_id: u32) -> Result<()> {
+        let db_state = self.dao.read_db()?;
+        let alias = item_id;
+        let item_id = db_state.resolve(item_id).unwrap_or(item_id);
+        if item_id != alias {
+            self.notice = Some(format!(
+                "{} is now {}",
+                self.config.id_format.format(alias),
+                self.config.id_format.format(item_id)
+            ));
+        }
+        let epic_id = if db_state.epics.contains_key(&item_id) {
+            item_id
+        } else {
+            db_state
+                .epics
+                .iter()
+                .find(|(_, epic)| epic.stories.contains(&item_id))
+                .map(|(epic_id, _)| *epic_id)
+                .ok_or_else(|| anyhow!("could not find epic or story {}", item_id))?
+        };
+        let mut pages: Vec<Box<dyn Page>> = vec![
+            Box::new(HomePage {
+                dao: Rc::clone(&self.dao),
+                config: Rc::clone(&self.config),
+                status_filter: StatusFilter::default(),
+            }),
+            Box::new(EpicDetail {
+                dao: Rc::clone(&self.dao),
+                config: Rc::clone(&self.config),
+                status_filter: StatusFilter::default(),
+                epic_id,
+            }),
+        ];
+        if item_id != epic_id {
+            pages.push(Box::new(StoryDetail {
+                dao: Rc::clone(&self.dao),
+                config: Rc::clone(&self.config),
+                time_format_toggled: false,
+                story_id: item_id,
+                epic_id,
+            }));
+        }
+        self.focused_epic_id = None;
+        self.replace_pages(pages);
+        Ok(())
+    }
+
+    pub fn get_focus_header(&self) -> Result<Option<String>> {
+        let epic_id = match self.focused_epic_id {
+            Some(epic_id) => epic_id,
+            None => return Ok(None),
+        };
+        let db_state = self.dao.read_db()?;
+        let epic = db_state
+            .epics
+            .get(&epic_id)
+            .ok_or_else(|| anyhow!("could not find focused epic!"))?;
+        Ok(Some(format!(
+            "FOCUS MODE | epic {} - {} | {}",
+            self.config.id_format.format(epic_id),
+            epic.name,
+            epic.status
+        )))
+    }
+
+    pub fn get_undo_banner(&self) -> Option<String> {
+        self.undo
+            .as_ref()
+            .map(|undo| format!("{} {}. Press u to undo.", undo.verb, undo.description))
+    }
+
+    /// Status bar shown under every page: where the user is, how much work is
+    /// open there and the last error, if any.
+    pub fn get_footer(&self) -> Result<String> {
+        let db_state = self.dao.read_db()?;
+        let (context, story_ids): (String, Vec<u32>) = match self.current_epic_id() {
+            Some(epic_id) => {
+                let epic = db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic!"))?;
+                (
+                    format!(
+                        "EPIC {} - {}",
+                        self.config.id_format.format(epic_id),
+                        epic.name
+                    ),
+                    epic.stories.clone(),
+                )
+            }
+            None => (
+                "BOARD".to_owned(),
+                db_state.stories.keys().copied().collect(),
+            ),
+        };
+        let count = |status: Status| {
+            story_ids
+                .iter()
+                .filter_map(|id| db_state.stories.get(id))
+                .filter(|story| story.status == status)
+                .count()
+        };
+        let mut footer = format!(
+            "{} | {} open | {} in progress",
+            context,
+            count(Status::Open),
+            count(Status::InProgress)
+        );
+        if let Some(notice) = &self.notice {
+            footer.push_str(&format!(" | {}", notice));
+        }
+        if let Some(error) = &self.last_error {
+            footer.push_str(&format!(" | last error: {}", error));
+        }
+        Ok(footer)
+    }
+
+    /// Lets the user's script react to what happens on the board.
+    pub fn set_scripts(&mut self, scripts: Scripts) {
+        self.scripts = Some(scripts);
+    }
+
+    /// Backs the board up before epics are deleted or merged and before
+    /// plugins change it.
+    pub fn set_backups(&mut self, backups: BackupPolicy) {
+        self.backups = Some(backups);
+    }
+
+    /// Enables watching stories. The workspace is the one on screen, which
+    /// the watched stories are recorded against.
+    pub fn set_watchlist(&mut self, watchlist: Watchlist, workspace: Option<String>) {
+        self.watchlist = Some(watchlist);
+        self.workspace = workspace;
+    }
+
+    /// The workspace the user asked to switch to, which also closed every
+    /// page. Taking it clears it.
+    pub fn take_workspace_switch(&mut self) -> Option<String> {
+        self.workspace_switch.take()
+    }
+
+    /// Opens the reminders page on top of the current one when any reminder
+    /// is due, so they are seen on startup.
+    pub fn show_due_reminders(&mut self) -> Result<()> {
+        if !due_reminders(&self.dao.read_db()?, Utc::now()).is_empty() {
+            self.push_page(self.reminders_page());
+        }
+        Ok(())
+    }
+
+    fn reminders_page(&self) -> Box<dyn Page> {
+        Box::new(RemindersPage {
+            dao: Rc::clone(&self.dao),
+            config: Rc::clone(&self.config),
+            selected: 0,
+        })
+    }
+
+    pub fn start_tour(&mut self, tour: Tour) {
+        self.tour = Some(tour);
+    }
+
+    pub fn get_tour_instruction(&self) -> Option<String> {
+        self.tour.as_ref().map(|tour| tour.current_instruction())
+    }
+
+    fn open_story(&mut self, story_id: u32) -> Result<()> {
+        let epic_id = self
+            .dao
+            .read_db()?
+            .epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(epic_id, _)| *epic_id)
+            .ok_or_else(|| anyhow!("could not find the epic of story {}", story_id))?;
+        self.push_page(Box::new(StoryDetail {
+            dao: Rc::clone(&self.dao),
+            config: Rc::clone(&self.config),
+            time_format_toggled: false,
+            story_id,
+            epic_id,
+        }));
+        Ok(())
+    }
+
+    /// Asks which comment to act on, accepting `C-3` as well as `3`.
+    fn prompt_comment_number(&self, purpose: &str) -> Result<Option<usize>> {
+        let Some(input) = (self.prompts.comment_number)(purpose) else {
+            return Ok(None);
+        };
+        let input = input.trim();
+        let number = input
+            .strip_prefix("C-")
+            .or_else(|| input.strip_prefix("c-"))
+            .unwrap_or(input)
+            .parse::<usize>()
+            .map_err(|_| anyhow!("'{}' is not a comment number", input))?;
+        Ok(Some(number))
+    }
+
+    fn current_epic_id(&self) -> Option<u32> {
+        let page = self.pages.last()?.as_any();
+        if let Some(epic_detail) = page.downcast_ref::<EpicDetail>() {
+            return Some(epic_detail.epic_id);
+        }
+        page.downcast_ref::<StoryDetail>()
+            .map(|story_detail| story_detail.epic_id)
+    }
+
+    fn leave_focus(&mut self) {
+        if let Some(epic_id) = self.focused_epic_id.take() {
+            self.replace_pages(vec![
+                Box::new(HomePage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    status_filter: StatusFilter::default(),
+                }),
+                Box::new(EpicDetail {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    status_filter: StatusFilter::default(),
+                    epic_id,
+                }),
+            ]);
+        }
+    }
+
+    fn epic_deletion_consequences(&self, epic_id: u32) -> Result<Vec<String>> {
+        let db_state = self.dao.read_db()?;
+        let mut consequences = vec![];
+        if let Some(epic) = db_state.epics.get(&epic_id) {
+            if !epic.stories.is_empty() {
+                consequences.push(format!(
+                    "its {} stories will also be deleted",
+                    epic.stories.len()
+                ));
+            }
+        }
+        if self.focused_epic_id == Some(epic_id) {
+            consequences.push("focus mode will end".to_owned());
+        }
+        Ok(consequences)
+    }
+
+    fn story_deletion_consequences(&self, story_id: u32) -> Result<Vec<String>> {
+        let db_state = self.dao.read_db()?;
+        let mut consequences = vec![];
+        if let Some(story) = db_state.stories.get(&story_id) {
+            if !story.comments.is_empty() {
+                consequences.push(format!(
+                    "its {} comments will also be deleted",
+                    story.comments.len()
+                ));
+            }
+        }
+        Ok(consequences)
+    }
+
+    fn push_page(&mut self, mut page: Box<dyn Page>) {
+        if let Some(current_page) = self.pages.last_mut() {
+            current_page.on_leave();
+        }
+        page.on_enter();
+        self.pages.push(page);
+    }
+
+    fn pop_page(&mut self) {
+        if let Some(mut page) = self.pages.pop() {
+            page.on_leave();
+        }
+        if let Some(current_page) = self.pages.last_mut() {
+            current_page.on_enter();
+        }
+    }
+
+    fn replace_pages(&mut self, pages: Vec<Box<dyn Page>>) {
+        if let Some(current_page) = self.pages.last_mut() {
+            current_page.on_leave();
+        }
+        self.pages = pages;
+        if let Some(current_page) = self.pages.last_mut() {
+            current_page.on_enter();
+        }
+    }
+
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
-        match action {
+        self.notice = None;
+        let result = self.apply_action(action);
+        match &result {
+            Result::Ok(()) => {
+                self.last_error = None;
+                if let Some(notice) = &self.notice {
+                    self.feedback = self.config.feedback.feedback(false, notice.clone());
+                }
+            }
+            Err(error) => {
+                self.last_error = Some(format!("{:#}", error));
+                self.feedback = self.config.feedback.feedback(true, format!("{:#}", error));
+            }
+        }
+        result
+    }
+
+    fn apply_action(&mut self, action: Action) -> Result<()> {
+        match action.clone() {
             Action::NavigateToEpicDetail { epic_id } => {
-                self.pages.push(Box::new(EpicDetail {
+                self.push_page(Box::new(EpicDetail {
                     dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    status_filter: StatusFilter::default(),
                     epic_id,
                 }));
             }
             Action::NavigateToStoryDetail { epic_id, story_id } => {
-                self.pages.push(Box::new(StoryDetail {
+                self.push_page(Box::new(StoryDetail {
                     dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    time_format_toggled: false,
                     story_id,
                     epic_id,
                 }));
             }
             Action::NavigateToPreviousPage => {
-                if !self.pages.is_empty() {
-                    self.pages.pop();
+                self.pop_page();
+                let depth = self.recipe.as_ref().map(|run| run.depth);
+                if depth.is_some_and(|depth| self.pages.len() <= depth) {
+                    self.continue_recipe()?;
                 }
             }
-            Action::CreateEpic => {
+            Action::NavigateToQueue => {
+                self.push_page(Box::new(QueuePage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    selected: 0,
+                }));
+            }
+            Action::NavigateToDependencies { epic_id } => {
+                self.push_page(Box::new(DependencyPage {
+                    epic_id,
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToTimeline { epic_id } => {
+                self.push_page(Box::new(EpicTimeline {
+                    epic_id,
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToBurnup { epic_id } => {
+                self.push_page(Box::new(EpicBurnup {
+                    epic_id,
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToRefinement => {
+                self.push_page(Box::new(RefinementPage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    position: 0,
+                }));
+            }
+            Action::NavigateToReminders => {
+                self.push_page(self.reminders_page());
+            }
+            Action::NavigateToForecast => {
+                self.push_page(Box::new(ForecastPage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToWatchlist => {
+                let watchlist = self
+                    .watchlist
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("the watchlist is not available"))?;
+                let entries = collect_watchlist(
+                    watchlist,
+                    |workspace| {
+                        // unsaved edits of the board on screen count too
+                        if workspace == self.workspace.as_deref() {
+                            return self.dao.read_db();
+                        }
+                        let path = self.config.database_path(workspace)?;
+                        JSONFileJiraDAOAdapter { path }.retrieve()
+                    },
+                    Utc::now(),
+                );
+                self.push_page(Box::new(WatchlistPage {
+                    entries,
+                    workspace: self.workspace.clone(),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToGrooming => {
+                let count = self.config.grooming_count.unwrap_or(DEFAULT_GROOMING_COUNT);
+                let story_ids = pick_for_grooming(&self.dao.read_db()?, count, Utc::now());
+                self.push_page(Box::new(GroomingPage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    story_ids,
+                    position: 0,
+                }));
+            }
+            Action::GroomStory { story_id, outcome } => {
+                let notice = match outcome {
+                    GroomOutcome::Close => {
+                        self.ensure_can_transition(story_id, &Status::Closed)?;
+                        let comment = Comment::new("Closed during grooming".to_owned());
+                        self.dao
+                            .close_story(story_id, Status::Closed, comment)
+                            .with_context(|| anyhow!("failed to close story"))?;
+                        "closed"
+                    }
+                    GroomOutcome::MarkStale => {
+                        let comment = Comment::new("Marked stale during grooming".to_owned());
+                        self.dao
+                            .add_comment(story_id, comment)
+                            .with_context(|| anyhow!("failed to mark story as stale"))?;
+                        "marked stale"
+                    }
+                    GroomOutcome::Bump => {
+                        self.dao
+                            .queue_story_first(story_id)
+                            .with_context(|| anyhow!("failed to bump story"))?;
+                        "moved to the top of next up"
+                    }
+                };
+                self.notice = Some(format!(
+                    "{} {}",
+                    self.config.id_format.format(story_id),
+                    notice
+                ));
+            }
+            Action::QueueStory { story_id } => {
+                self.dao
+                    .queue_story(story_id)
+                    .with_context(|| anyhow!("failed to queue story"))?;
+                self.notice = Some("added to the next up queue".to_owned());
+            }
+            Action::UnqueueStory { story_id } => {
+                self.dao
+                    .unqueue_story(story_id)
+                    .with_context(|| anyhow!("failed to remove story from the queue"))?;
+            }
+            Action::MoveQueuedStory { story_id, offset } => {
                 self.dao
-                    .create_epic((self.prompts.create_epic)())
+                    .move_queued_story(story_id, offset)
+                    .with_context(|| anyhow!("failed to reorder the queue"))?;
+            }
+            Action::JumpToItem { item_id } => {
+                self.open_item(item_id)?;
+            }
+            Action::OpenQueuedStory { story_id } => {
+                self.open_story(story_id)?;
+            }
+            Action::StartNextQueuedStory => {
+                match self
+                    .dao
+                    .start_next_queued_story()
+                    .with_context(|| anyhow!("failed to start the next story"))?
+                {
+                    Some(story_id) => self.open_story(story_id)?,
+                    None => self.notice = Some("the next up queue is empty".to_owned()),
+                }
+            }
+            Action::NavigateToAliases => {
+                self.push_page(Box::new(AliasesPage {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::NavigateToRecipes => {
+                self.push_page(Box::new(RecipesPage {
+                    config: Rc::clone(&self.config),
+                }));
+            }
+            Action::RunRecipe { index } => {
+                let recipe = available(&self.config.recipes)
+                    .into_iter()
+                    .nth(index)
+                    .ok_or_else(|| anyhow!("no recipe number {}", index + 1))?;
+                self.recipe = Some(RecipeRun::new(recipe));
+                self.continue_recipe()?;
+            }
+            Action::PruneAliases => {
+                let pruned = self
+                    .dao
+                    .prune_aliases()
+                    .with_context(|| anyhow!("failed to prune aliases"))?;
+                self.notice = Some(format!("pruned {} dead aliases", pruned));
+            }
+            Action::Flush => {
+                self.dao
+                    .flush()
+                    .with_context(|| anyhow!("failed to save the board"))?;
+                self.notice = Some("board saved".to_owned());
+            }
+            Action::CreateEpic => {
+                let Some(epic) = (self.prompts.create_epic)() else {
+                    return Ok(());
+                };
+                let epic_id = self
+                    .dao
+                    .create_epic(epic)
                     .with_context(|| anyhow!("failed to create a new epic"))?;
+                self.run_hook(|scripts, board| scripts.on_epic_created(board, epic_id))?;
             }
             Action::UpdateEpicStatus { epic_id } => {
-                if let Some(status) = (self.prompts.update_status)() {
-                    self.dao
-                        .update_epic_status(epic_id, status)
-                        .with_context(|| anyhow!("failed to update epic"))?;
+                let Some(status) = (self.prompts.update_status)(&Status::ALL) else {
+                    return Ok(());
+                };
+                self.dao
+                    .update_epic_status(epic_id, status)
+                    .with_context(|| anyhow!("failed to update epic"))?;
+            }
+            Action::EditEpic { epic_id } => {
+                let snapshot = self.dao.read_db()?;
+                let epic = snapshot
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic!"))?;
+                let dao = Rc::clone(&self.dao);
+                let edited = self.edit_fields(
+                    &[("Name", &epic.name), ("Description", &epic.description)],
+                    |field, value| match field {
+                        "Name" => dao.rename_epic(epic_id, value),
+                        _ => dao.update_epic_description(epic_id, value),
+                    },
+                )?;
+                if edited {
+                    let description = format!(
+                        "epic {} {}",
+                        self.config.id_format.format(epic_id),
+                        epic.name
+                    );
+                    self.undo = Some(Undo {
+                        verb: "Edited",
+                        description,
+                        snapshot,
+                    });
                 }
             }
-            Action::DeleteEpic { epic_id } => {
-                if (self.prompts.delete_epic)() {
-                    self.dao
-                        .delete_epic(epic_id)
-                        .with_context(|| anyhow!("failed to delete epic!"))?;
-                    if !self.pages.is_empty() {
-                        self.pages.pop();
+            Action::ToggleEpicFocus { epic_id } => {
+                if self.focused_epic_id == Some(epic_id) {
+                    self.leave_focus();
+                } else {
+                    self.focus_epic(epic_id)?;
+                }
+            }
+            Action::YankEpic { epic_id } => {
+                let db_state = self.dao.read_db()?;
+                let epic = db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic!"))?;
+                let key = self.config.id_format.format(epic_id);
+                (self.clipboard)(
+                    &self
+                        .config
+                        .yank_format
+                        .summary(&key, &epic.name, &epic.status),
+                )?;
+            }
+            Action::SplitEpic { epic_id } => {
+                let Some((name, input)) = (self.prompts.split_epic)() else {
+                    return Ok(());
+                };
+                let story_ids = input
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|token| !token.is_empty())
+                    .map(|token| {
+                        self.config
+                            .id_format
+                            .parse(token)
+                            .ok_or_else(|| anyhow!("'{}' is not a valid story id", token))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let summary = format!(
+                    "split {} stories off epic {}",
+                    story_ids.len(),
+                    self.config.id_format.format(epic_id)
+                );
+                if !self.confirm_bulk(&summary, story_ids.len()) {
+                    return Ok(());
+                }
+                let restore = if story_ids.len() > self.bulk_limit() {
+                    self.back_up("split-epic")?
+                } else {
+                    None
+                };
+                let new_epic_id = self
+                    .dao
+                    .split_epic(epic_id, &story_ids, name)
+                    .with_context(|| anyhow!("failed to split epic"))?;
+                self.log_bulk(&summary, story_ids.len(), restore.as_deref())?;
+                self.notice = Some(format!(
+                    "moved {} stories into {}",
+                    story_ids.len(),
+                    self.config.id_format.format(new_epic_id)
+                ));
+            }
+            Action::MergeEpic { epic_id } => {
+                let Some(input) = (self.prompts.merge_target)() else {
+                    return Ok(());
+                };
+                let target_id = self
+                    .config
+                    .id_format
+                    .parse(input.trim())
+                    .ok_or_else(|| anyhow!("'{}' is not a valid epic id", input.trim()))?;
+                // fails early on a missing or identical epic, before the preview
+                self.dao.preview_epic_merge(epic_id, target_id)?;
+                self.push_page(Box::new(EpicMergePreview {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    source_id: epic_id,
+                    target_id,
+                }));
+            }
+            Action::ConfirmEpicMerge {
+                source_id,
+                target_id,
+            } => {
+                let source_key = self.config.id_format.format(source_id);
+                let summary = format!(
+                    "merge epic {} into {}",
+                    source_key,
+                    self.config.id_format.format(target_id)
+                );
+                let count = self.dao.get_epic(source_id)?.stories.len() + 1;
+                if !self.confirm_bulk(&summary, count) {
+                    return Ok(());
+                }
+                let restore = self.back_up("merge-epic")?;
+                let snapshot = self.dao.read_db()?;
+                self.dao
+                    .merge_epics(source_id, target_id)
+                    .with_context(|| anyhow!("failed to merge epics"))?;
+                self.log_bulk(&summary, count, restore.as_deref())?;
+                let description = format!(
+                    "epic {} {} into {} {}",
+                    source_key,
+                    snapshot.epics[&source_id].name,
+                    self.config.id_format.format(target_id),
+                    snapshot.epics[&target_id].name
+                );
+                self.undo = Some(Undo {
+                    verb: "Merged",
+                    description,
+                    snapshot,
+                });
+                if self.focused_epic_id == Some(source_id) {
+                    self.focused_epic_id = None;
+                }
+                // the preview and any page of the deleted epic
+                while let Some(page) = self.pages.last() {
+                    let page = page.as_any();
+                    let shows_source = page.is::<EpicMergePreview>()
+                        || page
+                            .downcast_ref::<EpicDetail>()
+                            .is_some_and(|page| page.epic_id == source_id);
+                    if !shows_source {
+                        break;
                     }
+                    self.pop_page();
+                }
+                let mut notice = format!(
+                    "merged {} into {}",
+                    source_key,
+                    self.config.id_format.format(target_id)
+                );
+                if let Some(restore) = restore {
+                    notice.push_str(&format!(", restore with: {}", restore));
+                }
+                self.notice = Some(notice);
+                self.push_page(Box::new(EpicDetail {
+                    dao: Rc::clone(&self.dao),
+                    config: Rc::clone(&self.config),
+                    status_filter: StatusFilter::default(),
+                    epic_id: target_id,
+                }));
+            }
+            Action::ExportEpicReport { epic_id } => {
+                let suggested = format!("epic-{}.html", self.config.id_format.format(epic_id));
+                let Some(path) = (self.prompts.export_path)(&suggested) else {
+                    return Ok(());
+                };
+                let html = render_epic_html(&self.dao.read_db()?, epic_id, &self.config)?;
+                fs::write(&path, html)
+                    .with_context(|| anyhow!("failed to write report to {}", path))?;
+                self.notice = Some(format!("exported to {}", path));
+            }
+            Action::DeleteEpic { epic_id } => {
+                let consequences = self.epic_deletion_consequences(epic_id)?;
+                if !(self.prompts.confirm)("epic", &consequences) {
+                    return Ok(());
+                }
+                let summary = format!("delete epic {}", self.config.id_format.format(epic_id));
+                // a missing epic fails in the dao, with its usual error
+                let count = self
+                    .dao
+                    .read_db()?
+                    .epics
+                    .get(&epic_id)
+                    .map_or(1, |epic| epic.stories.len() + 1);
+                if !self.confirm_bulk(&summary, count) {
+                    return Ok(());
+                }
+                let restore = self.back_up("delete-epic")?;
+                let snapshot = self.dao.read_db()?;
+                self.dao
+                    .delete_epic(epic_id)
+                    .with_context(|| anyhow!("failed to delete epic!"))?;
+                self.log_bulk(&summary, count, restore.as_deref())?;
+                if let Some(restore) = restore {
+                    self.notice = Some(format!("backed up, restore with: {}", restore));
+                }
+                let description = format!(
+                    "epic {} {}",
+                    self.config.id_format.format(epic_id),
+                    snapshot.epics[&epic_id].name
+                );
+                self.undo = Some(Undo {
+                    verb: "Deleted",
+                    description,
+                    snapshot,
+                });
+                if self.focused_epic_id == Some(epic_id) {
+                    self.focused_epic_id = None;
                 }
+                self.pop_page();
             }
             Action::CreateStory { epic_id } => {
+                let Some(story) = (self.prompts.create_story)() else {
+                    return Ok(());
+                };
                 self.dao
-                    .create_story((self.prompts.create_story)(), epic_id)
+                    .create_story(story, epic_id)
                     .with_context(|| anyhow!("failed to create a new story"))?;
             }
             Action::UpdateStoryStatus { story_id } => {
-                if let Some(status) = (self.prompts.update_status)() {
-                    self.dao
-                        .update_story_status(story_id, status)
-                        .with_context(|| anyhow!("failed to update story"))?;
+                let story = self.dao.get_story(story_id)?;
+                let options = match self.config.workflow_for(story.kind.as_deref()) {
+                    Some(workflow) => workflow.next_statuses(&story.status),
+                    None => Status::ALL.to_vec(),
+                };
+                if options.is_empty() {
+                    return Err(anyhow!(
+                        "a {} story can't leave {}",
+                        story.kind.unwrap_or_default(),
+                        story.status
+                    ));
+                }
+                let Some(status) = (self.prompts.update_status)(&options) else {
+                    return Ok(());
+                };
+                self.ensure_can_transition(story_id, &status)?;
+                let closed = status.is_done();
+                self.dao
+                    .update_story_status(story_id, status)
+                    .with_context(|| anyhow!("failed to update story"))?;
+                if closed {
+                    self.run_hook(|scripts, board| scripts.on_story_closed(board, story_id))?;
                 }
             }
-            Action::DeleteStory { epic_id, story_id } => {
-                if (self.prompts.delete_story)() {
-                    self.dao
-                        .delete_story(epic_id, story_id)
-                        .with_context(|| anyhow!("failed to delete story"))?;
-                    if !self.pages.is_empty() {
-                        self.pages.pop();
+            Action::CloseStoryWithComment { story_id } => {
+                let Some((status, comment)) = (self.prompts.close_story)() else {
+                    return Ok(());
+                };
+                self.ensure_can_transition(story_id, &status)?;
+                self.dao
+                    .close_story(story_id, status, Comment::new(comment))
+                    .with_context(|| anyhow!("failed to close story"))?;
+                self.run_hook(|scripts, board| scripts.on_story_closed(board, story_id))?;
+            }
+            Action::RunPlugin { story_id } => {
+                let dir = plugins::plugins_dir(&self.config)
+                    .ok_or_else(|| anyhow!("no plugins directory"))?;
+                let names: Vec<String> = plugins::discover_plugins(&dir)?
+                    .into_iter()
+                    .map(|plugin| plugin.name)
+                    .collect();
+                if names.is_empty() {
+                    return Err(anyhow!("no plugins in {}", dir.display()));
+                }
+                let Some(name) = (self.prompts.plugin)(&names) else {
+                    return Ok(());
+                };
+                let plugin = plugins::find_plugin(&dir, name.trim())?;
+                self.back_up(&format!("plugin-{}", plugin.name))?;
+                let applied =
+                    plugins::run_on_board(&self.dao, &self.config, &plugin, Some(story_id))
+                        .with_context(|| anyhow!("plugin '{}' was not applied", plugin.name))?;
+                self.notice = Some(format!("{} applied {} changes", plugin.name, applied));
+            }
+            Action::SetStoryKind { story_id } => {
+                let Some(input) = (self.prompts.story_kind)(&self.config.story_kinds()) else {
+                    return Ok(());
+                };
+                let kind = match input.trim() {
+                    "-" => None,
+                    kind => {
+                        self.config.ensure_known_kind(kind)?;
+                        Some(kind.to_owned())
+                    }
+                };
+                self.dao
+                    .set_story_kind(story_id, kind)
+                    .with_context(|| anyhow!("failed to update story"))?;
+            }
+            Action::EstimateStory { story_id } => {
+                let scale = self.config.point_scale;
+                let Some(input) = (self.prompts.estimate)(&scale) else {
+                    return Ok(());
+                };
+                let points = match input.trim() {
+                    "-" => None,
+                    input => Some(scale.parse(input)?),
+                };
+                self.dao
+                    .set_story_points(story_id, points)
+                    .with_context(|| anyhow!("failed to update story"))?;
+            }
+            Action::EditStoryDescription { story_id } => {
+                let Some(description) = (self.prompts.description)() else {
+                    return Ok(());
+                };
+                self.dao
+                    .update_story_description(story_id, description)
+                    .with_context(|| anyhow!("failed to update story"))?;
+            }
+            Action::ToggleWatch { story_id } => {
+                let watchlist = self
+                    .watchlist
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("the watchlist is not available"))?;
+                let watched = watchlist.toggle(self.workspace.as_deref(), story_id)?;
+                let key = self.config.id_format.format(story_id);
+                self.notice = Some(if watched {
+                    format!("watching {}", key)
+                } else {
+                    format!("stopped watching {}", key)
+                });
+            }
+            Action::EditStory { story_id } => {
+                let snapshot = self.dao.read_db()?;
+                let story = snapshot
+                    .stories
+                    .get(&story_id)
+                    .ok_or_else(|| anyhow!("could not find story!"))?;
+                let dao = Rc::clone(&self.dao);
+                let edited = self.edit_fields(
+                    &[("Name", &story.name), ("Description", &story.description)],
+                    |field, value| match field {
+                        "Name" => dao.rename_story(story_id, value),
+                        _ => dao.update_story_description(story_id, value),
+                    },
+                )?;
+                if edited {
+                    let description = format!(
+                        "story {} {}",
+                        self.config.id_format.format(story_id),
+                        story.name
+                    );
+                    self.undo = Some(Undo {
+                        verb: "Edited",
+                        description,
+                        snapshot,
+                    });
+                }
+            }
+            Action::MarkStoryReady { story_id } => {
+                self.dao.set_story_ready(story_id, true)?;
+                self.notice = Some(format!(
+                    "{} is ready",
+                    self.config.id_format.format(story_id)
+                ));
+            }
+            Action::SetReminder { story_id } => {
+                let Some(input) = (self.prompts.reminder)() else {
+                    return Ok(());
+                };
+                let remind_at = parse_reminder(
+                    &input,
+                    Utc::now(),
+                    &self.config.time_display,
+                    &self.config.calendar,
+                )?;
+                if is_phrase(&input) {
+                    let question = format!(
+                        "'{}' is {}. Set the reminder?",
+                        input.trim(),
+                        self.config.time_display.format(&remind_at)
+                    );
+                    if !(self.prompts.approve)(&question) {
+                        return Ok(());
                     }
                 }
+                self.dao
+                    .set_reminder(story_id, Some(remind_at))
+                    .with_context(|| anyhow!("failed to set reminder"))?;
+                self.notice = Some(format!(
+                    "reminder set for {}",
+                    self.config.time_display.format(&remind_at)
+                ));
+            }
+            Action::DismissReminder { story_id } => {
+                self.dao
+                    .set_reminder(story_id, None)
+                    .with_context(|| anyhow!("failed to dismiss reminder"))?;
+                self.notice = Some("reminder dismissed".to_owned());
+            }
+            Action::YankStory { story_id } => {
+                let db_state = self.dao.read_db()?;
+                let story = db_state
+                    .stories
+                    .get(&story_id)
+                    .ok_or_else(|| anyhow!("could not find story!"))?;
+                let key = self.config.id_format.format(story_id);
+                (self.clipboard)(&self.config.yank_format.summary(
+                    &key,
+                    &story.name,
+                    &story.status,
+                ))?;
+            }
+            Action::CreateStoryBranch { story_id } => {
+                let repo = self
+                    .config
+                    .git_repo
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("no git repository configured"))?;
+                let db_state = self.dao.read_db()?;
+                let story = db_state
+                    .stories
+                    .get(&story_id)
+                    .ok_or_else(|| anyhow!("could not find story!"))?;
+                let branch = git::branch_name(&self.config.id_format.format(story_id), &story.name);
+                git::create_branch(Path::new(repo), &branch)
+                    .with_context(|| anyhow!("failed to create branch {}", branch))?;
+                self.notice = Some(format!("switched to new branch {}", branch));
+            }
+            Action::ToggleBlocker { story_id } => {
+                let Some(input) = (self.prompts.blocker)() else {
+                    return Ok(());
+                };
+                let blocker_id = self
+                    .config
+                    .id_format
+                    .parse(input.trim())
+                    .ok_or_else(|| anyhow!("'{}' is not a valid story id", input.trim()))?;
+                let linked = self
+                    .dao
+                    .toggle_blocker(story_id, blocker_id)
+                    .with_context(|| anyhow!("failed to update blockers"))?;
+                let blocker_key = self.config.id_format.format(blocker_id);
+                self.notice = Some(if linked {
+                    format!("now blocked by {}", blocker_key)
+                } else {
+                    format!("no longer blocked by {}", blocker_key)
+                });
+            }
+            Action::ToggleMirror { epic_id } => {
+                let Some(input) = (self.prompts.mirror)() else {
+                    return Ok(());
+                };
+                let story_id = self
+                    .config
+                    .id_format
+                    .parse(input.trim())
+                    .ok_or_else(|| anyhow!("'{}' is not a valid story id", input.trim()))?;
+                let mirrored = self
+                    .dao
+                    .toggle_mirror(epic_id, story_id)
+                    .with_context(|| anyhow!("failed to update mirrors"))?;
+                let story_key = self.config.id_format.format(story_id);
+                self.notice = Some(if mirrored {
+                    format!("now mirroring {}", story_key)
+                } else {
+                    format!("no longer mirroring {}", story_key)
+                });
+            }
+            Action::ToggleExternalBlocker { story_id } => {
+                let Some(input) = (self.prompts.external_blocker)() else {
+                    return Ok(());
+                };
+                let input = input.trim();
+                let (url, title) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+                if url.is_empty() {
+                    return Ok(());
+                }
+                let linked = self
+                    .dao
+                    .toggle_external_blocker(story_id, url, title.trim())
+                    .with_context(|| anyhow!("failed to update blockers"))?;
+                self.notice = Some(if linked {
+                    format!("now blocked by {}", url)
+                } else {
+                    format!("no longer blocked by {}", url)
+                });
+            }
+            Action::AddAcceptanceCriterion { story_id } => {
+                let Some(text) = (self.prompts.acceptance_criterion)() else {
+                    return Ok(());
+                };
+                let number = self
+                    .dao
+                    .add_acceptance_criterion(story_id, text)
+                    .with_context(|| anyhow!("failed to add acceptance criterion"))?;
+                self.notice = Some(format!("added AC-{}", number));
+            }
+            Action::ToggleAcceptanceCriterion { story_id } => {
+                let Some(input) = (self.prompts.criterion_number)() else {
+                    return Ok(());
+                };
+                let input = input.trim();
+                let number = input
+                    .strip_prefix("AC-")
+                    .unwrap_or(input)
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("'{}' is not a criterion number", input))?;
+                let done = self
+                    .dao
+                    .toggle_acceptance_criterion(story_id, number)
+                    .with_context(|| anyhow!("failed to update acceptance criterion"))?;
+                self.notice = Some(format!(
+                    "AC-{} {}",
+                    number,
+                    if done { "checked" } else { "unchecked" }
+                ));
+            }
+            Action::AddComment { story_id } => {
+                let Some(text) = (self.prompts.comment)() else {
+                    return Ok(());
+                };
+                self.dao
+                    .add_comment(story_id, Comment::new(text))
+                    .with_context(|| anyhow!("failed to add comment"))?;
+            }
+            Action::ReplyToComment { story_id } => {
+                let Some(number) = self.prompt_comment_number("reply to")? else {
+                    return Ok(());
+                };
+                let Some(text) = (self.prompts.comment)() else {
+                    return Ok(());
+                };
+                let reply = self
+                    .dao
+                    .reply_to_comment(story_id, number, text)
+                    .with_context(|| anyhow!("failed to reply"))?;
+                self.notice = Some(format!("C-{} replies to C-{}", reply, number));
+            }
+            Action::EditComment { story_id } => {
+                let Some(number) = self.prompt_comment_number("edit")? else {
+                    return Ok(());
+                };
+                let story = self.dao.get_story(story_id)?;
+                let current = story
+                    .comments
+                    .get(number.wrapping_sub(1))
+                    .ok_or_else(|| anyhow!("could not find C-{}", number))?;
+                let Some(text) = (self.prompts.edit_comment)(&current.text) else {
+                    return Ok(());
+                };
+                self.dao
+                    .edit_comment(story_id, number, text)
+                    .with_context(|| anyhow!("failed to edit comment"))?;
+            }
+            Action::DeleteComment { story_id } => {
+                let Some(number) = self.prompt_comment_number("delete")? else {
+                    return Ok(());
+                };
+                let story = self.dao.get_story(story_id)?;
+                let replies = story
+                    .comments
+                    .iter()
+                    .filter(|comment| comment.reply_to == Some(number))
+                    .count();
+                let consequences = if replies > 0 {
+                    vec![format!("its {} replies will move up a level", replies)]
+                } else {
+                    vec![]
+                };
+                if !(self.prompts.confirm)("comment", &consequences) {
+                    return Ok(());
+                }
+                self.dao
+                    .delete_comment(story_id, number)
+                    .with_context(|| anyhow!("failed to delete comment"))?;
+            }
+            Action::DeleteStory { epic_id, story_id } => {
+                let consequences = self.story_deletion_consequences(story_id)?;
+                if !(self.prompts.confirm)("story", &consequences) {
+                    return Ok(());
+                }
+                let snapshot = self.dao.read_db()?;
+                self.dao
+                    .delete_story(epic_id, story_id)
+                    .with_context(|| anyhow!("failed to delete story"))?;
+                let description = format!(
+                    "story {} {}",
+                    self.config.id_format.format(story_id),
+                    snapshot.stories[&story_id].name
+                );
+                self.undo = Some(Undo {
+                    verb: "Deleted",
+                    description,
+                    snapshot,
+                });
+                self.pop_page();
+            }
+            Action::UndoDeletion => {
+                let undo = self.undo.take().ok_or_else(|| anyhow!("nothing to undo"))?;
+                self.dao
+                    .replace_db(&undo.snapshot)
+                    .with_context(|| anyhow!("failed to restore {}", undo.description))?;
+                self.notice = Some(format!("restored {}", undo.description));
+            }
+            Action::SwitchWorkspace => {
+                let names = self.config.workspace_names();
+                if names.is_empty() {
+                    return Err(anyhow!("no workspaces are configured"));
+                }
+                let Some(name) = (self.prompts.workspace)(&names) else {
+                    return Ok(());
+                };
+                let name = name.trim();
+                self.config.database_path(Some(name))?;
+                self.workspace_switch = Some(name.to_owned());
+                self.replace_pages(vec![]);
             }
             Action::Exit => {
-                self.pages.clear();
+                self.replace_pages(vec![]);
+                self.dao
+                    .flush()
+                    .with_context(|| anyhow!("failed to save the board"))?;
+            }
+            Action::Redraw => {}
+        }
+
+        if let Some(tour) = &mut self.tour {
+            tour.observe(&action);
+        }
+
+        Ok(())
+    }
+
+    /// Runs the steps of the recipe in progress until one opens a page; the
+    /// next ones follow once that page is left. A failing step ends the
+    /// recipe.
+    fn continue_recipe(&mut self) -> Result<()> {
+        while let Some(run) = &mut self.recipe {
+            let name = run.name.clone();
+            let Some(step) = run.steps.pop_front() else {
+                self.recipe = None;
+                self.notice = Some(match self.notice.take() {
+                    Some(notice) => format!("{}, finished {}", notice, name),
+                    None => format!("finished {}", name),
+                });
+                break;
+            };
+            let depth = self.pages.len();
+            if let Err(error) = self.apply_action(step.action()) {
+                self.recipe = None;
+                return Err(error);
+            }
+            if self.pages.len() > depth {
+                if let Some(run) = self.recipe.as_mut().filter(|run| !run.steps.is_empty()) {
+                    run.depth = depth;
+                    self.notice = Some(format!("{}: [p] for the next step", name));
+                    break;
+                }
             }
         }
+        Ok(())
+    }
+
+    /// Offers each field pre-filled for editing, saving every change right
+    /// away, until the fields run out or one is cancelled. Returns whether
+    /// anything changed.
+    fn edit_fields(
+        &self,
+        fields: &[(&str, &str)],
+        mut save: impl FnMut(&str, String) -> Result<()>,
+    ) -> Result<bool> {
+        let mut edited = false;
+        for (field, current) in fields {
+            let Some(value) = (self.prompts.edit_field)(field, current) else {
+                break;
+            };
+            if value != *current {
+                save(field, value)
+                    .with_context(|| anyhow!("failed to save the {}", field.to_lowercase()))?;
+                edited = true;
+            }
+        }
+        Ok(edited)
+    }
+
+    /// Asks for the number of items to be typed before a change touching
+    /// more than the bulk limit. Returns whether to go ahead.
+    fn confirm_bulk(&self, summary: &str, count: usize) -> bool {
+        count <= self.bulk_limit()
+            || (self.prompts.confirm_bulk)(
+                &format!("This will {}, touching {} items.", summary, count),
+                &count.to_string(),
+            )
+    }
+
+    /// Records a bulk change in the activity log, with how to undo it.
+    fn log_bulk(&self, summary: &str, count: usize, restore: Option<&str>) -> Result<()> {
+        let Some(backups) = self.backups.as_ref().filter(|_| count > self.bulk_limit()) else {
+            return Ok(());
+        };
+        let mut entry = format!("{} ({} items)", summary, count);
+        if let Some(restore) = restore {
+            entry.push_str(&format!(", restore with: {}", restore));
+        }
+        backups.log_activity(&entry)
+    }
+
+    fn bulk_limit(&self) -> usize {
+        self.config.bulk_limit.unwrap_or(DEFAULT_BULK_LIMIT)
+    }
+
+    /// Backs the board up before a destructive change, returning the command
+    /// that restores it. Does nothing when backups are not set up.
+    fn back_up(&self, operation: &str) -> Result<Option<String>> {
+        self.backups
+            .as_ref()
+            .map(|backups| backups.back_up(&self.dao, operation))
+            .transpose()
+    }
+
+    /// Runs a script hook, showing what it returns as the notice. The action
+    /// already went through, so a failing script is reported the same way.
+    fn run_hook(
+        &mut self,
+        hook: impl Fn(&Scripts, &DBState) -> Result<Option<String>>,
+    ) -> Result<()> {
+        let Some(scripts) = &self.scripts else {
+            return Ok(());
+        };
+        match hook(scripts, &self.dao.read_db()?) {
+            Result::Ok(Some(message)) => self.notice = Some(message),
+            Result::Ok(None) => {}
+            Err(error) => self.notice = Some(format!("script error: {:#}", error)),
+        }
+        Ok(())
+    }
+
+    fn ensure_can_transition(&self, story_id: u32, status: &Status) -> Result<()> {
+        self.config
+            .ensure_can_transition(&self.dao.get_story(story_id)?, status)
+    }
+}
+
+// Private functions used for testing
+#[cfg(test)]
+impl Navigator {
+    fn get_page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn set_prompts(&mut self, prompts: Prompts) {
+        self.prompts = prompts;
+    }
+
+    fn set_clipboard(&mut self, clipboard: Box<CopyToClipboard>) {
+        self.clipboard = clipboard;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::write_behind::WriteBehindDatabase;
+    use crate::{
+        clipboard::YankFormat,
+        dao::test_utils::MockDB,
+        estimation::PointScale,
+        feedback::{FeedbackConfig, Signal},
+        fixtures::DbFixtureBuilder,
+        id_format::IdFormat,
+        models::{Epic, Status, Story},
+        ui::{EpicDetail, HomePage, Renderer, StoryDetail},
+    };
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    fn make_dao() -> Rc<JiraDAO> {
+        Rc::new(JiraDAO::new(Box::new(MockDB::new())))
+    }
+
+    fn make_sut() -> Navigator {
+        Navigator::new(make_dao(), Rc::new(Config::default()))
+    }
+
+    #[test]
+    fn should_start_on_home_page() {
+        let sut = make_sut();
+        let current_page = sut.get_current_page().unwrap();
+        let home_page = current_page.as_any().downcast_ref::<HomePage>();
+        assert_eq!(sut.get_page_count(), 1);
+        assert_eq!(home_page.is_some(), true);
+    }
+
+    #[test]
+    fn handle_action_should_navigate_pages() {
+        let mut sut = make_sut();
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        assert_eq!(sut.get_page_count(), 2);
+
+        let current_page = sut.get_current_page().unwrap();
+        let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
+        assert_eq!(epic_detail_page.is_some(), true);
+
+        sut.handle_action(Action::NavigateToStoryDetail {
+            epic_id: 1,
+            story_id: 2,
+        })
+        .unwrap();
+        assert_eq!(sut.get_page_count(), 3);
+
+        let current_page = sut.get_current_page().unwrap();
+        let story_detail_page = current_page.as_any().downcast_ref::<StoryDetail>();
+        assert_eq!(story_detail_page.is_some(), true);
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 2);
+
+        let current_page = sut.get_current_page().unwrap();
+        let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
+        assert_eq!(epic_detail_page.is_some(), true);
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 1);
+
+        let current_page = sut.get_current_page().unwrap();
+        let home_page = current_page.as_any().downcast_ref::<HomePage>();
+        assert_eq!(home_page.is_some(), true);
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 0);
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 0);
+    }
+
+    struct RecordingPage {
+        events: Rc<RefCell<Vec<&'static str>>>,
+        inputs: Vec<String>,
+    }
+
+    impl Page for RecordingPage {
+        fn draw_page(&self, _renderer: &mut dyn Renderer) -> Result<()> {
+            Ok(())
+        }
+
+        fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+            self.inputs.push(input.to_owned());
+            self.events.borrow_mut().push("input");
+            Ok(None)
+        }
+
+        fn on_enter(&mut self) {
+            self.events.borrow_mut().push("enter");
+        }
+
+        fn on_leave(&mut self) {
+            self.events.borrow_mut().push("leave");
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn navigator_should_call_page_lifecycle_hooks() {
+        let mut sut = make_sut();
+        let events = Rc::new(RefCell::new(vec![]));
+        sut.push_page(Box::new(RecordingPage {
+            events: Rc::clone(&events),
+            inputs: vec![],
+        }));
+        sut.handle_input("a").unwrap();
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        sut.handle_input("b").unwrap();
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+
+        assert_eq!(
+            *events.borrow(),
+            vec!["enter", "input", "leave", "enter", "input", "leave"]
+        );
+    }
+
+    #[test]
+    fn navigator_should_keep_page_state_between_inputs() {
+        let mut sut = make_sut();
+        sut.push_page(Box::new(RecordingPage {
+            events: Rc::new(RefCell::new(vec![])),
+            inputs: vec![],
+        }));
+        sut.handle_input("first").unwrap();
+        sut.handle_input("second").unwrap();
+
+        let page = sut.get_current_page().unwrap();
+        let recording_page = page.as_any().downcast_ref::<RecordingPage>().unwrap();
+        assert_eq!(recording_page.inputs, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn get_footer_should_show_context_and_counts() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story_status(Status::InProgress)
+            .with_epic("Search")
+            .with_story("Filters");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+
+        assert_eq!(sut.get_footer().unwrap(), "BOARD | 2 open | 1 in progress");
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        assert_eq!(
+            sut.get_footer().unwrap(),
+            "EPIC 4 - Search | 1 open | 0 in progress"
+        );
+    }
+
+    #[test]
+    fn get_footer_should_show_the_last_error_until_an_action_succeeds() {
+        let mut sut = make_sut();
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        sut.set_prompts(prompts);
+
+        assert_eq!(
+            sut.handle_action(Action::DeleteEpic { epic_id: 999 })
+                .is_err(),
+            true
+        );
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .contains("last error: failed to delete epic!"),
+            true
+        );
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_footer().unwrap().contains("last error"), false);
+    }
+
+    #[test]
+    fn handle_action_should_yank_items_in_the_configured_format() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story_status(Status::InProgress);
+        let (epic_id, story_id) = (
+            fixture.last_epic_id().unwrap(),
+            fixture.last_story_id().unwrap(),
+        );
+        let config = Config {
+            yank_format: YankFormat::Plain,
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(config));
+        let copied = Rc::new(RefCell::new(vec![]));
+        let copied_clone = Rc::clone(&copied);
+        sut.set_clipboard(Box::new(move |text| {
+            copied_clone.borrow_mut().push(text.to_owned());
+            Ok(())
+        }));
+
+        sut.handle_action(Action::YankEpic { epic_id }).unwrap();
+        sut.handle_action(Action::YankStory { story_id }).unwrap();
+
+        assert_eq!(
+            *copied.borrow(),
+            vec!["1 Payments [OPEN]", "2 Refunds [IN PROGRESS]"]
+        );
+    }
+
+    #[test]
+    fn handle_action_should_export_an_epic_report() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.html");
+        let path_clone = path.to_string_lossy().into_owned();
+        let mut prompts = Prompts::new();
+        prompts.export_path = Box::new(move |suggested| {
+            assert_eq!(suggested, "epic-1.html");
+            Some(path_clone.clone())
+        });
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::ExportEpicReport { epic_id })
+            .unwrap();
+
+        let html = fs::read_to_string(&path).unwrap();
+        assert_eq!(html.contains("Refunds"), true);
+        assert_eq!(sut.get_footer().unwrap().contains("exported to"), true);
+    }
+
+    #[test]
+    fn handle_action_should_split_epics() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_story("Disputes");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.split_epic = Box::new(|| Some(("Payments 2".to_owned(), "4, 3".to_owned())));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::SplitEpic { epic_id }).unwrap();
+
+        let db_state = dao.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].stories, vec![2]);
+        assert_eq!(db_state.epics[&5].stories, vec![3, 4]);
+        assert_eq!(
+            sut.get_footer().unwrap().contains("moved 2 stories into 5"),
+            true
+        );
+
+        let mut prompts = Prompts::new();
+        prompts.split_epic = Box::new(|| Some(("Payments 3".to_owned(), "2 x".to_owned())));
+        sut.set_prompts(prompts);
+        assert_eq!(
+            sut.handle_action(Action::SplitEpic { epic_id }).is_err(),
+            true
+        );
+        assert_eq!(dao.read_db().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn handle_action_should_toggle_blockers() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("build")
+            .with_story("release");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.blocker = Box::new(|| Some("2".to_owned()));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::ToggleBlocker { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().blocked_by, vec![2]);
+        assert_eq!(sut.get_footer().unwrap().contains("now blocked by 2"), true);
+
+        sut.handle_action(Action::ToggleBlocker { story_id })
+            .unwrap();
+        assert_eq!(
+            dao.get_story(story_id).unwrap().blocked_by,
+            Vec::<u32>::new()
+        );
+
+        let mut prompts = Prompts::new();
+        prompts.blocker = Box::new(|| Some("junk".to_owned()));
+        sut.set_prompts(prompts);
+        assert_eq!(
+            sut.handle_action(Action::ToggleBlocker { story_id })
+                .is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_follow_the_workflow_of_the_story_kind() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let chore = serde_json::from_str(r#"[{ "from": "Open", "to": ["Closed"] }]"#).unwrap();
+        let config = Config {
+            workflows: [("chore".to_owned(), chore)].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+        let mut prompts = Prompts::new();
+        prompts.story_kind = Box::new(|kinds| {
+            assert_eq!(kinds, ["chore".to_owned()]);
+            Some("chore".to_owned())
+        });
+        prompts.update_status = Box::new(|options| {
+            assert_eq!(options, [Status::Closed]);
+            options.first().cloned()
+        });
+        prompts.close_story = Box::new(|| Some((Status::Resolved, "done".to_owned())));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::SetStoryKind { story_id })
+            .unwrap();
+        assert_eq!(
+            sut.handle_action(Action::CloseStoryWithComment { story_id })
+                .unwrap_err()
+                .to_string(),
+            "a chore story can't go from OPEN to RESOLVED"
+        );
+        sut.handle_action(Action::UpdateStoryStatus { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().status, Status::Closed);
+        assert_eq!(
+            sut.handle_action(Action::UpdateStoryStatus { story_id })
+                .is_err(),
+            true
+        );
+
+        let mut prompts = Prompts::new();
+        prompts.story_kind = Box::new(|_| Some("bug".to_owned()));
+        sut.set_prompts(prompts);
+        assert_eq!(
+            sut.handle_action(Action::SetStoryKind { story_id })
+                .is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_block_resolving_with_unchecked_criteria() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let config = Config {
+            require_acceptance_criteria: true,
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+        let mut prompts = Prompts::new();
+        prompts.acceptance_criterion = Box::new(|| Some("logs in".to_owned()));
+        prompts.criterion_number = Box::new(|| Some("AC-1".to_owned()));
+        prompts.update_status = Box::new(|_| Some(Status::Resolved));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::AddAcceptanceCriterion { story_id })
+            .unwrap();
+        let result = sut.handle_action(Action::UpdateStoryStatus { story_id });
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "can't resolve while AC-1 unchecked"
+        );
+        assert_eq!(dao.get_story(story_id).unwrap().status, Status::Open);
+
+        sut.handle_action(Action::ToggleAcceptanceCriterion { story_id })
+            .unwrap();
+        assert_eq!(sut.get_footer().unwrap().contains("AC-1 checked"), true);
+        sut.handle_action(Action::UpdateStoryStatus { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().status, Status::Resolved);
+    }
+
+    #[test]
+    fn undo_should_restore_a_deleted_epic_on_the_next_key_press() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        sut.set_prompts(prompts);
+        let state_before = dao.read_db().unwrap();
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        sut.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+        assert_eq!(
+            sut.get_undo_banner(),
+            Some("Deleted epic 1 Payments. Press u to undo.".to_owned())
+        );
+
+        let action = sut.handle_input("u").unwrap().unwrap();
+        assert_eq!(action, Action::UndoDeletion);
+        sut.handle_action(action).unwrap();
+        assert_eq!(dao.read_db().unwrap(), state_before);
+        assert_eq!(sut.get_undo_banner(), None);
+    }
+
+    #[test]
+    fn handle_action_should_edit_story_fields_with_undo() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.edit_field = Box::new(|field, current| match field {
+            "Name" => Some(format!("{} by card", current)),
+            _ => Some(current.to_owned()),
+        });
+        sut.set_prompts(prompts);
+        let state_before = dao.read_db().unwrap();
+
+        sut.handle_action(Action::EditStory { story_id }).unwrap();
+
+        let story = dao.get_story(story_id).unwrap();
+        assert_eq!(story.name, "Refunds by card");
+        assert_eq!(
+            story.description,
+            state_before.stories[&story_id].description
+        );
+        assert_eq!(
+            sut.get_undo_banner(),
+            Some(format!(
+                "Edited story {} Refunds. Press u to undo.",
+                story_id
+            ))
+        );
+        let action = sut.handle_input("u").unwrap().unwrap();
+        sut.handle_action(action).unwrap();
+        assert_eq!(dao.read_db().unwrap(), state_before);
+
+        let mut prompts = Prompts::new();
+        prompts.edit_field = Box::new(|_, _| None);
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::EditEpic { epic_id: 1 }).unwrap();
+        assert_eq!(sut.get_undo_banner(), None);
+    }
+
+    #[test]
+    fn handle_action_should_list_watched_stories() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds");
+        let story_id = fixture.last_story_id().unwrap();
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+        let watchlist = Watchlist::load(dir.path().join("watchlist.json")).unwrap();
+        sut.set_watchlist(watchlist, None);
+
+        sut.handle_action(Action::ToggleWatch { story_id }).unwrap();
+        assert_eq!(sut.get_footer().unwrap().contains("watching 2"), true);
+        sut.handle_action(Action::NavigateToWatchlist).unwrap();
+
+        let current_page = sut.get_current_page().unwrap();
+        let page = current_page.as_any().downcast_ref::<WatchlistPage>();
+        let names: Vec<String> = page
+            .map(|page| {
+                page.entries
+                    .iter()
+                    .map(|entry| entry.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        assert_eq!(names, vec!["Refunds".to_owned()]);
+    }
+
+    #[test]
+    fn undo_should_expire_after_any_other_key() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds");
+        let (epic_id, story_id) = (
+            fixture.last_epic_id().unwrap(),
+            fixture.last_story_id().unwrap(),
+        );
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::DeleteStory { epic_id, story_id })
+            .unwrap();
+        assert_eq!(sut.get_undo_banner().is_some(), true);
+
+        sut.handle_input("junk").unwrap();
+        assert_eq!(sut.get_undo_banner(), None);
+        assert_eq!(sut.handle_action(Action::UndoDeletion).is_err(), true);
+    }
+
+    #[test]
+    fn handle_action_should_start_the_next_queued_story() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        sut.handle_action(Action::StartNextQueuedStory).unwrap();
+        assert_eq!(sut.get_footer().unwrap().contains("queue is empty"), true);
+
+        sut.handle_action(Action::QueueStory { story_id }).unwrap();
+        sut.handle_action(Action::NavigateToQueue).unwrap();
+        sut.handle_action(Action::StartNextQueuedStory).unwrap();
+
+        assert_eq!(sut.get_page_count(), 3);
+        let current_page = sut.get_current_page().unwrap();
+        let story_detail = current_page.as_any().downcast_ref::<StoryDetail>();
+        assert_eq!(story_detail.map(|page| page.story_id), Some(story_id));
+        assert_eq!(
+            dao.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn run_recipe_should_go_on_once_each_page_is_left() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        sut.handle_action(Action::QueueStory { story_id }).unwrap();
+
+        sut.handle_action(Action::NavigateToRecipes).unwrap();
+        sut.handle_action(Action::RunRecipe { index: 0 }).unwrap();
+        assert_eq!(sut.get_page_count(), 3);
+        let current_page = sut.get_current_page().unwrap();
+        assert_eq!(current_page.as_any().is::<RemindersPage>(), true);
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .ends_with("| start my day: [p] for the next step"),
+            true
+        );
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 3);
+        let current_page = sut.get_current_page().unwrap();
+        let story_detail = current_page.as_any().downcast_ref::<StoryDetail>();
+        assert_eq!(story_detail.map(|page| page.story_id), Some(story_id));
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .ends_with("| finished start my day"),
+            true
+        );
+
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 2);
+        assert_eq!(
+            sut.handle_action(Action::RunRecipe { index: 1 }).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_apply_grooming_outcomes() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("first")
+            .with_story_created_at(None)
+            .with_story("second")
+            .with_story_created_at(None);
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        sut.handle_action(Action::NavigateToGrooming).unwrap();
+        let current_page = sut.get_current_page().unwrap();
+        let grooming = current_page.as_any().downcast_ref::<GroomingPage>();
+        assert_eq!(grooming.map(|page| page.story_ids.len()), Some(2));
+
+        sut.handle_action(Action::GroomStory {
+            story_id: 2,
+            outcome: GroomOutcome::Close,
+        })
+        .unwrap();
+        sut.handle_action(Action::GroomStory {
+            story_id: 3,
+            outcome: GroomOutcome::Bump,
+        })
+        .unwrap();
+        assert_eq!(
+            sut.get_footer().unwrap().contains("3 moved to the top"),
+            true
+        );
+
+        let db_state = dao.read_db().unwrap();
+        assert_eq!(db_state.stories[&2].status, Status::Closed);
+        assert_eq!(db_state.queue, vec![3]);
+    }
+
+    #[test]
+    fn handle_action_should_not_close_stories_the_workflow_keeps_open() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        dao.set_story_kind(story_id, Some("chore".to_owned()))
+            .unwrap();
+        let chore = serde_json::from_str(r#"[{ "from": "Open", "to": ["Resolved"] }]"#).unwrap();
+        let config = Config {
+            workflows: [("chore".to_owned(), chore)].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+
+        let result = sut.handle_action(Action::GroomStory {
+            story_id,
+            outcome: GroomOutcome::Close,
+        });
+
+        assert_eq!(result.is_err(), true);
+        assert_eq!(dao.get_story(story_id).unwrap().status, Status::Open);
+    }
+
+    #[test]
+    fn handle_action_should_refine_stories_until_ready() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.description = Box::new(|| Some("Refund card payments".to_owned()));
+        prompts.acceptance_criterion = Box::new(|| Some("refunds in full".to_owned()));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::NavigateToRefinement).unwrap();
+        sut.handle_action(Action::EditStoryDescription { story_id })
+            .unwrap();
+        assert_eq!(
+            sut.handle_action(Action::MarkStoryReady { story_id })
+                .unwrap_err()
+                .to_string(),
+            "add acceptance criteria before marking the story ready"
+        );
+        sut.handle_action(Action::AddAcceptanceCriterion { story_id })
+            .unwrap();
+        sut.handle_action(Action::MarkStoryReady { story_id })
+            .unwrap();
+
+        let story = dao.get_story(story_id).unwrap();
+        assert_eq!(story.description, "Refund card payments");
+        assert_eq!(story.ready, true);
+        assert_eq!(sut.get_footer().unwrap().contains("2 is ready"), true);
+    }
+
+    #[test]
+    fn show_due_reminders_should_open_the_reminders_page() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        sut.show_due_reminders().unwrap();
+        assert_eq!(sut.get_page_count(), 1);
+
+        let mut prompts = Prompts::new();
+        prompts.reminder = Box::new(|| Some("2022-01-01 10:00".to_owned()));
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::SetReminder { story_id }).unwrap();
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .contains("reminder set for 2022-01-01 10:00 UTC"),
+            true
+        );
+        sut.show_due_reminders().unwrap();
+        let current_page = sut.get_current_page().unwrap();
+        assert_eq!(current_page.as_any().is::<RemindersPage>(), true);
+
+        sut.handle_action(Action::DismissReminder { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().remind_at, None);
+
+        let mut prompts = Prompts::new();
+        prompts.reminder = Box::new(|| Some("someday".to_owned()));
+        sut.set_prompts(prompts);
+        assert_eq!(
+            sut.handle_action(Action::SetReminder { story_id }).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn set_reminder_should_confirm_phrases_before_saving() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        let mut prompts = Prompts::new();
+        prompts.reminder = Box::new(|| Some("next friday".to_owned()));
+        prompts.approve = Box::new(|question| !question.starts_with("'next friday' is "));
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::SetReminder { story_id }).unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().remind_at, None);
+
+        let mut prompts = Prompts::new();
+        prompts.reminder = Box::new(|| Some("next friday".to_owned()));
+        prompts.approve = Box::new(|_| true);
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::SetReminder { story_id }).unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().remind_at.is_some(), true);
+    }
+
+    #[test]
+    fn open_item_should_build_the_navigation_stack() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let (epic_id, story_id) = (
+            fixture.last_epic_id().unwrap(),
+            fixture.last_story_id().unwrap(),
+        );
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+
+        sut.open_item(story_id).unwrap();
+        assert_eq!(sut.get_page_count(), 3);
+        let current_page = sut.get_current_page().unwrap();
+        assert_eq!(
+            current_page
+                .as_any()
+                .downcast_ref::<StoryDetail>()
+                .is_some(),
+            true
+        );
+
+        sut.open_item(epic_id).unwrap();
+        assert_eq!(sut.get_page_count(), 2);
+        assert_eq!(sut.open_item(999).is_err(), true);
+    }
+
+    #[test]
+    fn open_item_should_follow_aliases_of_merged_epics() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_epic("Billing")
+            .with_story("Invoices")
+            .build_dao();
+        dao.merge_epics(2, 1).unwrap();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        sut.handle_action(Action::JumpToItem { item_id: 2 })
+            .unwrap();
 
-        Ok(())
+        let current_page = sut.get_current_page().unwrap();
+        let epic = current_page.as_any().downcast_ref::<EpicDetail>();
+        assert_eq!(epic.map(|page| page.epic_id), Some(1));
+        assert_eq!(sut.get_footer().unwrap().ends_with("| 2 is now 1"), true);
+
+        dao.delete_epic(1).unwrap();
+        sut.handle_action(Action::PruneAliases).unwrap();
+        assert_eq!(dao.read_db().unwrap().aliases.is_empty(), true);
     }
 
-    // Private functions used for testing
+    #[test]
+    fn handle_action_should_toggle_mirrors() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_epic("Compliance")
+            .with_story("Audit log")
+            .build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.mirror = Box::new(|| Some("3".to_owned()));
+        sut.set_prompts(prompts);
 
-    fn get_page_count(&self) -> usize {
-        self.pages.len()
-    }
+        sut.handle_action(Action::ToggleMirror { epic_id: 1 })
+            .unwrap();
+        assert_eq!(dao.get_epic(1).unwrap().mirrors, vec![3]);
+        assert_eq!(sut.get_footer().unwrap().contains("now mirroring 3"), true);
 
-    fn set_prompts(&mut self, prompts: Prompts) {
-        self.prompts = prompts;
+        assert_eq!(
+            sut.handle_action(Action::ToggleMirror { epic_id: 2 })
+                .is_err(),
+            true
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        dao::test_utils::MockDB,
-        models::{Epic, Status, Story},
-        ui::{EpicDetail, HomePage, StoryDetail},
-    };
+    #[test]
+    fn handle_input_should_jump_to_items_from_any_page() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let fixture = fixture.with_epic("other");
+        let other_epic_id = fixture.last_epic_id().unwrap();
+        let config = Config {
+            id_format: IdFormat::Prefixed {
+                prefix: "PROJ".to_owned(),
+            },
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(config));
+        sut.open_item(other_epic_id).unwrap();
 
-    fn make_dao() -> Rc<JiraDAO> {
-        Rc::new(JiraDAO::new(Box::new(MockDB::new())))
+        let action = sut.handle_input("#PROJ-2").unwrap().unwrap();
+        assert_eq!(action, Action::JumpToItem { item_id: story_id });
+        sut.handle_action(action).unwrap();
+        assert_eq!(sut.get_page_count(), 3);
+        let current_page = sut.get_current_page().unwrap();
+        assert_eq!(current_page.as_any().is::<StoryDetail>(), true);
+
+        assert_eq!(sut.handle_input("#what").is_err(), true);
+        assert_eq!(sut.get_page_count(), 3);
     }
 
-    fn make_sut() -> Navigator {
-        Navigator::new(make_dao())
+    #[test]
+    fn comment_actions_should_reply_edit_and_delete() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .with_story_comment("which card types?");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+
+        let mut prompts = Prompts::new();
+        prompts.comment_number = Box::new(|_| Some("C-1".to_owned()));
+        prompts.comment = Box::new(|| Some("visa only".to_owned()));
+        prompts.edit_comment = Box::new(|current| Some(format!("{} for now", current)));
+        prompts.confirm = Box::new(|entity, consequences| {
+            entity == "comment" && consequences == ["its 1 replies will move up a level"]
+        });
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::ReplyToComment { story_id })
+            .unwrap();
+        assert_eq!(
+            sut.get_footer().unwrap().contains("C-2 replies to C-1"),
+            true
+        );
+        sut.handle_action(Action::EditComment { story_id }).unwrap();
+        sut.handle_action(Action::DeleteComment { story_id })
+            .unwrap();
+
+        let comments = dao.get_story(story_id).unwrap().comments;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].text, "visa only");
+        assert_eq!(comments[0].reply_to, None);
+
+        let mut prompts = Prompts::new();
+        prompts.comment_number = Box::new(|_| Some("first".to_owned()));
+        sut.set_prompts(prompts);
+        assert_eq!(
+            sut.handle_action(Action::EditComment { story_id }).is_err(),
+            true
+        );
     }
 
     #[test]
-    fn should_start_on_home_page() {
-        let sut = make_sut();
-        let current_page = sut.get_current_page().unwrap();
-        let home_page = current_page.as_any().downcast_ref::<HomePage>();
-        assert_eq!(sut.get_page_count(), 1);
-        assert_eq!(home_page.is_some(), true);
+    fn estimate_story_should_only_accept_values_on_the_scale() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let config = Config {
+            point_scale: PointScale::TShirt,
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+        let estimate = |input: &'static str| {
+            let mut prompts = Prompts::new();
+            prompts.estimate = Box::new(move |scale| {
+                assert_eq!(*scale, PointScale::TShirt);
+                Some(input.to_owned())
+            });
+            prompts
+        };
+
+        sut.set_prompts(estimate("l"));
+        sut.handle_action(Action::EstimateStory { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().points, Some(5));
+
+        sut.set_prompts(estimate("4"));
+        assert_eq!(
+            sut.handle_action(Action::EstimateStory { story_id })
+                .unwrap_err()
+                .to_string(),
+            "4 is not on the t-shirt scale, try M or L"
+        );
+        assert_eq!(dao.get_story(story_id).unwrap().points, Some(5));
+
+        sut.set_prompts(estimate("-"));
+        sut.handle_action(Action::EstimateStory { story_id })
+            .unwrap();
+        assert_eq!(dao.get_story(story_id).unwrap().points, None);
     }
 
     #[test]
-    fn handle_action_should_navigate_pages() {
+    fn handle_action_should_clear_pages_on_exit() {
         let mut sut = make_sut();
+        sut.handle_action(Action::Exit).unwrap();
+        assert_eq!(sut.get_page_count(), 0);
+    }
 
-        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+    #[test]
+    fn handle_action_should_save_pending_writes_on_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json").to_string_lossy().into_owned();
+        let file = JSONFileJiraDAOAdapter { path: path.clone() };
+        file.persist(&DbFixtureBuilder::new().build()).unwrap();
+        let database = WriteBehindDatabase::new(Box::new(file), Duration::from_secs(60));
+        let dao = Rc::new(JiraDAO::new(Box::new(database)));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        dao.create_epic(Epic::new("pending".to_owned(), "".to_owned()))
             .unwrap();
-        assert_eq!(sut.get_page_count(), 2);
-
-        let current_page = sut.get_current_page().unwrap();
-        let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
-        assert_eq!(epic_detail_page.is_some(), true);
 
-        sut.handle_action(Action::NavigateToStoryDetail {
-            epic_id: 1,
-            story_id: 2,
-        })
-        .unwrap();
-        assert_eq!(sut.get_page_count(), 3);
+        sut.handle_action(Action::Exit).unwrap();
 
-        let current_page = sut.get_current_page().unwrap();
-        let story_detail_page = current_page.as_any().downcast_ref::<StoryDetail>();
-        assert_eq!(story_detail_page.is_some(), true);
+        let saved = JSONFileJiraDAOAdapter { path }.retrieve().unwrap();
+        assert_eq!(saved.epics.len(), 1);
+    }
 
-        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
-        assert_eq!(sut.get_page_count(), 2);
+    #[test]
+    fn focus_epic_should_replace_the_navigation_stack() {
+        let dao = make_dao();
+        let epic_id = dao
+            .create_epic(Epic::new("name".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
 
+        sut.focus_epic(epic_id).unwrap();
+        assert_eq!(sut.get_page_count(), 1);
         let current_page = sut.get_current_page().unwrap();
         let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
         assert_eq!(epic_detail_page.is_some(), true);
+        assert_eq!(
+            sut.get_focus_header().unwrap(),
+            Some("FOCUS MODE | epic 1 - name | OPEN".to_owned())
+        );
 
         sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(sut.get_page_count(), 0);
+    }
+
+    #[test]
+    fn focus_epic_should_error_for_invalid_epic_id() {
+        let mut sut = make_sut();
+        assert_eq!(sut.focus_epic(999).is_err(), true);
+        assert_eq!(sut.get_page_count(), 1);
+        assert_eq!(sut.get_focus_header().unwrap(), None);
+    }
+
+    #[test]
+    fn handle_action_should_toggle_epic_focus() {
+        let dao = make_dao();
+        let epic_id = dao
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+
+        sut.handle_action(Action::ToggleEpicFocus { epic_id })
+            .unwrap();
         assert_eq!(sut.get_page_count(), 1);
+        assert_eq!(sut.get_focus_header().unwrap().is_some(), true);
 
-        let current_page = sut.get_current_page().unwrap();
-        let home_page = current_page.as_any().downcast_ref::<HomePage>();
+        sut.handle_action(Action::ToggleEpicFocus { epic_id })
+            .unwrap();
+        assert_eq!(sut.get_page_count(), 2);
+        assert_eq!(sut.get_focus_header().unwrap(), None);
+        let home_page = sut.pages[0].as_any().downcast_ref::<HomePage>();
         assert_eq!(home_page.is_some(), true);
+    }
 
-        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
-        assert_eq!(sut.get_page_count(), 0);
+    #[test]
+    fn handle_action_should_advance_the_tour() {
+        let mut sut = make_sut();
+        assert_eq!(sut.get_tour_instruction(), None);
 
+        sut.start_tour(Tour::getting_started());
+        let first_instruction = sut.get_tour_instruction().unwrap();
         sut.handle_action(Action::NavigateToPreviousPage).unwrap();
-        assert_eq!(sut.get_page_count(), 0);
+        assert_eq!(sut.get_tour_instruction().unwrap(), first_instruction);
+
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|| Some(Epic::new("".to_owned(), "".to_owned())));
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::CreateEpic).unwrap();
+        assert_ne!(sut.get_tour_instruction().unwrap(), first_instruction);
     }
 
     #[test]
-    fn handle_action_should_clear_pages_on_exit() {
+    fn handle_action_should_not_advance_the_tour_when_a_prompt_is_cancelled() {
         let mut sut = make_sut();
-        sut.handle_action(Action::Exit).unwrap();
-        assert_eq!(sut.get_page_count(), 0);
+        sut.start_tour(Tour::getting_started());
+        let first_instruction = sut.get_tour_instruction().unwrap();
+
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|| None);
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::CreateEpic).unwrap();
+
+        assert_eq!(sut.get_tour_instruction().unwrap(), first_instruction);
     }
 
     #[test]
     fn handle_action_should_handle_create_epic() {
         let dao = make_dao();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.create_epic = Box::new(|| Epic::new("name".to_owned(), "description".to_owned()));
+        prompts.create_epic =
+            Box::new(|| Some(Epic::new("name".to_owned(), "description".to_owned())));
         sut.set_prompts(prompts);
 
         sut.handle_action(Action::CreateEpic).unwrap();
@@ -204,15 +2487,28 @@ mod tests {
         assert_eq!(epic.description, "description".to_owned());
     }
 
+    #[test]
+    fn handle_action_should_skip_create_epic_when_prompt_is_cancelled() {
+        let dao = make_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|| None);
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::CreateEpic).unwrap();
+
+        assert_eq!(dao.read_db().unwrap().epics.len(), 0);
+    }
+
     #[test]
     fn handle_action_should_handle_update_epic() {
         let dao = make_dao();
         let epic_id = dao
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.update_status = Box::new(|_| Some(Status::InProgress));
         sut.set_prompts(prompts);
 
         sut.handle_action(Action::UpdateEpicStatus { epic_id })
@@ -231,9 +2527,9 @@ mod tests {
         let epic_id = dao
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.delete_epic = Box::new(|| true);
+        prompts.confirm = Box::new(|_, _| true);
         sut.set_prompts(prompts);
 
         sut.handle_action(Action::DeleteEpic { epic_id }).unwrap();
@@ -242,15 +2538,48 @@ mod tests {
         assert_eq!(db_state.epics.len(), 0);
     }
 
+    #[test]
+    fn handle_action_should_confirm_epic_deletion_with_cascade_info() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story 1")
+            .with_story("story 2");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let asked = Rc::new(RefCell::new(vec![]));
+        let asked_clone = Rc::clone(&asked);
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(move |entity, consequences| {
+            asked_clone
+                .borrow_mut()
+                .push((entity.to_owned(), consequences.to_vec()));
+            false
+        });
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+
+        assert_eq!(
+            *asked.borrow(),
+            vec![(
+                "epic".to_owned(),
+                vec!["its 2 stories will also be deleted".to_owned()]
+            )]
+        );
+        assert_eq!(dao.read_db().unwrap().epics.len(), 1);
+    }
+
     #[test]
     fn handle_action_should_handle_create_story() {
         let dao = make_dao();
         let epic_id = dao
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.create_story = Box::new(|| Story::new("name".to_owned(), "description".to_owned()));
+        prompts.create_story =
+            Box::new(|| Some(Story::new("name".to_owned(), "description".to_owned())));
         sut.set_prompts(prompts);
 
         sut.handle_action(Action::CreateStory { epic_id }).unwrap();
@@ -272,9 +2601,9 @@ mod tests {
         let story_id = dao
             .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
             .unwrap();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.update_status = Box::new(|_| Some(Status::InProgress));
         sut.set_prompts(prompts);
         sut.handle_action(Action::UpdateStoryStatus { story_id })
             .unwrap();
@@ -285,6 +2614,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_action_should_handle_close_story_with_comment() {
+        let dao = make_dao();
+        let epic_id = dao
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = dao
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.close_story = Box::new(|| Some((Status::Closed, "done".to_owned())));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::CloseStoryWithComment { story_id })
+            .unwrap();
+
+        let db_state = dao.read_db().unwrap();
+        let story = db_state.stories.get(&story_id).unwrap();
+        assert_eq!(story.status, Status::Closed);
+        assert_eq!(story.comments.len(), 1);
+        assert_eq!(story.comments[0].text, "done".to_owned());
+    }
+
     #[test]
     fn handle_action_should_handle_delete_story() {
         let dao = make_dao();
@@ -294,13 +2647,273 @@ mod tests {
         let story_id = dao
             .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
             .unwrap();
-        let mut sut = Navigator::new(Rc::clone(&dao));
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
         let mut prompts = Prompts::new();
-        prompts.delete_story = Box::new(|| true);
+        prompts.confirm = Box::new(|_, _| true);
         sut.set_prompts(prompts);
         sut.handle_action(Action::DeleteStory { epic_id, story_id })
             .unwrap();
         let db_state = dao.read_db().unwrap();
         assert_eq!(db_state.stories.len(), 0);
     }
+
+    #[test]
+    fn should_signal_ignored_input_errors_and_confirmations() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let config = Config {
+            feedback: FeedbackConfig {
+                on_error: Signal::Bell,
+                on_success: Signal::Banner,
+            },
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(config));
+
+        sut.handle_input("zz").unwrap();
+        let feedback = sut.take_feedback().unwrap();
+        assert_eq!(feedback.signal, Signal::Bell);
+        assert_eq!(feedback.message, "'zz' does nothing here");
+        assert_eq!(sut.take_feedback(), None);
+
+        sut.handle_input("f1").unwrap();
+        assert_eq!(sut.take_feedback(), None);
+        sut.handle_action(Action::NavigateToQueue).unwrap();
+        assert_eq!(sut.handle_input("k").unwrap(), None);
+        assert_eq!(sut.take_feedback(), None);
+        sut.handle_action(Action::NavigateToPreviousPage).unwrap();
+
+        sut.handle_action(Action::QueueStory { story_id }).unwrap();
+        let feedback = sut.take_feedback().unwrap();
+        assert_eq!(feedback.signal, Signal::Banner);
+        assert_eq!(feedback.is_error, false);
+
+        sut.handle_action(Action::MarkStoryReady { story_id })
+            .unwrap_err();
+        assert_eq!(sut.take_feedback().map(|f| f.is_error), Some(true));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn handle_action_should_apply_what_a_plugin_returns() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("triage");
+        std::fs::write(
+            &path,
+            "#!/bin/sh\ncat > /dev/null\n\
+             echo '[{\"op\": \"set_kind\", \"story_id\": 2, \"kind\": \"bug\"}]'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let config = Config {
+            plugins_dir: Some(dir.path().to_string_lossy().into_owned()),
+            workflows: [("bug".to_owned(), Default::default())]
+                .into_iter()
+                .collect(),
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+        let mut prompts = Prompts::new();
+        prompts.plugin = Box::new(|names| names.first().cloned());
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::RunPlugin { story_id }).unwrap();
+
+        assert_eq!(
+            dao.get_story(story_id).unwrap().kind,
+            Some("bug".to_owned())
+        );
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .contains("triage applied 1 changes"),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_run_script_hooks() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("Refunds");
+        let story_id = fixture.last_story_id().unwrap();
+        let mut sut = Navigator::new(fixture.build_dao(), Rc::new(Config::default()));
+        sut.set_scripts(
+            Scripts::compile(
+                r#"
+                fn on_story_closed(story, board) { `well done on ${story.name}` }
+                fn on_epic_created(epic, board) { throw "no more epics" }
+                "#,
+            )
+            .unwrap(),
+        );
+        let mut prompts = Prompts::new();
+        prompts.close_story = Box::new(|| Some((Status::Closed, "shipped".to_owned())));
+        prompts.create_epic = Box::new(|| Some(Epic::new("new".to_owned(), "".to_owned())));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::CloseStoryWithComment { story_id })
+            .unwrap();
+        assert_eq!(
+            sut.get_footer().unwrap().contains("well done on Refunds"),
+            true
+        );
+
+        sut.handle_action(Action::CreateEpic).unwrap();
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .contains("script error: on_epic_created failed"),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_close_the_board_to_switch_workspaces() {
+        let config = Config {
+            workspaces: [("acme".to_owned(), "acme.json".to_owned())].into(),
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(make_dao(), Rc::new(config));
+        let mut prompts = Prompts::new();
+        prompts.workspace = Box::new(|_| Some("globex".to_owned()));
+        sut.set_prompts(prompts);
+
+        assert_eq!(sut.handle_action(Action::SwitchWorkspace).is_err(), true);
+        assert_eq!(sut.get_page_count(), 1);
+
+        let mut prompts = Prompts::new();
+        prompts.workspace = Box::new(|names| names.first().cloned());
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::SwitchWorkspace).unwrap();
+
+        assert_eq!(sut.get_page_count(), 0);
+        assert_eq!(sut.take_workspace_switch(), Some("acme".to_owned()));
+        assert_eq!(sut.take_workspace_switch(), None);
+    }
+
+    #[test]
+    fn handle_action_should_merge_epics_after_a_preview() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Cards")
+            .with_epic("Billing")
+            .with_story("Invoices");
+        let dao = fixture.build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        let mut prompts = Prompts::new();
+        prompts.merge_target = Box::new(|| Some("1".to_owned()));
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 3 })
+            .unwrap();
+        sut.handle_action(Action::MergeEpic { epic_id: 3 }).unwrap();
+        let current_page = sut.get_current_page().unwrap();
+        assert_eq!(current_page.as_any().is::<EpicMergePreview>(), true);
+        assert_eq!(dao.read_db().unwrap().epics.len(), 2);
+
+        sut.handle_action(Action::ConfirmEpicMerge {
+            source_id: 3,
+            target_id: 1,
+        })
+        .unwrap();
+        let db_state = dao.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 1);
+        assert_eq!(db_state.epics[&1].stories, vec![2, 4]);
+        assert_eq!(sut.get_page_count(), 2);
+        let current_page = sut.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>();
+        assert_eq!(epic_detail.map(|page| page.epic_id), Some(1));
+        assert_eq!(
+            sut.get_undo_banner(),
+            Some("Merged epic 3 Billing into 1 Payments. Press u to undo.".to_owned())
+        );
+
+        sut.handle_action(Action::UndoDeletion).unwrap();
+        assert_eq!(dao.read_db().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn handle_action_should_back_up_before_deleting_an_epic() {
+        let dir = tempfile::tempdir().unwrap();
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build_dao();
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(Config::default()));
+        sut.set_backups(BackupPolicy {
+            dir: dir.path().to_path_buf(),
+            workspace: None,
+        });
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        sut.handle_action(Action::DeleteEpic { epic_id: 1 })
+            .unwrap();
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(
+            sut.get_footer()
+                .unwrap()
+                .contains("restore with: jira_cli import-archive"),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_action_should_guard_bulk_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("first")
+            .with_story("second")
+            .build_dao();
+        let config = Config {
+            bulk_limit: Some(2),
+            ..Config::default()
+        };
+        let mut sut = Navigator::new(Rc::clone(&dao), Rc::new(config));
+        sut.set_backups(BackupPolicy {
+            dir: dir.path().to_path_buf(),
+            workspace: Some("acme".to_owned()),
+        });
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        prompts.confirm_bulk = Box::new(|summary, expected| {
+            summary == "This will delete epic 1, touching 3 items." && expected == "4"
+        });
+        sut.set_prompts(prompts);
+
+        sut.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        sut.handle_action(Action::DeleteEpic { epic_id: 1 })
+            .unwrap();
+        assert_eq!(dao.read_db().unwrap().epics.len(), 1);
+
+        let mut prompts = Prompts::new();
+        prompts.confirm = Box::new(|_, _| true);
+        prompts.confirm_bulk = Box::new(|_, expected| expected == "3");
+        sut.set_prompts(prompts);
+        sut.handle_action(Action::DeleteEpic { epic_id: 1 })
+            .unwrap();
+
+        assert_eq!(dao.read_db().unwrap().epics.len(), 0);
+        let log = std::fs::read_to_string(dir.path().join("activity.log")).unwrap();
+        assert_eq!(
+            log.contains("acme: delete epic 1 (3 items), restore with: jira_cli --workspace acme"),
+            true
+        );
+    }
 }
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::{Epic, Story};
+
+const BULLETS: [char; 3] = ['-', '*', '+'];
+const TAB_WIDTH: usize = 4;
+
+/// Parses an indented outline where top-level bullets are epics and nested
+/// bullets are their stories. Bullets nested below a story are appended to
+/// that story's description.
+pub fn parse_outline(content: &str) -> Result<Vec<(Epic, Vec<Story>)>> {
+    let mut epics: Vec<(Epic, Vec<Story>)> = vec![];
+    let mut story_indent: Option<usize> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = indentation_of(line);
+        let text = strip_bullet(line.trim())
+            .ok_or_else(|| anyhow!("line {}: expected a bullet item", line_number))?;
+
+        if indent == 0 {
+            epics.push((Epic::new(text.to_owned(), "".to_owned()), vec![]));
+            story_indent = None;
+            continue;
+        }
+
+        let (_, stories) = epics
+            .last_mut()
+            .ok_or_else(|| anyhow!("line {}: story has no parent epic", line_number))?;
+        let story_indent = *story_indent.get_or_insert(indent);
+        if indent <= story_indent {
+            stories.push(Story::new(text.to_owned(), "".to_owned()));
+        } else {
+            let story = stories
+                .last_mut()
+                .ok_or_else(|| anyhow!("line {}: detail has no parent story", line_number))?;
+            if !story.description.is_empty() {
+                story.description.push('\n');
+            }
+            story.description.push_str(text);
+        }
+    }
+
+    Ok(epics)
+}
+
+fn indentation_of(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+fn strip_bullet(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix(BULLETS)?;
+    let name = rest.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_outline_should_build_epics_and_stories() {
+        let outline = "- Epic 1\n  - Story 1\n  - Story 2\n\n* Epic 2\n\t+ Story 3\n";
+        let epics = parse_outline(outline).unwrap();
+
+        assert_eq!(epics.len(), 2);
+        assert_eq!(epics[0].0.name, "Epic 1");
+        let story_names: Vec<&str> = epics[0].1.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(story_names, vec!["Story 1", "Story 2"]);
+        assert_eq!(epics[1].0.name, "Epic 2");
+        assert_eq!(epics[1].1[0].name, "Story 3");
+    }
+
+    #[test]
+    fn parse_outline_should_use_deeper_bullets_as_story_description() {
+        let outline = "- Epic\n  - Story\n    - first detail\n    - second detail\n";
+        let epics = parse_outline(outline).unwrap();
+        assert_eq!(epics[0].1[0].description, "first detail\nsecond detail");
+    }
+
+    #[test]
+    fn parse_outline_should_report_line_numbers() {
+        let error = parse_outline("  - orphan story").unwrap_err();
+        assert_eq!(error.to_string(), "line 1: story has no parent epic");
+
+        let error = parse_outline("- Epic\n\nplain text").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: expected a bullet item");
+
+        let error = parse_outline("- Epic\n  -").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected a bullet item");
+    }
+}
@@ -0,0 +1,390 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::models::{AcceptanceCriterion, Comment, DBState, Status, Story};
+
+/// An executable in the plugins directory, run under its file name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// What a plugin receives on stdin: the item it was run on, if any, and the
+/// whole board.
+#[derive(Debug, Serialize)]
+pub struct PluginContext<'a> {
+    pub epic_id: Option<u32>,
+    pub story_id: Option<u32>,
+    pub board: &'a DBState,
+}
+
+/// A change a plugin asks for by printing a JSON array of these on stdout,
+/// e.g. `[{ "op": "add_comment", "story_id": 3, "text": "deployed" }]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Mutation {
+    CreateStory {
+        epic_id: u32,
+        name: String,
+        #[serde(default)]
+        description: String,
+    },
+    UpdateStatus {
+        story_id: u32,
+        status: Status,
+    },
+    AddComment {
+        story_id: u32,
+        text: String,
+    },
+    AddAcceptanceCriterion {
+        story_id: u32,
+        text: String,
+    },
+    SetKind {
+        story_id: u32,
+        kind: Option<String>,
+    },
+}
+
+/// `plugins_dir` from the config, or `~/.config/jira-cli/plugins`.
+pub fn plugins_dir(config: &Config) -> Option<PathBuf> {
+    if let Some(dir) = &config.plugins_dir {
+        return Some(PathBuf::from(dir));
+    }
+    let home = std::env::var_os("HOME")?;
+    Some(Path::new(&home).join(".config/jira-cli/plugins"))
+}
+
+/// The executables in `dir`, sorted by name. A missing directory has none.
+pub fn discover_plugins(dir: &Path) -> Result<Vec<Plugin>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let entries = fs::read_dir(dir).with_context(|| anyhow!("failed to read {}", dir.display()))?;
+    let mut plugins = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if !is_executable(&path) {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            plugins.push(Plugin {
+                name: name.to_owned(),
+                path: path.clone(),
+            });
+        }
+    }
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(plugins)
+}
+
+pub fn find_plugin(dir: &Path, name: &str) -> Result<Plugin> {
+    discover_plugins(dir)?
+        .into_iter()
+        .find(|plugin| plugin.name == name)
+        .ok_or_else(|| anyhow!("no plugin named '{}' in {}", name, dir.display()))
+}
+
+/// Runs the plugin with the context on stdin and reads back the mutations
+/// it asks for. Empty output means it changes nothing.
+pub fn run_plugin(plugin: &Plugin, context: &PluginContext) -> Result<Vec<Mutation>> {
+    let mut child = Command::new(&plugin.path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| anyhow!("failed to start plugin '{}'", plugin.name))?;
+    let input = serde_json::to_vec(context)?;
+    // written alongside the read, so a plugin printing before it has read
+    // the whole board can't block on a full pipe
+    let writer = child.stdin.take().map(|mut stdin| {
+        thread::spawn(move || {
+            // a plugin that doesn't read its context closes the pipe early
+            let _ = stdin.write_all(&input);
+        })
+    });
+    let output = child.wait_with_output()?;
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    if !output.status.success() {
+        return Err(anyhow!(
+            "plugin '{}' failed: {}",
+            plugin.name,
+            output.status
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    serde_json::from_str(&stdout)
+        .with_context(|| anyhow!("plugin '{}' printed invalid mutations", plugin.name))
+}
+
+/// Runs the plugin on an epic or a story and saves what it changed, all or
+/// nothing. Returns how many mutations were applied.
+pub fn run_on_board(
+    dao: &JiraDAO,
+    config: &Config,
+    plugin: &Plugin,
+    item_id: Option<u32>,
+) -> Result<usize> {
+    let mut state = dao.read_db()?;
+    let (epic_id, story_id) = match item_id {
+        None => (None, None),
+        Some(id) if state.epics.contains_key(&id) => (Some(id), None),
+        Some(id) if state.stories.contains_key(&id) => {
+            let epic_id = state
+                .epics
+                .iter()
+                .find(|(_, epic)| epic.stories.contains(&id))
+                .map(|(epic_id, _)| *epic_id);
+            (epic_id, Some(id))
+        }
+        Some(id) => return Err(anyhow!("no epic or story with id {}", id)),
+    };
+    let context = PluginContext {
+        epic_id,
+        story_id,
+        board: &state,
+    };
+    let mutations = run_plugin(plugin, &context)?;
+    apply_mutations(&mut state, config, &mutations)?;
+    if !mutations.is_empty() {
+        dao.replace_db(&state)?;
+    }
+    Ok(mutations.len())
+}
+
+/// Applies the mutations to the board, checking each one the way the UI
+/// would. Stops at the first invalid one, leaving the caller to discard the
+/// partly changed state.
+pub fn apply_mutations(state: &mut DBState, config: &Config, mutations: &[Mutation]) -> Result<()> {
+    for (position, mutation) in mutations.iter().enumerate() {
+        apply_mutation(state, config, mutation)
+            .with_context(|| anyhow!("mutation {} was rejected", position + 1))?;
+    }
+    Ok(())
+}
+
+fn apply_mutation(state: &mut DBState, config: &Config, mutation: &Mutation) -> Result<()> {
+    match mutation {
+        Mutation::CreateStory {
+            epic_id,
+            name,
+            description,
+        } => {
+            if name.trim().is_empty() {
+                return Err(anyhow!("a story needs a name"));
+            }
+            let new_id = state.last_item_id + 1;
+            state
+                .epics
+                .get_mut(epic_id)
+                .ok_or_else(|| anyhow!("epic {} not found", epic_id))?
+                .add_story(new_id);
+            state
+                .stories
+                .insert(new_id, Story::new(name.clone(), description.clone()));
+            state.last_item_id = new_id;
+        }
+        Mutation::UpdateStatus { story_id, status } => {
+            let story = story_mut(state, *story_id)?;
+            config.ensure_can_transition(story, status)?;
+            story.set_status(status.clone());
+        }
+        Mutation::AddComment { story_id, text } => {
+            story_mut(state, *story_id)?
+                .comments
+                .push(Comment::new(text.clone()));
+        }
+        Mutation::AddAcceptanceCriterion { story_id, text } => {
+            story_mut(state, *story_id)?
+                .acceptance_criteria
+                .push(AcceptanceCriterion::new(text.clone()));
+        }
+        Mutation::SetKind { story_id, kind } => {
+            if let Some(kind) = kind {
+                config.ensure_known_kind(kind)?;
+            }
+            story_mut(state, *story_id)?.kind = kind.clone();
+        }
+    }
+    Ok(())
+}
+
+fn story_mut(state: &mut DBState, story_id: u32) -> Result<&mut Story> {
+    state
+        .stories
+        .get_mut(&story_id)
+        .ok_or_else(|| anyhow!("story {} not found", story_id))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::workflow::{Workflow, WorkflowStep};
+
+    #[test]
+    fn apply_mutations_should_reject_invalid_changes() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build();
+        let mut config = Config::default();
+        config.workflows.insert(
+            "chore".to_owned(),
+            Workflow(vec![WorkflowStep {
+                from: Status::Open,
+                to: vec![Status::Closed],
+            }]),
+        );
+        let mutations: Vec<Mutation> = serde_json::from_str(
+            r#"[
+                { "op": "create_story", "epic_id": 1, "name": "follow-up" },
+                { "op": "add_comment", "story_id": 2, "text": "deployed" },
+                { "op": "set_kind", "story_id": 2, "kind": "chore" }
+            ]"#,
+        )
+        .unwrap();
+
+        apply_mutations(&mut state, &config, &mutations).unwrap();
+        assert_eq!(state.epics[&1].stories, vec![2, 3]);
+        assert_eq!(state.epics[&1].scope_changes.last().unwrap().story_id, 3);
+        assert_eq!(state.stories[&3].name, "follow-up");
+        assert_eq!(state.stories[&2].comments[0].text, "deployed");
+
+        let result = apply_mutations(
+            &mut state,
+            &config,
+            &[Mutation::UpdateStatus {
+                story_id: 2,
+                status: Status::Resolved,
+            }],
+        );
+        assert_eq!(result.is_err(), true);
+        let result = apply_mutations(
+            &mut state,
+            &config,
+            &[Mutation::SetKind {
+                story_id: 2,
+                kind: Some("spike".to_owned()),
+            }],
+        );
+        assert_eq!(result.is_err(), true);
+        config.require_acceptance_criteria = true;
+        state.stories.get_mut(&3).unwrap().acceptance_criteria =
+            vec![AcceptanceCriterion::new("documented".to_owned())];
+        let result = apply_mutations(
+            &mut state,
+            &config,
+            &[Mutation::UpdateStatus {
+                story_id: 3,
+                status: Status::Resolved,
+            }],
+        );
+        assert_eq!(result.is_err(), true);
+        let result = apply_mutations(
+            &mut state,
+            &config,
+            &[Mutation::AddComment {
+                story_id: 99,
+                text: "lost".to_owned(),
+            }],
+        );
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn run_plugin_should_pass_the_context_and_read_mutations() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("echo-story");
+        fs::write(
+            &path,
+            "#!/bin/sh\nid=$(sed 's/.*\"story_id\":\\([0-9]*\\).*/\\1/')\n\
+             echo \"[{\\\"op\\\": \\\"add_comment\\\", \\\"story_id\\\": $id, \\\"text\\\": \\\"seen\\\"}]\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a plugin").unwrap();
+
+        let plugins = discover_plugins(dir.path()).unwrap();
+        assert_eq!(plugins.len(), 1);
+        let state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("story")
+            .build();
+        let context = PluginContext {
+            epic_id: Some(1),
+            story_id: Some(2),
+            board: &state,
+        };
+
+        assert_eq!(
+            run_plugin(&plugins[0], &context).unwrap(),
+            vec![Mutation::AddComment {
+                story_id: 2,
+                text: "seen".to_owned()
+            }]
+        );
+        assert_eq!(
+            discover_plugins(&dir.path().join("missing")).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn run_plugin_should_not_block_when_the_plugin_prints_before_reading() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chatty");
+        fs::write(
+            &path,
+            "#!/bin/sh\nhead -c 200000 /dev/zero | tr '\\0' ' '\ncat > /dev/null\necho '[]'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        let state = DbFixtureBuilder::new()
+            .with_epic(&"e".repeat(200_000))
+            .build();
+        let context = PluginContext {
+            epic_id: Some(1),
+            story_id: None,
+            board: &state,
+        };
+
+        let plugin = Plugin {
+            name: "chatty".to_owned(),
+            path,
+        };
+        assert_eq!(run_plugin(&plugin, &context).unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ui::Action;
+
+/// One step of a recipe, written in snake case, e.g. `"reminders"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeStep {
+    Reminders,
+    Queue,
+    /// Starts the story at the top of the next up queue and opens it.
+    StartNext,
+    Grooming,
+    Refinement,
+    Watchlist,
+    Forecast,
+}
+
+impl RecipeStep {
+    pub fn action(self) -> Action {
+        match self {
+            RecipeStep::Reminders => Action::NavigateToReminders,
+            RecipeStep::Queue => Action::NavigateToQueue,
+            RecipeStep::StartNext => Action::StartNextQueuedStory,
+            RecipeStep::Grooming => Action::NavigateToGrooming,
+            RecipeStep::Refinement => Action::NavigateToRefinement,
+            RecipeStep::Watchlist => Action::NavigateToWatchlist,
+            RecipeStep::Forecast => Action::NavigateToForecast,
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            RecipeStep::Reminders => "due reminders",
+            RecipeStep::Queue => "next up queue",
+            RecipeStep::StartNext => "start the next story",
+            RecipeStep::Grooming => "grooming",
+            RecipeStep::Refinement => "refinement",
+            RecipeStep::Watchlist => "watchlist",
+            RecipeStep::Forecast => "forecast",
+        }
+    }
+}
+
+/// Steps run one after the other, e.g.
+/// `{ "name": "start my day", "steps": ["reminders", "start_next"] }`.
+/// A step opening a page waits for that page to be left.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recipe {
+    pub name: String,
+    pub steps: Vec<RecipeStep>,
+}
+
+impl Recipe {
+    pub fn start_my_day() -> Self {
+        Self {
+            name: "start my day".to_owned(),
+            steps: vec![RecipeStep::Reminders, RecipeStep::StartNext],
+        }
+    }
+}
+
+/// The configured recipes, or the built-in ones when none are.
+pub fn available(configured: &[Recipe]) -> Vec<Recipe> {
+    if configured.is_empty() {
+        return vec![Recipe::start_my_day()];
+    }
+    configured.to_vec()
+}
+
+/// A recipe in progress.
+pub struct RecipeRun {
+    pub name: String,
+    pub steps: VecDeque<RecipeStep>,
+    /// Pages open before the step on screen; leaving back to this many
+    /// runs the next step.
+    pub depth: usize,
+}
+
+impl RecipeRun {
+    pub fn new(recipe: Recipe) -> Self {
+        Self {
+            name: recipe.name,
+            steps: recipe.steps.into(),
+            depth: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn available_should_fall_back_to_the_built_in_recipes() {
+        assert_eq!(available(&[]), vec![Recipe::start_my_day()]);
+
+        let configured: Vec<Recipe> = serde_json::from_str(
+            r#"[{ "name": "plan", "steps": ["grooming", "refinement", "forecast"] }]"#,
+        )
+        .unwrap();
+        assert_eq!(available(&configured), configured);
+        assert_eq!(configured[0].steps[2].action(), Action::NavigateToForecast);
+        assert_eq!(
+            serde_json::from_str::<Recipe>(r#"{ "name": "x", "steps": ["timer"] }"#).is_err(),
+            true
+        );
+    }
+}
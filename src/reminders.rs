@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+
+use crate::calendar::Calendar;
+use crate::models::DBState;
+use crate::natural_date::parse_natural;
+use crate::time_display::TimeDisplay;
+
+/// Reads when to be reminded: an offset from now such as `30m`, `2h`, `3d`,
+/// `1w` or `3wd` (working days), a date understood by
+/// [`TimeDisplay::parse`], or a phrase such as `next friday` (see
+/// [`parse_natural`]).
+pub fn parse_reminder(
+    input: &str,
+    now: DateTime<Utc>,
+    display: &TimeDisplay,
+    calendar: &Calendar,
+) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    let invalid = || {
+        anyhow!(
+            "'{}' is not a reminder time, try 2h, next friday or YYYY-MM-DD HH:MM",
+            input
+        )
+    };
+    if let Some(timestamp) = display.parse(input) {
+        return Ok(timestamp);
+    }
+    if is_phrase(input) {
+        let wall_clock =
+            parse_natural(input, display.to_wall_clock(&now), calendar).ok_or_else(invalid)?;
+        return display.from_wall_clock(&wall_clock).ok_or_else(invalid);
+    }
+    let unit_start = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let amount: i64 = input[..unit_start].parse().map_err(|_| invalid())?;
+    let offset = match &input[unit_start..] {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        "wd" => {
+            let wall_clock = display.to_wall_clock(&now);
+            let day = calendar
+                .add_working_days(wall_clock.date(), amount.try_into().map_err(|_| invalid())?)
+                .ok_or_else(invalid)?;
+            return display
+                .from_wall_clock(&day.and_time(wall_clock.time()))
+                .ok_or_else(invalid);
+        }
+        _ => return Err(invalid()),
+    };
+    // amounts too large for a date are as invalid as any other typo
+    offset
+        .and_then(|offset| now.checked_add_signed(offset))
+        .ok_or_else(invalid)
+}
+
+/// Whether the input is worded rather than a number or a date, so the time
+/// it was read as is worth showing before saving it.
+pub fn is_phrase(input: &str) -> bool {
+    input.trim().starts_with(|c: char| c.is_alphabetic())
+}
+
+/// Stories whose reminder is due, the most overdue first.
+pub fn due_reminders(db_state: &DBState, now: DateTime<Utc>) -> Vec<u32> {
+    db_state
+        .stories
+        .iter()
+        .filter_map(|(id, story)| Some((story.remind_at?, *id)))
+        .filter(|(remind_at, _)| *remind_at <= now)
+        .sorted()
+        .map(|(_, id)| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_reminder_should_accept_offsets_and_dates() {
+        let display = TimeDisplay::default();
+        let parse = |input| parse_reminder(input, now(), &display, &Calendar::default());
+
+        assert_eq!(parse("30m").unwrap(), now() + Duration::minutes(30));
+        assert_eq!(parse("2h").unwrap(), now() + Duration::hours(2));
+        assert_eq!(parse("3d").unwrap(), now() + Duration::days(3));
+        assert_eq!(parse("1w").unwrap(), now() + Duration::weeks(1));
+        assert_eq!(parse("3wd").unwrap(), now() + Duration::days(5));
+        assert_eq!(
+            parse("2023-05-12 08:15").unwrap(),
+            Utc.with_ymd_and_hms(2023, 5, 12, 8, 15, 0).unwrap()
+        );
+        assert_eq!(
+            parse("next friday").unwrap(),
+            Utc.with_ymd_and_hms(2023, 5, 12, 9, 0, 0).unwrap()
+        );
+        assert_eq!(parse("whenever").is_err(), true);
+        assert_eq!(parse("3").is_err(), true);
+        assert_eq!(parse("h").is_err(), true);
+        assert_eq!(parse("3y").is_err(), true);
+        assert_eq!(parse("99999999999w").is_err(), true);
+        assert_eq!(parse("9999999999999d").is_err(), true);
+        assert_eq!(parse("99999999999999999999m").is_err(), true);
+    }
+
+    #[test]
+    fn parse_reminder_should_read_phrases_on_the_configured_clock() {
+        let display: TimeDisplay = serde_json::from_str(
+            r#"{ "time_zone": "+02:00", "date_format": "%Y-%m-%d %H:%M", "relative": false }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_reminder("eod", now(), &display, &Calendar::default()).unwrap(),
+            Utc.with_ymd_and_hms(2023, 5, 10, 15, 0, 0).unwrap()
+        );
+        assert_eq!(is_phrase("eod"), true);
+        assert_eq!(is_phrase("2h"), false);
+        assert_eq!(is_phrase("2023-05-12"), false);
+    }
+
+    #[test]
+    fn due_reminders_should_list_overdue_stories_first() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("later")
+            .with_story("recent")
+            .with_story("overdue")
+            .with_story("none")
+            .build();
+        state.stories.get_mut(&2).unwrap().remind_at = Some(now() + Duration::hours(1));
+        state.stories.get_mut(&3).unwrap().remind_at = Some(now() - Duration::hours(1));
+        state.stories.get_mut(&4).unwrap().remind_at = Some(now() - Duration::days(1));
+
+        assert_eq!(due_reminders(&state, now()), vec![4, 3]);
+    }
+}
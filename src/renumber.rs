@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::models::DBState;
+
+/// How new ids are handed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenumberScheme {
+    /// Keeps the current order and closes the gaps left by deletions.
+    Compact,
+    /// Numbers each epic followed by its stories, the way a fresh board
+    /// built epic by epic would be.
+    ByEpic,
+}
+
+/// An item whose id changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renumbering {
+    pub old_id: u32,
+    pub new_id: u32,
+    pub is_epic: bool,
+    pub name: String,
+}
+
+/// Works out the new ids without touching the board. Only items whose id
+/// changes are returned, ordered by their current id.
+pub fn plan_renumbering(db_state: &DBState, scheme: RenumberScheme) -> Vec<Renumbering> {
+    let order: Vec<u32> = match scheme {
+        RenumberScheme::Compact => db_state
+            .epics
+            .keys()
+            .chain(db_state.stories.keys())
+            .copied()
+            .sorted()
+            .collect(),
+        RenumberScheme::ByEpic => db_state
+            .epics
+            .iter()
+            .sorted_by_key(|(id, _)| **id)
+            .flat_map(|(id, epic)| {
+                std::iter::once(*id).chain(
+                    epic.stories
+                        .iter()
+                        .copied()
+                        .filter(|story_id| db_state.stories.contains_key(story_id)),
+                )
+            })
+            // stories no epic lists go last, so every item still gets an id
+            .chain(db_state.stories.keys().copied().sorted())
+            .unique()
+            .collect(),
+    };
+    order
+        .into_iter()
+        .zip(1..)
+        .filter(|(old_id, new_id)| old_id != new_id)
+        .filter_map(|(old_id, new_id)| {
+            let (is_epic, name) = match db_state.epics.get(&old_id) {
+                Some(epic) => (true, epic.name.clone()),
+                None => (false, db_state.stories.get(&old_id)?.name.clone()),
+            };
+            Some(Renumbering {
+                old_id,
+                new_id,
+                is_epic,
+                name,
+            })
+        })
+        .sorted_by_key(|renumbering| renumbering.old_id)
+        .collect()
+}
+
+/// Returns the board with every id, and every reference to one, replaced.
+pub fn apply_renumbering(db_state: &DBState, plan: &[Renumbering]) -> DBState {
+    let mapping: HashMap<u32, u32> = plan
+        .iter()
+        .map(|renumbering| (renumbering.old_id, renumbering.new_id))
+        .collect();
+    let map = |id: &u32| mapping.get(id).copied().unwrap_or(*id);
+
+    let epics: HashMap<_, _> = db_state
+        .epics
+        .iter()
+        .map(|(id, epic)| {
+            let mut epic = epic.clone();
+            epic.stories = epic.stories.iter().map(map).collect();
+            epic.split_from = epic.split_from.as_ref().map(map);
+            epic.split_into = epic.split_into.iter().map(map).collect();
+            epic.mirrors = epic.mirrors.iter().map(map).collect();
+            for change in epic.scope_changes.iter_mut() {
+                change.story_id = map(&change.story_id);
+            }
+            (map(id), epic)
+        })
+        .collect();
+    let stories: HashMap<_, _> = db_state
+        .stories
+        .iter()
+        .map(|(id, story)| {
+            let mut story = story.clone();
+            story.blocked_by = story.blocked_by.iter().map(map).collect();
+            (map(id), story)
+        })
+        .collect();
+    let last_item_id = epics
+        .keys()
+        .chain(stories.keys())
+        .max()
+        .copied()
+        .unwrap_or(0);
+
+    DBState {
+        last_item_id,
+        epics,
+        stories,
+        queue: db_state.queue.iter().map(map).collect(),
+        // old ids keep opening the items they were given to
+        aliases: db_state
+            .aliases
+            .iter()
+            .map(|(id, target)| (*id, map(target)))
+            .chain(
+                plan.iter()
+                    .map(|renumbering| (renumbering.old_id, renumbering.new_id)),
+            )
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    /// Epic 4 with stories 3 and 5, with ids 1 and 2 deleted.
+    fn gappy_board() -> DBState {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_epic("Search")
+            .with_story("Autocomplete")
+            .with_story_blocked_by(3)
+            .build();
+        state.epics.remove(&1);
+        state.stories.remove(&2);
+        state.epics.get_mut(&4).unwrap().stories.insert(0, 3);
+        state.queue = vec![5, 3];
+        state
+    }
+
+    #[test]
+    fn plan_renumbering_should_close_gaps() {
+        let plan = plan_renumbering(&gappy_board(), RenumberScheme::Compact);
+
+        assert_eq!(
+            plan.iter()
+                .map(|renumbering| (renumbering.old_id, renumbering.new_id))
+                .collect::<Vec<_>>(),
+            vec![(3, 1), (4, 2), (5, 3)]
+        );
+        assert_eq!(plan[1].is_epic, true);
+        assert_eq!(plan[1].name, "Search");
+    }
+
+    #[test]
+    fn plan_renumbering_by_epic_should_follow_the_epics() {
+        let plan = plan_renumbering(&gappy_board(), RenumberScheme::ByEpic);
+
+        assert_eq!(
+            plan.iter()
+                .map(|renumbering| (renumbering.old_id, renumbering.new_id))
+                .collect::<Vec<_>>(),
+            vec![(3, 2), (4, 1), (5, 3)]
+        );
+    }
+
+    #[test]
+    fn apply_renumbering_should_update_every_reference() {
+        let state = gappy_board();
+        let plan = plan_renumbering(&state, RenumberScheme::Compact);
+
+        let renumbered = apply_renumbering(&state, &plan);
+
+        assert_eq!(renumbered.last_item_id, 3);
+        assert_eq!(renumbered.epics[&2].name, "Search");
+        assert_eq!(renumbered.epics[&2].stories, vec![1, 3]);
+        assert_eq!(renumbered.stories[&3].blocked_by, vec![1]);
+        assert_eq!(renumbered.queue, vec![3, 1]);
+        assert_eq!(renumbered.resolve(4), Some(2));
+        assert_eq!(renumbered.resolve(5), Some(3));
+        // 3 went to another story
+        assert_eq!(renumbered.dead_aliases(), vec![3]);
+        assert_eq!(
+            plan_renumbering(&renumbered, RenumberScheme::Compact),
+            vec![]
+        );
+    }
+}
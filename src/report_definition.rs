@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::models::{DBState, Status, Story};
+
+/// Where report definitions are looked up when `reports_dir` isn't set.
+pub const DEFAULT_REPORTS_DIR: &str = "./data/reports";
+
+/// A report kept as `<name>.toml` in the reports folder, e.g.
+///
+/// ```toml
+/// group_by = "epic"
+/// aggregates = ["count", "done", "points"]
+/// format = "table"
+///
+/// [filter]
+/// status = ["Open", "InProgress"]
+/// kind = ["bug"]
+/// ```
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportDefinition {
+    pub filter: ReportFilter,
+    /// Without grouping the report is a single `all` row.
+    pub group_by: Option<GroupBy>,
+    /// Defaults to counting the stories.
+    pub aggregates: Vec<Aggregate>,
+    pub format: ReportFormat,
+}
+
+/// Which stories the report covers. Every field left out matches all of
+/// them; a list matches any of its values.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportFilter {
+    pub status: Vec<Status>,
+    /// Story types, as configured in `workflows`.
+    pub kind: Vec<String>,
+    /// Epic ids, as numbers.
+    pub epic: Vec<u32>,
+    pub ready: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    Epic,
+    Status,
+    Kind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregate {
+    /// How many stories.
+    Count,
+    /// How many of them are resolved or closed.
+    Done,
+    /// The sum of their estimates.
+    Points,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+impl Aggregate {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Done => "done",
+            Self::Points => "points",
+        }
+    }
+
+    fn compute(&self, stories: &[&Story]) -> u32 {
+        match self {
+            Self::Count => stories.len() as u32,
+            Self::Done => stories
+                .iter()
+                .filter(|story| story.status.is_done())
+                .count() as u32,
+            Self::Points => stories.iter().filter_map(|story| story.points).sum(),
+        }
+    }
+}
+
+pub fn reports_dir(config: &Config) -> PathBuf {
+    PathBuf::from(config.reports_dir.as_deref().unwrap_or(DEFAULT_REPORTS_DIR))
+}
+
+/// The names of the definitions in `dir`, sorted. A missing folder has none.
+pub fn report_definition_names(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut names = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            names.extend(
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned()),
+            );
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+impl ReportDefinition {
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let path = dir.join(format!("{}.toml", name));
+        let content = fs::read_to_string(&path)
+            .with_context(|| anyhow!("no report '{}' in {}", name, dir.display()))?;
+        toml::from_str(&content).with_context(|| anyhow!("invalid report in {}", path.display()))
+    }
+
+    /// Runs the report on the board and renders it in the chosen format.
+    pub fn run(&self, db_state: &DBState, config: &Config) -> Result<String> {
+        let aggregates = if self.aggregates.is_empty() {
+            vec![Aggregate::Count]
+        } else {
+            self.aggregates.clone()
+        };
+        let mut groups: BTreeMap<String, Vec<&Story>> = BTreeMap::new();
+        for (story_id, story) in db_state.stories.iter().sorted_by_key(|(id, _)| **id) {
+            let epic_id = db_state.epic_of(*story_id);
+            if !self.filter.matches(story, epic_id) {
+                continue;
+            }
+            let key = match self.group_by {
+                None => "all".to_owned(),
+                Some(GroupBy::Status) => story.status.to_string(),
+                Some(GroupBy::Kind) => story.kind.clone().unwrap_or_else(|| "-".to_owned()),
+                Some(GroupBy::Epic) => match epic_id {
+                    Some(epic_id) => format!(
+                        "{} {}",
+                        config.id_format.format(epic_id),
+                        db_state.epics[&epic_id].name
+                    ),
+                    None => "-".to_owned(),
+                },
+            };
+            groups.entry(key).or_default().push(story);
+        }
+
+        let header: Vec<&str> = std::iter::once(self.group_name())
+            .chain(aggregates.iter().map(Aggregate::name))
+            .collect();
+        let rows: Vec<Vec<String>> = groups
+            .iter()
+            .map(|(key, stories)| {
+                std::iter::once(key.clone())
+                    .chain(
+                        aggregates
+                            .iter()
+                            .map(|aggregate| aggregate.compute(stories).to_string()),
+                    )
+                    .collect()
+            })
+            .collect();
+        Ok(match self.format {
+            ReportFormat::Table => render_table(&header, &rows),
+            ReportFormat::Csv => render_csv(&header, &rows),
+            ReportFormat::Json => render_json(&header, &groups, &aggregates)?,
+        })
+    }
+
+    fn group_name(&self) -> &'static str {
+        match self.group_by {
+            None => "stories",
+            Some(GroupBy::Epic) => "epic",
+            Some(GroupBy::Status) => "status",
+            Some(GroupBy::Kind) => "kind",
+        }
+    }
+}
+
+impl ReportFilter {
+    fn matches(&self, story: &Story, epic_id: Option<u32>) -> bool {
+        (self.status.is_empty() || self.status.contains(&story.status))
+            && (self.kind.is_empty()
+                || story
+                    .kind
+                    .as_ref()
+                    .is_some_and(|kind| self.kind.contains(kind)))
+            && (self.epic.is_empty() || epic_id.is_some_and(|id| self.epic.contains(&id)))
+            && self.ready.is_none_or(|ready| story.ready == ready)
+    }
+}
+
+fn render_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    std::iter::once(line(header.to_vec()))
+        .chain(
+            rows.iter()
+                .map(|row| line(row.iter().map(String::as_str).collect())),
+        )
+        .join("\n")
+}
+
+fn render_csv(header: &[&str], rows: &[Vec<String>]) -> String {
+    let quote = |cell: &str| {
+        if cell.contains([',', '"', '\n']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_owned()
+        }
+    };
+    std::iter::once(header.iter().map(|cell| quote(cell)).join(","))
+        .chain(
+            rows.iter()
+                .map(|row| row.iter().map(|cell| quote(cell)).join(",")),
+        )
+        .join("\n")
+}
+
+fn render_json(
+    header: &[&str],
+    groups: &BTreeMap<String, Vec<&Story>>,
+    aggregates: &[Aggregate],
+) -> Result<String> {
+    let rows: Vec<serde_json::Value> = groups
+        .iter()
+        .map(|(key, stories)| {
+            let mut row = serde_json::Map::new();
+            row.insert(header[0].to_owned(), key.clone().into());
+            for aggregate in aggregates {
+                row.insert(
+                    aggregate.name().to_owned(),
+                    aggregate.compute(stories).into(),
+                );
+            }
+            serde_json::Value::Object(row)
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    fn make_board() -> DBState {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_epic("Search")
+            .with_story("Facets")
+            .build();
+        let refunds = state.stories.get_mut(&2).unwrap();
+        refunds.points = Some(3);
+        refunds.kind = Some("bug".to_owned());
+        let invoices = state.stories.get_mut(&3).unwrap();
+        invoices.points = Some(5);
+        invoices.status = Status::Closed;
+        state
+    }
+
+    #[test]
+    fn run_should_group_and_aggregate_the_filtered_stories() {
+        let definition: ReportDefinition = toml::from_str(
+            r#"
+            group_by = "epic"
+            aggregates = ["count", "done", "points"]
+
+            [filter]
+            status = ["Open", "Closed"]
+            "#,
+        )
+        .unwrap();
+
+        let output = definition.run(&make_board(), &Config::default()).unwrap();
+
+        assert_eq!(
+            output,
+            "epic        count  done  points\n\
+             1 Payments      2     1       8\n\
+             4 Search        1     0       0"
+        );
+    }
+
+    #[test]
+    fn run_should_render_csv_and_json() {
+        let mut definition: ReportDefinition = toml::from_str(
+            r#"
+            group_by = "kind"
+            format = "csv"
+            [filter]
+            epic = [1]
+            "#,
+        )
+        .unwrap();
+        let board = make_board();
+
+        assert_eq!(
+            definition.run(&board, &Config::default()).unwrap(),
+            "kind,count\n-,1\nbug,1"
+        );
+        definition.format = ReportFormat::Json;
+        let json: serde_json::Value =
+            serde_json::from_str(&definition.run(&board, &Config::default()).unwrap()).unwrap();
+        assert_eq!(json[1], serde_json::json!({ "kind": "bug", "count": 1 }));
+        assert_eq!(
+            toml::from_str::<ReportDefinition>("group_by = \"owner\"").is_err(),
+            true
+        );
+    }
+}
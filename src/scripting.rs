@@ -0,0 +1,183 @@
+use anyhow::{anyhow, Context, Result};
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::fs;
+use std::path::Path;
+
+use crate::models::DBState;
+
+const REPORT_PREFIX: &str = "report_";
+
+/// A user script with event hooks and custom reports, e.g.
+///
+/// ```rhai
+/// fn on_story_closed(story, board) { `closed ${story.name}` }
+/// fn report_open(board) { board.stories.values().filter(|s| s.status == "Open").len() }
+/// ```
+///
+/// Scripts only ever see a copy of the board, have no file or process
+/// access, and are stopped once they run too long.
+pub struct Scripts {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Scripts {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| anyhow!("failed to read script {}", path.display()))?;
+        Self::compile(&source).with_context(|| anyhow!("in script {}", path.display()))
+    }
+
+    pub fn compile(source: &str) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(1_000_000)
+            .set_max_call_levels(32)
+            .set_max_string_size(1 << 20)
+            .disable_symbol("eval")
+            // the board is drawn on stdout, a stray print would garble it
+            .on_print(|_| {})
+            .on_debug(|_, _, _| {});
+        let ast = engine
+            .compile(source)
+            .map_err(|error| anyhow!("{}", error))?;
+        Ok(Self { engine, ast })
+    }
+
+    /// Runs `on_story_closed(story, board)` when the script defines it. The
+    /// text it returns, if any, is meant to be shown to the user.
+    pub fn on_story_closed(&self, board: &DBState, story_id: u32) -> Result<Option<String>> {
+        let story = board
+            .stories
+            .get(&story_id)
+            .ok_or_else(|| anyhow!("story not found"))?;
+        self.run_hook("on_story_closed", item_view(story_id, story)?, board)
+    }
+
+    /// Runs `on_epic_created(epic, board)` when the script defines it.
+    pub fn on_epic_created(&self, board: &DBState, epic_id: u32) -> Result<Option<String>> {
+        let epic = board
+            .epics
+            .get(&epic_id)
+            .ok_or_else(|| anyhow!("epic not found"))?;
+        self.run_hook("on_epic_created", item_view(epic_id, epic)?, board)
+    }
+
+    /// Names of the reports, from the script's `report_<name>(board)`
+    /// functions.
+    pub fn report_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .ast
+            .iter_functions()
+            .filter(|function| function.params.len() == 1)
+            .filter_map(|function| function.name.strip_prefix(REPORT_PREFIX))
+            .map(str::to_owned)
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn run_report(&self, name: &str, board: &DBState) -> Result<String> {
+        if !self.report_names().iter().any(|report| report == name) {
+            return Err(anyhow!("the script has no report named '{}'", name));
+        }
+        let result = self.call(&format!("{}{}", REPORT_PREFIX, name), (board_view(board)?,))?;
+        Ok(result.to_string())
+    }
+
+    fn run_hook(&self, hook: &str, item: Dynamic, board: &DBState) -> Result<Option<String>> {
+        let defined = self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == hook && function.params.len() == 2);
+        if !defined {
+            return Ok(None);
+        }
+        let result = self.call(hook, (item, board_view(board)?))?;
+        Ok((!result.is_unit()).then(|| result.to_string()))
+    }
+
+    fn call(&self, function: &str, args: impl rhai::FuncArgs) -> Result<Dynamic> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, function, args)
+            .map_err(|error| anyhow!("{} failed: {}", function, error))
+    }
+}
+
+/// Goes through JSON so that maps keyed by id get the string keys rhai
+/// expects, e.g. `board.stories["3"]`.
+fn board_view(board: &DBState) -> Result<Dynamic> {
+    to_dynamic(serde_json::to_value(board)?)
+}
+
+fn item_view(id: u32, item: &impl serde::Serialize) -> Result<Dynamic> {
+    let mut value = serde_json::to_value(item)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("id".to_owned(), id.into());
+    }
+    to_dynamic(value)
+}
+
+fn to_dynamic(value: serde_json::Value) -> Result<Dynamic> {
+    rhai::serde::to_dynamic(value).map_err(|error| anyhow!("{}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    fn board() -> DBState {
+        DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build()
+    }
+
+    #[test]
+    fn hooks_should_run_only_when_defined() {
+        let scripts = Scripts::compile(
+            r#"
+            fn on_story_closed(story, board) {
+                `closed ${story.id} ${story.name}, ${board.stories.len()} stories left`
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            scripts.on_story_closed(&board(), 2).unwrap(),
+            Some("closed 2 Refunds, 2 stories left".to_owned())
+        );
+        assert_eq!(scripts.on_epic_created(&board(), 1).unwrap(), None);
+    }
+
+    #[test]
+    fn reports_should_be_listed_and_run() {
+        let scripts = Scripts::compile(
+            r#"
+            fn report_names(board) {
+                board.stories.values().map(|story| story.name).sort()
+            }
+            fn report_open(board) {
+                board.stories.values().filter(|story| story.status == "Open").len()
+            }
+            fn helper() {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(scripts.report_names(), vec!["names", "open"]);
+        assert_eq!(scripts.run_report("open", &board()).unwrap(), "2");
+        assert_eq!(scripts.run_report("helper", &board()).is_err(), true);
+    }
+
+    #[test]
+    fn scripts_should_be_stopped_when_running_too_long() {
+        let scripts = Scripts::compile("fn on_epic_created(epic, board) { loop {} }").unwrap();
+
+        assert_eq!(scripts.on_epic_created(&board(), 1).is_err(), true);
+        assert_eq!(Scripts::compile("fn broken( {").is_err(), true);
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::Calendar;
+use crate::models::{Status, Story};
+
+/// How long a story may stay in a status, e.g.
+/// `{ "status": "InProgress", "working_days": 5 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sla {
+    pub status: Status,
+    pub working_days: i64,
+}
+
+/// A story that has been in a status longer than its SLA allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    pub status: Status,
+    pub since: DateTime<Utc>,
+    pub working_days: i64,
+    pub limit: i64,
+}
+
+/// A stretch of time the story spent in one status. `until` is `None` for
+/// the status it is in now.
+struct Stint {
+    status: Status,
+    since: DateTime<Utc>,
+    until: Option<DateTime<Utc>>,
+}
+
+/// Replays the status changes. The first stint is only known when the story
+/// recorded its creation.
+fn stints(story: &Story) -> Vec<Stint> {
+    let mut stints = vec![];
+    let first_status = story
+        .status_changes
+        .first()
+        .map_or(&story.status, |change| &change.from);
+    if let Some(created_at) = story.created_at {
+        stints.push(Stint {
+            status: first_status.clone(),
+            since: created_at,
+            until: story.status_changes.first().map(|change| change.at),
+        });
+    }
+    for (index, change) in story.status_changes.iter().enumerate() {
+        stints.push(Stint {
+            status: change.to.clone(),
+            since: change.at,
+            until: story.status_changes.get(index + 1).map(|next| next.at),
+        });
+    }
+    stints
+}
+
+fn check(stint: &Stint, slas: &[Sla], calendar: &Calendar, now: DateTime<Utc>) -> Option<Breach> {
+    let sla = slas.iter().find(|sla| sla.status == stint.status)?;
+    let working_days = calendar.working_days_between(
+        stint.since.date_naive(),
+        stint.until.unwrap_or(now).date_naive(),
+    );
+    (working_days > sla.working_days).then(|| Breach {
+        status: stint.status.clone(),
+        since: stint.since,
+        working_days,
+        limit: sla.working_days,
+    })
+}
+
+/// The SLA the story is breaking right now, if any.
+pub fn current_breach(
+    story: &Story,
+    slas: &[Sla],
+    calendar: &Calendar,
+    now: DateTime<Utc>,
+) -> Option<Breach> {
+    let current = stints(story).pop()?;
+    if current.status != story.status {
+        return None;
+    }
+    check(&current, slas, calendar, now)
+}
+
+/// How many stints of the stories fell under an SLA, and how many of those
+/// broke it, ongoing ones included.
+pub fn compliance<'a>(
+    stories: impl IntoIterator<Item = &'a Story>,
+    slas: &[Sla],
+    calendar: &Calendar,
+    now: DateTime<Utc>,
+) -> (usize, usize) {
+    let (mut covered, mut breached) = (0, 0);
+    for stint in stories.into_iter().flat_map(stints) {
+        if !slas.iter().any(|sla| sla.status == stint.status) {
+            continue;
+        }
+        covered += 1;
+        if check(&stint, slas, calendar, now).is_some() {
+            breached += 1;
+        }
+    }
+    (covered, breached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StatusChange;
+    use chrono::TimeZone;
+
+    /// Noon on a day of May 2023; the 1st was a Monday.
+    fn may(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, day, 12, 0, 0).unwrap()
+    }
+
+    fn in_progress_since(day: u32) -> Story {
+        let mut story = Story::new("Refunds".to_owned(), "".to_owned());
+        story.created_at = Some(may(1));
+        story.status = Status::InProgress;
+        story.status_changes = vec![StatusChange {
+            from: Status::Open,
+            to: Status::InProgress,
+            at: may(day),
+        }];
+        story
+    }
+
+    fn slas() -> Vec<Sla> {
+        vec![Sla {
+            status: Status::InProgress,
+            working_days: 5,
+        }]
+    }
+
+    #[test]
+    fn current_breach_should_count_working_days_in_the_status() {
+        let story = in_progress_since(2);
+
+        assert_eq!(
+            current_breach(&story, &slas(), &Calendar::default(), may(9)),
+            None
+        );
+        assert_eq!(
+            current_breach(&story, &slas(), &Calendar::default(), may(10)),
+            Some(Breach {
+                status: Status::InProgress,
+                since: may(2),
+                working_days: 6,
+                limit: 5,
+            })
+        );
+        assert_eq!(
+            current_breach(&story, &[], &Calendar::default(), may(30)),
+            None
+        );
+    }
+
+    #[test]
+    fn compliance_should_count_past_and_ongoing_stints() {
+        let mut finished = in_progress_since(2);
+        finished.status = Status::Closed;
+        finished.status_changes.push(StatusChange {
+            from: Status::InProgress,
+            to: Status::Closed,
+            at: may(4),
+        });
+        let late = in_progress_since(2);
+
+        assert_eq!(
+            compliance([&finished, &late], &slas(), &Calendar::default(), may(15)),
+            (2, 1)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::permalink;
+use crate::config::Config;
+use crate::models::{DBState, Status};
+use crate::sla::compliance;
+
+/// Bumped whenever a field is renamed or removed; new fields may be added
+/// without a bump.
+pub const STATS_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoardStats {
+    pub schema_version: u32,
+    pub generated_at: DateTime<Utc>,
+    /// Story counts keyed by status.
+    pub stories_by_status: BTreeMap<String, usize>,
+    pub epics: Vec<EpicProgress>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpicProgress {
+    pub id: u32,
+    pub key: String,
+    pub name: String,
+    pub status: String,
+    pub stories_total: usize,
+    pub stories_done: usize,
+    pub percent_done: usize,
+    pub permalink: String,
+    /// Percent of the times its stories were in a status with an SLA that
+    /// stayed within it; `None` when no SLA applied.
+    #[serde(default)]
+    pub sla_compliance: Option<usize>,
+    #[serde(default)]
+    pub sla_breaches: usize,
+}
+
+pub fn board_stats(db_state: &DBState, config: &Config) -> BoardStats {
+    let generated_at = Utc::now();
+    let mut stories_by_status: BTreeMap<String, usize> = [
+        Status::Open,
+        Status::InProgress,
+        Status::Resolved,
+        Status::Closed,
+    ]
+    .iter()
+    .map(|status| (status.to_string(), 0))
+    .collect();
+    for story in db_state.stories.values() {
+        *stories_by_status
+            .entry(story.status.to_string())
+            .or_default() += 1;
+    }
+
+    let epics = db_state
+        .epics
+        .iter()
+        .sorted_by_key(|(id, _)| **id)
+        .map(|(id, epic)| {
+            let stories_done = epic
+                .stories
+                .iter()
+                .filter_map(|story_id| db_state.stories.get(story_id))
+                .filter(|story| story.status.is_done())
+                .count();
+            let stories_total = epic.stories.len();
+            let (sla_covered, sla_breaches) = compliance(
+                epic.stories
+                    .iter()
+                    .filter_map(|story_id| db_state.stories.get(story_id)),
+                &config.slas,
+                &config.calendar,
+                generated_at,
+            );
+            EpicProgress {
+                id: *id,
+                key: config.id_format.format(*id),
+                name: epic.name.clone(),
+                status: epic.status.to_string(),
+                stories_total,
+                stories_done,
+                percent_done: (stories_done * 100).checked_div(stories_total).unwrap_or(0),
+                permalink: permalink(&config.id_format.format(*id)),
+                sla_compliance: ((sla_covered - sla_breaches) * 100).checked_div(sla_covered),
+                sla_breaches,
+            }
+        })
+        .collect();
+
+    BoardStats {
+        schema_version: STATS_SCHEMA_VERSION,
+        generated_at,
+        stories_by_status,
+        epics,
+    }
+}
+
+impl BoardStats {
+    pub fn to_text(&self) -> String {
+        let mut lines = vec!["Stories by status:".to_owned()];
+        for (status, count) in &self.stories_by_status {
+            lines.push(format!("  {:<12} {}", status, count));
+        }
+        lines.push("Epics:".to_owned());
+        for epic in &self.epics {
+            let mut line = format!(
+                "  {} {} | {} | {}/{} stories done ({}%)",
+                epic.key,
+                epic.name,
+                epic.status,
+                epic.stories_done,
+                epic.stories_total,
+                epic.percent_done
+            );
+            if let Some(percent) = epic.sla_compliance {
+                line.push_str(&format!(
+                    " | SLA met {}%, {} breaches",
+                    percent, epic.sla_breaches
+                ));
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn board_stats_should_count_statuses_and_progress() {
+        let state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story_status(Status::Closed)
+            .with_story("Invoices")
+            .with_epic("Search")
+            .build();
+
+        let stats = board_stats(&state, &Config::default());
+
+        assert_eq!(stats.schema_version, STATS_SCHEMA_VERSION);
+        assert_eq!(stats.stories_by_status["OPEN"], 1);
+        assert_eq!(stats.stories_by_status["CLOSED"], 1);
+        assert_eq!(stats.stories_by_status["IN PROGRESS"], 0);
+        assert_eq!(stats.epics.len(), 2);
+        assert_eq!(stats.epics[0].percent_done, 50);
+        assert_eq!(stats.epics[1].stories_total, 0);
+        assert_eq!(stats.epics[1].percent_done, 0);
+    }
+
+    #[test]
+    fn board_stats_should_report_sla_compliance_per_epic() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build();
+        for (story_id, days_ago) in [(2, 30), (3, 0)] {
+            let story = state.stories.get_mut(&story_id).unwrap();
+            story.set_status(Status::InProgress);
+            story.status_changes[0].at = Utc::now() - chrono::Duration::days(days_ago);
+        }
+        let config = Config {
+            slas: vec![crate::sla::Sla {
+                status: Status::InProgress,
+                working_days: 5,
+            }],
+            ..Config::default()
+        };
+
+        let stats = board_stats(&state, &config);
+
+        assert_eq!(stats.epics[0].sla_compliance, Some(50));
+        assert_eq!(stats.epics[0].sla_breaches, 1);
+        assert_eq!(stats.to_text().contains("SLA met 50%, 1 breaches"), true);
+        assert_eq!(
+            board_stats(&state, &Config::default()).epics[0].sla_compliance,
+            None
+        );
+    }
+
+    #[test]
+    fn board_stats_should_serialize_with_a_stable_shape() {
+        let state = DbFixtureBuilder::new().with_epic("Payments").build();
+        let json = serde_json::to_value(board_stats(&state, &Config::default())).unwrap();
+
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["epics"][0]["key"], "1");
+        assert_eq!(json["epics"][0]["stories_done"], 0);
+        assert_eq!(json["epics"][0]["permalink"], "jira_cli open 1");
+        assert_eq!(json["stories_by_status"]["OPEN"], 0);
+    }
+}
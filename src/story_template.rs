@@ -0,0 +1,194 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AcceptanceCriterion, Story};
+
+/// A story to create from the command line, with `{{variable}}`
+/// placeholders in its texts, e.g.
+/// `{ "name": "[{{severity}}] {{title}}", "kind": "bug",
+///   "acceptance_criteria": ["Reproduced on {{version}}"],
+///   "defaults": { "severity": "medium" } }`.
+/// Placeholders without a default have to be given.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoryTemplate {
+    pub name: String,
+    pub description: String,
+    pub kind: Option<String>,
+    pub points: Option<u32>,
+    pub acceptance_criteria: Vec<String>,
+    pub defaults: HashMap<String, String>,
+}
+
+/// Names of the `{{variable}}` placeholders in the text.
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        names.push(rest[start + 2..start + end].trim());
+        rest = &rest[start + end + 2..];
+    }
+    names
+}
+
+fn substitute(text: &str, values: &HashMap<&str, &str>) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(values[rest[start + 2..start + end].trim()]);
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+impl StoryTemplate {
+    fn texts(&self) -> impl Iterator<Item = &String> {
+        [&self.name, &self.description]
+            .into_iter()
+            .chain(self.kind.iter())
+            .chain(self.acceptance_criteria.iter())
+    }
+
+    /// Every variable the template uses.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        self.texts().flat_map(|text| placeholders(text)).collect()
+    }
+
+    /// The variables that have no default.
+    pub fn required(&self) -> Vec<&str> {
+        self.variables()
+            .into_iter()
+            .filter(|name| !self.defaults.contains_key(*name))
+            .collect()
+    }
+
+    /// Fills in the placeholders, failing on missing variables and on ones
+    /// the template doesn't use, which are most likely typos.
+    pub fn instantiate(&self, vars: &[(String, String)]) -> Result<Story> {
+        let variables = self.variables();
+        let unknown = vars
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| !variables.contains(name))
+            .join(", ");
+        if !unknown.is_empty() {
+            return Err(anyhow!("the template has no variable {}", unknown));
+        }
+        let mut values: HashMap<&str, &str> = self
+            .defaults
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        values.extend(
+            vars.iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        let missing = variables
+            .iter()
+            .filter(|name| !values.contains_key(*name))
+            .join(", ");
+        if !missing.is_empty() {
+            return Err(anyhow!("missing variables: {}", missing));
+        }
+
+        let mut story = Story::new(
+            substitute(&self.name, &values),
+            substitute(&self.description, &values),
+        );
+        if story.name.trim().is_empty() {
+            return Err(anyhow!("the story would have no name"));
+        }
+        story.kind = self.kind.as_ref().map(|kind| substitute(kind, &values));
+        story.points = self.points;
+        story.acceptance_criteria = self
+            .acceptance_criteria
+            .iter()
+            .map(|criterion| AcceptanceCriterion::new(substitute(criterion, &values)))
+            .collect();
+        Ok(story)
+    }
+}
+
+/// Splits a `--var` value such as `severity=high`.
+pub fn parse_var(input: &str) -> Result<(String, String)> {
+    let (name, value) = input
+        .split_once('=')
+        .ok_or_else(|| anyhow!("'{}' is not a name=value variable", input))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("'{}' has no variable name", input));
+    }
+    Ok((name.to_owned(), value.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bug() -> StoryTemplate {
+        serde_json::from_str(
+            r#"{
+                "name": "[{{severity}}] {{ title }}",
+                "description": "Seen in {{version}}",
+                "kind": "bug",
+                "points": 2,
+                "acceptance_criteria": ["Fixed in {{version}}"],
+                "defaults": { "severity": "medium" }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn vars(pairs: &[&str]) -> Vec<(String, String)> {
+        pairs.iter().map(|pair| parse_var(pair).unwrap()).collect()
+    }
+
+    #[test]
+    fn instantiate_should_substitute_variables_and_defaults() {
+        let story = bug()
+            .instantiate(&vars(&["title=Refunds fail", "version=1.2=beta"]))
+            .unwrap();
+
+        assert_eq!(story.name, "[medium] Refunds fail");
+        assert_eq!(story.description, "Seen in 1.2=beta");
+        assert_eq!(story.kind, Some("bug".to_owned()));
+        assert_eq!(story.points, Some(2));
+        assert_eq!(story.acceptance_criteria[0].text, "Fixed in 1.2=beta");
+
+        let story = bug()
+            .instantiate(&vars(&["title=x", "version=1", "severity=high"]))
+            .unwrap();
+        assert_eq!(story.name, "[high] x");
+    }
+
+    #[test]
+    fn instantiate_should_name_missing_and_unknown_variables() {
+        assert_eq!(bug().required(), vec!["title", "version"]);
+        assert_eq!(
+            bug()
+                .instantiate(&vars(&["title=x"]))
+                .unwrap_err()
+                .to_string(),
+            "missing variables: version"
+        );
+        assert_eq!(
+            bug()
+                .instantiate(&vars(&["title=x", "version=1", "sevrity=high"]))
+                .unwrap_err()
+                .to_string(),
+            "the template has no variable sevrity"
+        );
+        assert_eq!(parse_var("severity").is_err(), true);
+    }
+}
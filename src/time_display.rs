@@ -0,0 +1,287 @@
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use serde::{Deserialize, Serialize};
+
+/// Time zone used when showing timestamps. Timestamps are always stored in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TimeZonePreference {
+    Utc,
+    Local,
+    Offset(i32),
+}
+
+impl TryFrom<String> for TimeZonePreference {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        match value.as_str() {
+            "UTC" | "utc" => return Ok(Self::Utc),
+            "local" => return Ok(Self::Local),
+            _ => {}
+        }
+        let invalid = || {
+            anyhow!(
+                "invalid time zone '{}', expected UTC, local or +HH:MM",
+                value
+            )
+        };
+        let (sign, rest) = if let Some(rest) = value.strip_prefix('+') {
+            (1, rest)
+        } else if let Some(rest) = value.strip_prefix('-') {
+            (-1, rest)
+        } else {
+            return Err(invalid());
+        };
+        let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+        let hours = hours.parse::<i32>().map_err(|_| invalid())?;
+        let minutes = minutes.parse::<i32>().map_err(|_| invalid())?;
+        if hours > 14 || minutes > 59 {
+            return Err(invalid());
+        }
+        Ok(Self::Offset(sign * (hours * 3600 + minutes * 60)))
+    }
+}
+
+impl From<TimeZonePreference> for String {
+    fn from(value: TimeZonePreference) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for TimeZonePreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Local => write!(f, "local"),
+            Self::Offset(seconds) => {
+                let sign = if *seconds < 0 { '-' } else { '+' };
+                let seconds = seconds.abs();
+                write!(
+                    f,
+                    "{}{:02}:{:02}",
+                    sign,
+                    seconds / 3600,
+                    seconds % 3600 / 60
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeDisplay {
+    pub time_zone: TimeZonePreference,
+    /// A strftime pattern, e.g. `%d/%m/%Y %H:%M`.
+    pub date_format: String,
+    /// Show recent timestamps as `2d ago` / `in 3h` on screen. Exports always
+    /// use `date_format`.
+    pub relative: bool,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        Self {
+            time_zone: TimeZonePreference::Utc,
+            date_format: "%Y-%m-%d %H:%M %Z".to_owned(),
+            relative: false,
+        }
+    }
+}
+
+impl TimeDisplay {
+    pub fn validate(&self) -> Result<()> {
+        if StrftimeItems::new(&self.date_format).any(|item| item == Item::Error) {
+            return Err(anyhow!("invalid date format '{}'", self.date_format));
+        }
+        Ok(())
+    }
+
+    /// Formats a timestamp for the screen, relative or absolute depending on
+    /// `relative`.
+    pub fn show(&self, timestamp: &DateTime<Utc>, relative: bool) -> String {
+        if relative {
+            self.format_relative(timestamp, Utc::now())
+        } else {
+            self.format(timestamp)
+        }
+    }
+
+    /// `just now`, `5m ago`, `in 3h`, ... Anything 30 days or more away is
+    /// shown with `date_format` instead.
+    pub fn format_relative(&self, timestamp: &DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let elapsed = now.signed_duration_since(*timestamp);
+        let distance = if elapsed < Duration::zero() {
+            -elapsed
+        } else {
+            elapsed
+        };
+        let amount = if distance < Duration::minutes(1) {
+            return "just now".to_owned();
+        } else if distance < Duration::hours(1) {
+            format!("{}m", distance.num_minutes())
+        } else if distance < Duration::days(1) {
+            format!("{}h", distance.num_hours())
+        } else if distance < Duration::days(30) {
+            format!("{}d", distance.num_days())
+        } else {
+            return self.format(timestamp);
+        };
+        if elapsed < Duration::zero() {
+            format!("in {}", amount)
+        } else {
+            format!("{} ago", amount)
+        }
+    }
+
+    /// Reads `YYYY-MM-DD HH:MM` (or just `YYYY-MM-DD`, meaning 09:00) in the
+    /// configured time zone.
+    pub fn parse(&self, input: &str) -> Option<DateTime<Utc>> {
+        let input = input.trim();
+        let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .ok()
+            .or_else(|| {
+                let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?;
+                Some(date.and_time(NaiveTime::from_hms_opt(9, 0, 0)?))
+            })?;
+        self.from_wall_clock(&naive)
+    }
+
+    /// The time `timestamp` shows on a clock in the configured zone.
+    pub fn to_wall_clock(&self, timestamp: &DateTime<Utc>) -> NaiveDateTime {
+        match self.time_zone {
+            TimeZonePreference::Utc => timestamp.naive_utc(),
+            TimeZonePreference::Local => timestamp.with_timezone(&Local).naive_local(),
+            TimeZonePreference::Offset(seconds) => match FixedOffset::east_opt(seconds) {
+                Some(offset) => timestamp.with_timezone(&offset).naive_local(),
+                None => timestamp.naive_utc(),
+            },
+        }
+    }
+
+    /// Reads a time on a clock in the configured zone, or `None` when the
+    /// zone skips or repeats it.
+    pub fn from_wall_clock(&self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        let timestamp = match self.time_zone {
+            TimeZonePreference::Utc => Utc.from_local_datetime(naive).single()?,
+            TimeZonePreference::Local => Local
+                .from_local_datetime(naive)
+                .single()?
+                .with_timezone(&Utc),
+            TimeZonePreference::Offset(seconds) => FixedOffset::east_opt(seconds)?
+                .from_local_datetime(naive)
+                .single()?
+                .with_timezone(&Utc),
+        };
+        Some(timestamp)
+    }
+
+    pub fn format(&self, timestamp: &DateTime<Utc>) -> String {
+        let format = self.date_format.as_str();
+        match self.time_zone {
+            TimeZonePreference::Utc => timestamp.format(format).to_string(),
+            TimeZonePreference::Local => timestamp.with_timezone(&Local).format(format).to_string(),
+            TimeZonePreference::Offset(seconds) => match FixedOffset::east_opt(seconds) {
+                Some(offset) => timestamp.with_timezone(&offset).format(format).to_string(),
+                None => timestamp.format(format).to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn timestamp() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 3, 1, 23, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn time_zone_should_parse_supported_values() {
+        let parse = |value: &str| TimeZonePreference::try_from(value.to_owned());
+        assert_eq!(parse("UTC").unwrap(), TimeZonePreference::Utc);
+        assert_eq!(parse("local").unwrap(), TimeZonePreference::Local);
+        assert_eq!(parse("+02:00").unwrap(), TimeZonePreference::Offset(7200));
+        assert_eq!(parse("-03:30").unwrap(), TimeZonePreference::Offset(-12600));
+        assert_eq!(parse("Europe/Paris").is_err(), true);
+        assert_eq!(parse("+2").is_err(), true);
+        assert_eq!(parse("+25:00").is_err(), true);
+        assert_eq!(parse("").is_err(), true);
+    }
+
+    #[test]
+    fn time_zone_should_round_trip_through_strings() {
+        for value in ["UTC", "local", "+02:00", "-03:30"] {
+            let preference = TimeZonePreference::try_from(value.to_owned()).unwrap();
+            assert_eq!(String::from(preference), value);
+        }
+    }
+
+    #[test]
+    fn format_should_apply_time_zone_and_pattern() {
+        let display = TimeDisplay::default();
+        assert_eq!(display.format(&timestamp()), "2022-03-01 23:30 UTC");
+
+        let display = TimeDisplay {
+            time_zone: TimeZonePreference::Offset(7200),
+            date_format: "%d/%m/%Y %H:%M %Z".to_owned(),
+            relative: false,
+        };
+        assert_eq!(display.format(&timestamp()), "02/03/2022 01:30 +02:00");
+    }
+
+    #[test]
+    fn parse_should_read_times_in_the_configured_zone() {
+        let display = TimeDisplay {
+            time_zone: TimeZonePreference::Offset(7200),
+            ..TimeDisplay::default()
+        };
+        assert_eq!(display.parse("2022-03-02 01:30"), Some(timestamp()));
+        assert_eq!(
+            display.parse("2022-03-02"),
+            Some(Utc.with_ymd_and_hms(2022, 3, 2, 7, 0, 0).unwrap())
+        );
+        assert_eq!(display.parse("tomorrow"), None);
+        assert_eq!(display.parse("2022-02-30"), None);
+    }
+
+    #[test]
+    fn format_relative_should_pick_the_unit_at_each_boundary() {
+        let display = TimeDisplay::default();
+        let at = |seconds: i64| {
+            display.format_relative(&timestamp(), timestamp() + Duration::seconds(seconds))
+        };
+
+        assert_eq!(at(59), "just now");
+        assert_eq!(at(60), "1m ago");
+        assert_eq!(at(3599), "59m ago");
+        assert_eq!(at(3600), "1h ago");
+        assert_eq!(at(86399), "23h ago");
+        assert_eq!(at(86400), "1d ago");
+        assert_eq!(at(30 * 86400 - 1), "29d ago");
+        assert_eq!(at(30 * 86400), "2022-03-01 23:30 UTC");
+        assert_eq!(at(-3 * 3600), "in 3h");
+        assert_eq!(at(-30), "just now");
+    }
+
+    #[test]
+    fn validate_should_reject_invalid_patterns() {
+        assert_eq!(TimeDisplay::default().validate().is_ok(), true);
+        let display = TimeDisplay {
+            time_zone: TimeZonePreference::Utc,
+            date_format: "%Y-%m-%d %Q".to_owned(),
+            relative: false,
+        };
+        assert_eq!(display.validate().is_err(), true);
+    }
+}
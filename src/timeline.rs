@@ -0,0 +1,116 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+use crate::models::{DBState, Status};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineEvent {
+    Created,
+    StatusChanged { from: Status, to: Status },
+    Commented { text: String },
+}
+
+/// Something that happened to one of an epic's stories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub at: DateTime<Utc>,
+    pub story_id: u32,
+    pub event: TimelineEvent,
+}
+
+/// Creations, status changes and comments of the epic's stories, oldest
+/// first. Stories from before creation times and status changes were
+/// recorded only contribute their comments.
+pub fn epic_timeline(db_state: &DBState, epic_id: u32) -> Result<Vec<TimelineEntry>> {
+    let epic = db_state
+        .epics
+        .get(&epic_id)
+        .ok_or_else(|| anyhow!("could not find epic {}", epic_id))?;
+    let mut entries = vec![];
+    for story_id in &epic.stories {
+        let Some(story) = db_state.stories.get(story_id) else {
+            continue;
+        };
+        let entry = |at: DateTime<Utc>, event| TimelineEntry {
+            at,
+            story_id: *story_id,
+            event,
+        };
+        if let Some(created_at) = story.created_at {
+            entries.push(entry(created_at, TimelineEvent::Created));
+        }
+        for change in &story.status_changes {
+            entries.push(entry(
+                change.at,
+                TimelineEvent::StatusChanged {
+                    from: change.from.clone(),
+                    to: change.to.clone(),
+                },
+            ));
+        }
+        for comment in &story.comments {
+            entries.push(entry(
+                comment.created_at,
+                TimelineEvent::Commented {
+                    text: comment.text.clone(),
+                },
+            ));
+        }
+    }
+    // stable, so same-time events keep the epic order
+    entries.sort_by_key(|entry| entry.at);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+    use crate::models::{Comment, StatusChange};
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 10, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn epic_timeline_should_interleave_story_events() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .with_epic("Search")
+            .with_story("Autocomplete")
+            .build();
+        let refunds = state.stories.get_mut(&2).unwrap();
+        refunds.created_at = Some(at(9));
+        refunds.status_changes.push(StatusChange {
+            from: Status::Open,
+            to: Status::InProgress,
+            at: at(12),
+        });
+        let invoices = state.stories.get_mut(&3).unwrap();
+        invoices.created_at = None;
+        invoices.comments.push(Comment {
+            created_at: at(10),
+            ..Comment::new("needs finance".to_owned())
+        });
+
+        let timeline = epic_timeline(&state, 1).unwrap();
+
+        assert_eq!(
+            timeline
+                .iter()
+                .map(|entry| (entry.at, entry.story_id))
+                .collect::<Vec<_>>(),
+            vec![(at(9), 2), (at(10), 3), (at(12), 2)]
+        );
+        assert_eq!(
+            timeline[1].event,
+            TimelineEvent::Commented {
+                text: "needs finance".to_owned()
+            }
+        );
+        assert_eq!(epic_timeline(&state, 99).is_err(), true);
+    }
+}
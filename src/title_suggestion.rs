@@ -0,0 +1,122 @@
+/// Longest suggested title, in characters, before it is cut at a word.
+pub const MAX_TITLE_LENGTH: usize = 60;
+
+/// Openings that say nothing about the story itself.
+const LEAD_INS: &[&str] = &[
+    "we need to ",
+    "we should ",
+    "we must ",
+    "please ",
+    "need to ",
+];
+
+/// Words a cut title shouldn't end on, as in "Refund card payments and".
+const DANGLING_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "of", "on", "or", "the", "to", "with",
+];
+
+/// Suggests a story title from the first sentence of its description, e.g.
+/// "Refund card payments" for "We need to refund card payments. Stripe
+/// only." Long sentences are cut at a word boundary and end with `…`.
+pub fn suggest_title(description: &str) -> Option<String> {
+    let sentence = first_sentence(description)?;
+    let mut words: Vec<&str> = sentence.split_whitespace().collect();
+    let lowered = sentence.to_lowercase();
+    if let Some(lead_in) = LEAD_INS
+        .iter()
+        .find(|lead_in| lowered.starts_with(*lead_in))
+    {
+        words.drain(..lead_in.split_whitespace().count());
+    }
+
+    let mut title = String::new();
+    let mut truncated = false;
+    for word in &words {
+        let length = title.chars().count() + word.chars().count() + usize::from(!title.is_empty());
+        if length > MAX_TITLE_LENGTH {
+            truncated = true;
+            break;
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    if truncated {
+        title = trim_dangling(&title);
+    }
+    let title = title.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != ')');
+    let mut chars = title.chars();
+    let first = chars.next()?;
+    let mut title: String = first.to_uppercase().chain(chars).collect();
+    if truncated {
+        title.push('…');
+    }
+    Some(title)
+}
+
+/// Text up to the first `.`, `!` or `?` that ends a sentence, so that
+/// "v1.2" or "e.g." inside a word doesn't cut it short.
+fn first_sentence(description: &str) -> Option<&str> {
+    let line = description
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let at_boundary = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_boundary {
+            return Some(&line[..index]);
+        }
+    }
+    Some(line)
+}
+
+fn trim_dangling(title: &str) -> String {
+    let mut words: Vec<&str> = title.split_whitespace().collect();
+    while words.len() > 1 {
+        let last = words[words.len() - 1].to_lowercase();
+        if !DANGLING_WORDS.contains(&last.as_str()) {
+            break;
+        }
+        words.pop();
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_title_should_use_the_first_sentence() {
+        assert_eq!(
+            suggest_title("We need to refund card payments. Stripe only for now."),
+            Some("Refund card payments".to_owned())
+        );
+        assert_eq!(
+            suggest_title("\n  upgrade to v1.2 of the SDK!\nIt fixes the retries."),
+            Some("Upgrade to v1.2 of the SDK".to_owned())
+        );
+        assert_eq!(
+            suggest_title("Show totals (net of tax)"),
+            Some("Show totals (net of tax)".to_owned())
+        );
+        assert_eq!(suggest_title("  \n "), None);
+        assert_eq!(suggest_title("..."), None);
+    }
+
+    #[test]
+    fn suggest_title_should_cut_long_sentences_at_a_word() {
+        let title = suggest_title(
+            "Let customers download every invoice of the last twelve months as a single zip file",
+        )
+        .unwrap();
+
+        assert_eq!(
+            title,
+            "Let customers download every invoice of the last twelve…"
+        );
+        assert_eq!(title.chars().count() <= MAX_TITLE_LENGTH + 1, true);
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use crate::models::{DBState, Epic, Status, Story};
+use crate::ui::Action;
+
+pub struct TourStep {
+    pub instruction: &'static str,
+    pub is_completed_by: fn(&Action) -> bool,
+}
+
+/// A scripted sequence of steps, each completed by the user triggering a
+/// matching action.
+pub struct Tour {
+    steps: Vec<TourStep>,
+    current_step: usize,
+}
+
+impl Tour {
+    pub fn new(steps: Vec<TourStep>) -> Self {
+        Self {
+            steps,
+            current_step: 0,
+        }
+    }
+
+    pub fn getting_started() -> Self {
+        Self::new(vec![
+            TourStep {
+                instruction: "Create an epic: press [c] and enter a name and a description.",
+                is_completed_by: |action| matches!(action, Action::CreateEpic),
+            },
+            TourStep {
+                instruction: "Open an epic: type its id from the table and press Enter.",
+                is_completed_by: |action| matches!(action, Action::NavigateToEpicDetail { .. }),
+            },
+            TourStep {
+                instruction: "Add a story to this epic: press [c].",
+                is_completed_by: |action| matches!(action, Action::CreateStory { .. }),
+            },
+            TourStep {
+                instruction: "Open a story: type its id from the stories table.",
+                is_completed_by: |action| matches!(action, Action::NavigateToStoryDetail { .. }),
+            },
+            TourStep {
+                instruction: "Change the story status: press [u] and pick a new status.",
+                is_completed_by: |action| matches!(action, Action::UpdateStoryStatus { .. }),
+            },
+        ])
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_step >= self.steps.len()
+    }
+
+    pub fn current_instruction(&self) -> String {
+        if self.is_finished() {
+            return "TUTORIAL complete! Your real board was not touched. Press [q] on the home page to quit.".to_owned();
+        }
+        format!(
+            "TUTORIAL step {}/{}: {}",
+            self.current_step + 1,
+            self.steps.len(),
+            self.steps[self.current_step].instruction
+        )
+    }
+
+    /// Advances to the next step when the action completes the current one.
+    pub fn observe(&mut self, action: &Action) {
+        if let Some(step) = self.steps.get(self.current_step) {
+            if (step.is_completed_by)(action) {
+                self.current_step += 1;
+            }
+        }
+    }
+}
+
+/// A small board to play with, so the tutorial never writes to the real database.
+pub fn sample_state() -> DBState {
+    let mut epics = HashMap::new();
+    let mut stories = HashMap::new();
+
+    let mut welcome = Epic::new(
+        "Welcome to jira_cli".to_owned(),
+        "A sample epic for the tutorial".to_owned(),
+    );
+    welcome.status = Status::InProgress;
+    welcome.stories = vec![2, 3];
+    epics.insert(1, welcome);
+
+    let mut done_story = Story::new(
+        "Read the README".to_owned(),
+        "Learn what the app can do".to_owned(),
+    );
+    done_story.status = Status::Closed;
+    stories.insert(2, done_story);
+    stories.insert(
+        3,
+        Story::new(
+            "Finish the tutorial".to_owned(),
+            "Follow the steps at the bottom of each page".to_owned(),
+        ),
+    );
+
+    DBState {
+        last_item_id: 3,
+        epics,
+        stories,
+        queue: vec![],
+        aliases: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tour_should_advance_only_on_matching_actions() {
+        let mut tour = Tour::getting_started();
+        assert_eq!(tour.current_instruction().contains("step 1/5"), true);
+
+        tour.observe(&Action::NavigateToEpicDetail { epic_id: 1 });
+        assert_eq!(tour.current_instruction().contains("step 1/5"), true);
+
+        tour.observe(&Action::CreateEpic);
+        assert_eq!(tour.current_instruction().contains("step 2/5"), true);
+        assert_eq!(tour.is_finished(), false);
+    }
+
+    #[test]
+    fn tour_should_finish_after_last_step() {
+        let mut tour = Tour::getting_started();
+        tour.observe(&Action::CreateEpic);
+        tour.observe(&Action::NavigateToEpicDetail { epic_id: 1 });
+        tour.observe(&Action::CreateStory { epic_id: 1 });
+        tour.observe(&Action::NavigateToStoryDetail {
+            epic_id: 1,
+            story_id: 2,
+        });
+        tour.observe(&Action::UpdateStoryStatus { story_id: 2 });
+
+        assert_eq!(tour.is_finished(), true);
+        assert_eq!(tour.current_instruction().contains("complete"), true);
+        tour.observe(&Action::CreateEpic);
+        assert_eq!(tour.is_finished(), true);
+    }
+
+    #[test]
+    fn sample_state_should_be_consistent() {
+        let state = sample_state();
+        for epic in state.epics.values() {
+            for story_id in &epic.stories {
+                assert_eq!(state.stories.contains_key(story_id), true);
+            }
+        }
+        let max_id = state.epics.keys().chain(state.stories.keys()).max();
+        assert_eq!(max_id, Some(&state.last_item_id));
+    }
+}
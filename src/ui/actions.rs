@@ -1,13 +1,179 @@
-#[derive(Debug, PartialEq, Eq)]
+/// What to do with a story picked for grooming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroomOutcome {
+    Close,
+    MarkStale,
+    /// Moves the story to the front of the "next up" queue.
+    Bump,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
-    NavigateToEpicDetail { epic_id: u32 },
-    NavigateToStoryDetail { epic_id: u32, story_id: u32 },
+    NavigateToEpicDetail {
+        epic_id: u32,
+    },
+    NavigateToStoryDetail {
+        epic_id: u32,
+        story_id: u32,
+    },
     NavigateToPreviousPage,
+    /// Typed as `#<id>` on any page.
+    JumpToItem {
+        item_id: u32,
+    },
+    NavigateToQueue,
+    NavigateToDependencies {
+        epic_id: u32,
+    },
+    NavigateToTimeline {
+        epic_id: u32,
+    },
+    NavigateToBurnup {
+        epic_id: u32,
+    },
+    NavigateToGrooming,
+    NavigateToReminders,
+    NavigateToRefinement,
+    NavigateToWatchlist,
+    NavigateToForecast,
+    NavigateToAliases,
+    NavigateToRecipes,
+    /// Runs the recipe at `index` of the available ones.
+    RunRecipe {
+        index: usize,
+    },
     CreateEpic,
-    UpdateEpicStatus { epic_id: u32 },
-    DeleteEpic { epic_id: u32 },
-    CreateStory { epic_id: u32 },
-    UpdateStoryStatus { story_id: u32 },
-    DeleteStory { epic_id: u32, story_id: u32 },
+    UpdateEpicStatus {
+        epic_id: u32,
+    },
+    /// Goes through the epic's text fields, pre-filled for editing.
+    EditEpic {
+        epic_id: u32,
+    },
+    DeleteEpic {
+        epic_id: u32,
+    },
+    ToggleEpicFocus {
+        epic_id: u32,
+    },
+    YankEpic {
+        epic_id: u32,
+    },
+    SplitEpic {
+        epic_id: u32,
+    },
+    MergeEpic {
+        epic_id: u32,
+    },
+    /// Asks for a story of another epic to list in this one, or to stop
+    /// listing.
+    ToggleMirror {
+        epic_id: u32,
+    },
+    ConfirmEpicMerge {
+        source_id: u32,
+        target_id: u32,
+    },
+    ExportEpicReport {
+        epic_id: u32,
+    },
+    CreateStory {
+        epic_id: u32,
+    },
+    UpdateStoryStatus {
+        story_id: u32,
+    },
+    CloseStoryWithComment {
+        story_id: u32,
+    },
+    SetStoryKind {
+        story_id: u32,
+    },
+    EstimateStory {
+        story_id: u32,
+    },
+    EditStoryDescription {
+        story_id: u32,
+    },
+    /// Watches the story, or stops watching it.
+    ToggleWatch {
+        story_id: u32,
+    },
+    /// Goes through the story's text fields, pre-filled for editing.
+    EditStory {
+        story_id: u32,
+    },
+    MarkStoryReady {
+        story_id: u32,
+    },
+    SetReminder {
+        story_id: u32,
+    },
+    DismissReminder {
+        story_id: u32,
+    },
+    RunPlugin {
+        story_id: u32,
+    },
+    YankStory {
+        story_id: u32,
+    },
+    CreateStoryBranch {
+        story_id: u32,
+    },
+    ToggleBlocker {
+        story_id: u32,
+    },
+    ToggleExternalBlocker {
+        story_id: u32,
+    },
+    AddAcceptanceCriterion {
+        story_id: u32,
+    },
+    ToggleAcceptanceCriterion {
+        story_id: u32,
+    },
+    QueueStory {
+        story_id: u32,
+    },
+    UnqueueStory {
+        story_id: u32,
+    },
+    MoveQueuedStory {
+        story_id: u32,
+        offset: isize,
+    },
+    OpenQueuedStory {
+        story_id: u32,
+    },
+    StartNextQueuedStory,
+    DeleteStory {
+        epic_id: u32,
+        story_id: u32,
+    },
+    AddComment {
+        story_id: u32,
+    },
+    ReplyToComment {
+        story_id: u32,
+    },
+    EditComment {
+        story_id: u32,
+    },
+    DeleteComment {
+        story_id: u32,
+    },
+    GroomStory {
+        story_id: u32,
+        outcome: GroomOutcome,
+    },
+    UndoDeletion,
+    /// Drops the aliases that no longer open anything.
+    PruneAliases,
+    /// The page used the input itself, e.g. to move its selection; only a
+    /// redraw follows.
+    Redraw,
+    Flush,
+    SwitchWorkspace,
     Exit,
 }
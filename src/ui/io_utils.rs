@@ -1,4 +1,46 @@
-use std::io;
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    // Shared by every text prompt so entries typed earlier in the session
+    // can be recalled with the arrow keys.
+    static EDITOR: RefCell<Option<DefaultEditor>> = RefCell::new(DefaultEditor::new().ok());
+}
+
+/// How menu commands are read. Text fields always use line input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMode {
+    /// Single keypresses when the terminal supports it, lines otherwise.
+    #[default]
+    Auto,
+    Keypress,
+    /// Compatibility mode: every command is typed and confirmed with Enter.
+    Line,
+}
+
+impl InputMode {
+    pub fn resolve(self) -> InputMode {
+        match self {
+            InputMode::Auto => {
+                let is_dumb = env::var("TERM").map_or(true, |term| term == "dumb");
+                if is_dumb || !io::stdin().is_terminal() {
+                    InputMode::Line
+                } else {
+                    InputMode::Keypress
+                }
+            }
+            mode => mode,
+        }
+    }
+}
 
 pub fn get_user_input() -> String {
     let mut user_input = String::new();
@@ -6,6 +48,208 @@ pub fn get_user_input() -> String {
     user_input.trim().to_owned()
 }
 
+/// Reads a line of text with editing and history. Returns None when the user
+/// cancels with Ctrl-C or Ctrl-D.
+pub fn read_text(prompt: &str) -> Option<String> {
+    EDITOR.with(|editor| match editor.borrow_mut().as_mut() {
+        Some(editor) => match editor.readline(prompt) {
+            Ok(line) => {
+                let line = line.trim().to_owned();
+                if !line.is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                Some(line)
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => None,
+            Err(_) => {
+                println!("{}", prompt);
+                Some(get_user_input())
+            }
+        },
+        None => {
+            println!("{}", prompt);
+            Some(get_user_input())
+        }
+    })
+}
+
+/// Like `read_text`, with the line pre-filled with `initial` and the cursor
+/// at its end.
+pub fn edit_text(prompt: &str, initial: &str) -> Option<String> {
+    EDITOR.with(|editor| match editor.borrow_mut().as_mut() {
+        Some(editor) => match editor.readline_with_initial(prompt, (initial, "")) {
+            Ok(line) => Some(line.trim().to_owned()),
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => None,
+            Err(_) => {
+                println!("{}[{}]", prompt, initial);
+                Some(get_user_input())
+            }
+        },
+        None => {
+            println!("{}[{}]", prompt, initial);
+            Some(get_user_input())
+        }
+    })
+}
+
 pub fn wait_for_key_press() {
     io::stdin().read_line(&mut String::new()).unwrap();
 }
+
+/// Reads a menu command. In keypress mode a lowercase letter or a function
+/// key (as `f1`, `f2`, ...) is returned as soon as it is pressed; anything
+/// else (ids such as `12`, `PROJ-12` or `#proj-12`) is echoed and submitted
+/// with Enter. Returns `None` when Ctrl-C is pressed, which raw mode keeps
+/// from stopping the process.
+pub fn get_command(mode: InputMode) -> Option<String> {
+    if mode != InputMode::Keypress {
+        return Some(get_user_input());
+    }
+    match read_command_from_keys() {
+        Ok(command) => command,
+        Err(_) => Some(get_user_input()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum KeyOutcome {
+    Pending,
+    Submit(String),
+    Interrupt,
+}
+
+fn read_command_from_keys() -> io::Result<Option<String>> {
+    terminal::enable_raw_mode()?;
+    let mut buffer = String::new();
+    let outcome = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => key,
+            Ok(_) => continue,
+            Err(error) => {
+                terminal::disable_raw_mode()?;
+                return Err(error);
+            }
+        };
+        let previous_len = buffer.len();
+        match handle_key(&mut buffer, key) {
+            KeyOutcome::Pending => {
+                if buffer.len() > previous_len {
+                    print!("{}", &buffer[previous_len..]);
+                } else if buffer.len() < previous_len {
+                    print!("\u{8} \u{8}");
+                }
+                io::stdout().flush()?;
+            }
+            outcome => break outcome,
+        }
+    };
+    terminal::disable_raw_mode()?;
+    println!();
+    match outcome {
+        KeyOutcome::Submit(command) => Ok(Some(command)),
+        _ => Ok(None),
+    }
+}
+
+fn handle_key(buffer: &mut String, key: KeyEvent) -> KeyOutcome {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return match key.code {
+            KeyCode::Char('c') => KeyOutcome::Interrupt,
+            _ => KeyOutcome::Pending,
+        };
+    }
+    match key.code {
+        KeyCode::Char(c) if buffer.is_empty() && c.is_ascii_lowercase() => {
+            KeyOutcome::Submit(c.to_string())
+        }
+        KeyCode::F(number) if buffer.is_empty() => KeyOutcome::Submit(format!("f{}", number)),
+        KeyCode::Char(c) => {
+            buffer.push(c);
+            KeyOutcome::Pending
+        }
+        KeyCode::Backspace => {
+            buffer.pop();
+            KeyOutcome::Pending
+        }
+        KeyCode::Enter => KeyOutcome::Submit(buffer.trim().to_owned()),
+        KeyCode::Esc => {
+            buffer.clear();
+            KeyOutcome::Submit(String::new())
+        }
+        _ => KeyOutcome::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn handle_key_should_submit_letters_immediately() {
+        let mut buffer = String::new();
+        assert_eq!(
+            handle_key(&mut buffer, key(KeyCode::Char('c'))),
+            KeyOutcome::Submit("c".to_owned())
+        );
+    }
+
+    #[test]
+    fn handle_key_should_buffer_ids_until_enter() {
+        let mut buffer = String::new();
+        for c in "PROJ-1x".chars() {
+            assert_eq!(
+                handle_key(&mut buffer, key(KeyCode::Char(c))),
+                KeyOutcome::Pending
+            );
+        }
+        handle_key(&mut buffer, key(KeyCode::Backspace));
+        handle_key(&mut buffer, key(KeyCode::Char('2')));
+
+        assert_eq!(
+            handle_key(&mut buffer, key(KeyCode::Enter)),
+            KeyOutcome::Submit("PROJ-12".to_owned())
+        );
+    }
+
+    #[test]
+    fn handle_key_should_buffer_ids_starting_with_a_hash() {
+        let mut buffer = String::new();
+        for c in "#proj-12".chars() {
+            assert_eq!(
+                handle_key(&mut buffer, key(KeyCode::Char(c))),
+                KeyOutcome::Pending
+            );
+        }
+
+        assert_eq!(
+            handle_key(&mut buffer, key(KeyCode::Enter)),
+            KeyOutcome::Submit("#proj-12".to_owned())
+        );
+    }
+
+    #[test]
+    fn handle_key_should_submit_function_keys_immediately() {
+        let mut buffer = String::new();
+        assert_eq!(
+            handle_key(&mut buffer, key(KeyCode::F(2))),
+            KeyOutcome::Submit("f2".to_owned())
+        );
+    }
+
+    #[test]
+    fn handle_key_should_interrupt_on_ctrl_c() {
+        let mut buffer = String::new();
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(handle_key(&mut buffer, ctrl_c), KeyOutcome::Interrupt);
+    }
+
+    #[test]
+    fn resolve_should_keep_explicit_modes() {
+        assert_eq!(InputMode::Line.resolve(), InputMode::Line);
+        assert_eq!(InputMode::Keypress.resolve(), InputMode::Keypress);
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// Old ids of merged and renumbered items and what they open now.
+pub struct AliasesPage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+}
+
+impl Page for AliasesPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "----------------------------- ALIASES -----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let dead = db_state.dead_aliases();
+        let rows: Vec<Vec<String>> = db_state
+            .aliases
+            .keys()
+            .map(|id| {
+                let opens = match db_state.resolve(*id).filter(|_| !dead.contains(id)) {
+                    Some(target) => {
+                        let name = match db_state.epics.get(&target) {
+                            Some(epic) => &epic.name,
+                            None => &db_state.stories[&target].name,
+                        };
+                        format!("{} {}", self.config.id_format.format(target), name)
+                    }
+                    None => "(dead)".to_owned(),
+                };
+                vec![self.config.id_format.format(*id), opens]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "old id",
+                    width: 11,
+                },
+                Column {
+                    title: "opens",
+                    width: 40,
+                },
+            ],
+            &rows,
+        );
+        if rows.is_empty() {
+            renderer.write_line("No aliases: nothing was merged or renumbered yet.");
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [x] prune dead aliases");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "x" => Ok(Some(Action::PruneAliases)),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn draw_page_should_show_what_each_alias_opens() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_epic("Billing")
+            .build_dao();
+        dao.merge_epics(3, 1).unwrap();
+        let mut state = dao.read_db().unwrap();
+        state.aliases.insert(9, 8);
+        dao.replace_db(&state).unwrap();
+        let mut sut = AliasesPage {
+            dao,
+            config: make_config(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[2].contains("| 1 Payments"), true);
+        assert_eq!(renderer.lines[3].contains("| (dead)"), true);
+        assert_eq!(sut.handle_input("x").unwrap(), Some(Action::PruneAliases));
+    }
+}
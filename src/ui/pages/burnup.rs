@@ -0,0 +1,150 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::rc::Rc;
+
+use crate::burnup::epic_burnup;
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// Widest bar of the chart, for the day with the most scope.
+const CHART_WIDTH: usize = 30;
+
+/// Completed stories against the epic's scope over time, followed by the
+/// stories that came in or left once work had started.
+pub struct EpicBurnup {
+    pub epic_id: u32,
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+}
+
+impl Page for EpicBurnup {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "----------------------------- BURNUP -----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let burnup = epic_burnup(&db_state, self.epic_id, Utc::now())?;
+        let most_scope = burnup
+            .points
+            .iter()
+            .map(|point| point.scope)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let rows: Vec<Vec<String>> = burnup
+            .points
+            .iter()
+            .map(|point| {
+                let done = point.done * CHART_WIDTH / most_scope;
+                let scope = point.scope * CHART_WIDTH / most_scope;
+                vec![
+                    point.day.format("%Y-%m-%d").to_string(),
+                    format!("{}/{}", point.done, point.scope),
+                    format!("{}{}", "#".repeat(done), ".".repeat(scope - done)),
+                ]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "day",
+                    width: 10,
+                },
+                Column {
+                    title: "done",
+                    width: 7,
+                },
+                Column {
+                    title: "# done  . still to do",
+                    width: CHART_WIDTH,
+                },
+            ],
+            &rows,
+        );
+
+        renderer.write_line("");
+        match burnup.started_at {
+            None => renderer.write_line("No story has been started yet."),
+            Some(started_at) if burnup.scope_changes.is_empty() => renderer.write_line(&format!(
+                "No scope changes since work started on {}.",
+                self.config.time_display.format(&started_at)
+            )),
+            Some(started_at) => {
+                renderer.write_line(&format!(
+                    "Scope changes since work started on {}:",
+                    self.config.time_display.format(&started_at)
+                ));
+                for change in &burnup.scope_changes {
+                    let name = db_state
+                        .stories
+                        .get(&change.story_id)
+                        .map(|story| story.name.as_str())
+                        .unwrap_or("(deleted)");
+                    renderer.write_line(&format!(
+                        "  {} {} {} on {}",
+                        if change.added { "+" } else { "-" },
+                        self.config.id_format.format(change.story_id),
+                        name,
+                        self.config.time_display.format(&change.at)
+                    ));
+                }
+            }
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        models::{Status, Story},
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn draw_page_should_list_scope_added_after_the_start() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .build_dao();
+        dao.update_story_status(2, Status::InProgress).unwrap();
+        dao.create_story(Story::new("Disputes".to_owned(), "".to_owned()), 1)
+            .unwrap();
+        let sut = EpicBurnup {
+            epic_id: 1,
+            dao,
+            config: make_config(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        let contents = renderer.contents();
+        assert_eq!(contents.contains("| 0/2"), true);
+        assert_eq!(contents.contains("  + 3 Disputes on "), true);
+    }
+}
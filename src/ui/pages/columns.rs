@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dao::{EpicSummary, StorySummary};
+use crate::id_format::IdFormat;
+
+use super::renderer::Column;
+
+/// A column that can be shown in the epic and story listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListColumn {
+    Id,
+    Name,
+    Description,
+    Status,
+    /// Stories done for an epic, acceptance criteria checked for a story.
+    Progress,
+}
+
+impl ListColumn {
+    pub fn column(self) -> Column {
+        let (title, width) = match self {
+            ListColumn::Id => ("id", 11),
+            ListColumn::Name => ("name", 32),
+            ListColumn::Description => ("description", 27),
+            ListColumn::Status => ("status", 17),
+            ListColumn::Progress => ("progress", 10),
+        };
+        Column { title, width }
+    }
+
+    pub fn epic_cell(self, epic: &EpicSummary, id_format: &IdFormat) -> String {
+        match self {
+            ListColumn::Id => id_format.format(epic.id),
+            ListColumn::Name => epic.name.clone(),
+            ListColumn::Description => epic.description.clone(),
+            ListColumn::Status => epic.status.to_string(),
+            ListColumn::Progress => format!("{}/{}", epic.stories_done, epic.stories_total),
+        }
+    }
+
+    pub fn story_cell(self, story: &StorySummary, id_format: &IdFormat) -> String {
+        match self {
+            ListColumn::Id => id_format.format(story.id),
+            ListColumn::Name => story.name.clone(),
+            ListColumn::Description => story.description.clone(),
+            ListColumn::Status => story.status.to_string(),
+            ListColumn::Progress => format!("{}/{}", story.criteria_done, story.criteria_total),
+        }
+    }
+}
+
+/// Which columns the listings show, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListColumns {
+    pub epics: Vec<ListColumn>,
+    pub stories: Vec<ListColumn>,
+}
+
+impl Default for ListColumns {
+    fn default() -> Self {
+        let columns = vec![ListColumn::Id, ListColumn::Name, ListColumn::Status];
+        Self {
+            epics: columns.clone(),
+            stories: columns,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Status;
+
+    #[test]
+    fn list_columns_should_parse_partial_config() {
+        let columns: ListColumns =
+            serde_json::from_str(r#"{ "stories": ["name", "progress", "id"] }"#).unwrap();
+
+        assert_eq!(columns.epics, ListColumns::default().epics);
+        assert_eq!(
+            columns.stories,
+            vec![ListColumn::Name, ListColumn::Progress, ListColumn::Id]
+        );
+    }
+
+    #[test]
+    fn story_cell_should_show_criteria_progress() {
+        let story = StorySummary {
+            id: 7,
+            name: "Refunds".to_owned(),
+            description: "".to_owned(),
+            status: Status::Open,
+            criteria_total: 3,
+            criteria_done: 1,
+            ready: false,
+            blocked_by: vec![],
+            external_blockers: 0,
+        };
+
+        assert_eq!(
+            ListColumn::Progress.story_cell(&story, &IdFormat::default()),
+            "1/3"
+        );
+        assert_eq!(ListColumn::Id.story_cell(&story, &IdFormat::default()), "7");
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::{DependencyView, JiraDAO};
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// The stories of an epic in the order their blockers allow. Stories on the
+/// critical path are marked with `*`.
+pub struct DependencyPage {
+    pub epic_id: u32,
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+}
+
+impl Page for DependencyPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "-------------------------- DEPENDENCIES --------------------------",
+        )]);
+
+        let DependencyView { plan, stories } = self.dao.get_dependency_view(self.epic_id)?;
+        let format_ids = |ids: &[u32]| {
+            ids.iter()
+                .map(|id| self.config.id_format.format(*id))
+                .join(", ")
+        };
+        let rows: Vec<Vec<String>> = stories
+            .iter()
+            .enumerate()
+            .map(|(position, story)| {
+                let marker = if plan.critical_path.contains(&story.id) {
+                    "*"
+                } else {
+                    " "
+                };
+                let step = if position < plan.order.len() {
+                    (position + 1).to_string()
+                } else {
+                    "-".to_owned()
+                };
+                vec![
+                    format!("{} {}", marker, step),
+                    self.config.id_format.format(story.id),
+                    story.name.clone(),
+                    story.status.to_string(),
+                    match story.external_blockers {
+                        0 => format_ids(&story.blocked_by),
+                        outside if story.blocked_by.is_empty() => {
+                            format!("+{} outside", outside)
+                        }
+                        outside => {
+                            format!("{} +{} outside", format_ids(&story.blocked_by), outside)
+                        }
+                    },
+                ]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "#",
+                    width: 5,
+                },
+                Column {
+                    title: "id",
+                    width: 11,
+                },
+                Column {
+                    title: "name",
+                    width: 24,
+                },
+                Column {
+                    title: "status",
+                    width: 13,
+                },
+                Column {
+                    title: "blocked by",
+                    width: 16,
+                },
+            ],
+            &rows,
+        );
+
+        renderer.write_line("");
+        renderer.write_line(&format!(
+            "Critical path: {} stories ({})",
+            plan.critical_path.len(),
+            plan.critical_path
+                .iter()
+                .map(|id| self.config.id_format.format(*id))
+                .join(" > ")
+        ));
+        if !plan.cyclic.is_empty() {
+            renderer.write_spans(&[Span::bold(&format!(
+                "Cycle: {} can't be ordered until a blocker is removed",
+                format_ids(&plan.cyclic)
+            ))]);
+        }
+
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [:id:] navigate to story");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            input => {
+                if let Some(story_id) = self.config.id_format.parse(input) {
+                    let stories = self.dao.list_stories_of_epic(self.epic_id)?;
+                    if stories.iter().any(|story| story.id == story_id) {
+                        return Ok(Some(Action::NavigateToStoryDetail {
+                            epic_id: self.epic_id,
+                            story_id,
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> DependencyPage {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("release")
+            .with_story_blocked_by(3)
+            .with_story("build")
+            .with_story("loop a")
+            .with_story_blocked_by(5)
+            .with_story("loop b")
+            .with_story_blocked_by(4)
+            .with_story_external_blocker("https://tracker.example.com/OPS-7", "firewall")
+            .build_dao();
+        DependencyPage {
+            epic_id: 1,
+            dao,
+            config: make_config(),
+        }
+    }
+
+    #[test]
+    fn draw_page_should_order_stories_and_flag_cycles() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[2].starts_with("* 1   | 3 "), true);
+        assert_eq!(renderer.lines[3].starts_with("* 2   | 2 "), true);
+        assert_eq!(renderer.lines[4].starts_with("  -   | 4 "), true);
+        assert_eq!(
+            renderer
+                .contents()
+                .contains("Critical path: 2 stories (3 > 2)"),
+            true
+        );
+        assert_eq!(renderer.contents().contains("Cycle: 4, 5"), true);
+        assert_eq!(renderer.contents().contains("4 +1 outside"), true);
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("3").unwrap(),
+            Some(Action::NavigateToStoryDetail {
+                epic_id: 1,
+                story_id: 3
+            })
+        );
+        assert_eq!(sut.handle_input("1").unwrap(), None);
+        assert_eq!(sut.handle_input("junk").unwrap(), None);
+    }
+}
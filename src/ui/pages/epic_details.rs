@@ -1,82 +1,233 @@
-use anyhow::{anyhow, Result};
-use itertools::Itertools;
+use anyhow::Result;
+use chrono::Utc;
 use std::rc::Rc;
 
+use crate::config::Config;
 use crate::dao::JiraDAO;
 use crate::ui::actions::Action;
-use crate::ui::pages::page_helpers::get_column_string;
 
+use super::columns::ListColumn;
 use super::page::Page;
+use super::page_helpers::{epic_column, status_cell};
+use super::renderer::{Column, Renderer, Span};
+use super::status_filter::StatusFilter;
 
 pub struct EpicDetail {
     pub epic_id: u32,
     pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub status_filter: StatusFilter,
 }
 
 impl Page for EpicDetail {
-    fn draw_page(&self) -> Result<()> {
-        let dao_state = self.dao.read_db()?;
-        let epic = dao_state
-            .epics
-            .get(&self.epic_id)
-            .ok_or_else(|| anyhow!("could not find epic!"))?;
-
-        println!("------------------------------ EPIC ------------------------------");
-        println!(" id |     name     |         description         |    status    ");
-
-        let id_col = get_column_string(&self.epic_id.to_string(), 3);
-        let name_col = get_column_string(&epic.name, 12);
-        let description_col = get_column_string(&epic.description, 27);
-        let status_col = get_column_string(&epic.status.to_string(), 15);
-        println!(
-            "{} | {} | {} | {}",
-            id_col, name_col, description_col, status_col
-        );
-
-        println!();
-
-        println!("---------------------------- STORIES ----------------------------");
-        println!("     id     |               name               |      status      ");
-
-        let stories = &dao_state.stories;
-        for id in stories.keys().sorted() {
-            let story = &stories[id];
-            let id_col = get_column_string(&id.to_string(), 11);
-            let name_col = get_column_string(&story.name, 32);
-            let status_col = get_column_string(&story.status.to_string(), 17);
-            println!("{} | {} | {}", id_col, name_col, status_col);
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        let epic = self.dao.get_epic(self.epic_id)?;
+
+        renderer.write_spans(&[Span::bold(
+            "------------------------------ EPIC ------------------------------",
+        )]);
+        renderer.write_table(
+            &[
+                Column {
+                    title: "id",
+                    width: 10,
+                },
+                Column {
+                    title: "name",
+                    width: 12,
+                },
+                Column {
+                    title: "description",
+                    width: 27,
+                },
+                Column {
+                    title: "status",
+                    width: 15,
+                },
+            ],
+            &[vec![
+                self.config.id_format.format(self.epic_id),
+                epic.name,
+                epic.description,
+                epic.status.to_string(),
+            ]],
+        );
+        if let Some(source_id) = epic.split_from {
+            renderer.write_line(&format!(
+                "Split from: {}",
+                self.config.id_format.format(source_id)
+            ));
+        }
+        if !epic.merged_from.is_empty() {
+            renderer.write_line(&format!("Merged from: {}", epic.merged_from.join(", ")));
+        }
+        if !epic.split_into.is_empty() {
+            renderer.write_line(&format!(
+                "Split into: {}",
+                epic.split_into
+                    .iter()
+                    .map(|id| self.config.id_format.format(*id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
         }
 
-        println!();
-        println!();
+        renderer.write_line("");
 
-        println!("[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story");
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- STORIES ----------------------------",
+        )]);
+        if let Some(chips) = self.status_filter.chips() {
+            renderer.write_spans(&[Span::dim("showing "), Span::bold(&chips)]);
+        }
+
+        let columns = &self.config.columns.stories;
+        let db_state = self.dao.read_db()?;
+        let now = Utc::now();
+        let rows: Vec<Vec<String>> = self
+            .dao
+            .list_stories_of_epic(self.epic_id)?
+            .into_iter()
+            .filter(|story| {
+                self.status_filter.matches(&story.status)
+                    && self.status_filter.matches_readiness(story.ready)
+            })
+            .map(|story| {
+                columns
+                    .iter()
+                    .map(|column| match (column, db_state.stories.get(&story.id)) {
+                        (ListColumn::Status, Some(full_story)) => {
+                            status_cell(full_story, &self.config, now)
+                        }
+                        _ => column.story_cell(&story, &self.config.id_format),
+                    })
+                    .collect()
+            })
+            .collect();
+        renderer.write_table(
+            &columns
+                .iter()
+                .map(|column| column.column())
+                .collect::<Vec<_>>(),
+            &rows,
+        );
+
+        let mirrors: Vec<Vec<String>> = epic
+            .mirrors
+            .iter()
+            .filter_map(|id| {
+                let story = db_state.stories.get(id)?;
+                Some(vec![
+                    self.config.id_format.format(*id),
+                    story.name.clone(),
+                    status_cell(story, &self.config, now),
+                    epic_column(&db_state, *id, &self.config),
+                ])
+            })
+            .collect();
+        if !mirrors.is_empty() {
+            renderer.write_line("");
+            renderer.write_spans(&[Span::bold(
+                "---------------------------- MIRRORED ----------------------------",
+            )]);
+            renderer.write_table(
+                &[
+                    Column {
+                        title: "id",
+                        width: 11,
+                    },
+                    Column {
+                        title: "name",
+                        width: 27,
+                    },
+                    Column {
+                        title: "status",
+                        width: 15,
+                    },
+                    Column {
+                        title: "epic",
+                        width: 20,
+                    },
+                ],
+                &mirrors,
+            );
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+
+        renderer.write_line("[p] previous | [u] update epic | [e] edit | [d] delete epic | [c] create story | [f] toggle focus | [y] yank | [h] export html | [o] dependencies | [t] timeline | [b] burnup | [s] split epic | [m] merge into | [i] mirror story | [F1-F4] filter status | [F5] ready only | [:id:] navigate to story");
 
         Ok(())
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        let db_state = self.dao.read_db()?;
-        let stories = db_state.stories;
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        if self.status_filter.handle_key(input) {
+            return Ok(Some(Action::Redraw));
+        }
+        if input.eq_ignore_ascii_case("f5") {
+            self.status_filter.toggle_ready_only();
+            return Ok(Some(Action::Redraw));
+        }
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
             "u" => Ok(Some(Action::UpdateEpicStatus {
                 epic_id: self.epic_id,
             })),
+            "e" => Ok(Some(Action::EditEpic {
+                epic_id: self.epic_id,
+            })),
             "d" => Ok(Some(Action::DeleteEpic {
                 epic_id: self.epic_id,
             })),
             "c" => Ok(Some(Action::CreateStory {
                 epic_id: self.epic_id,
             })),
+            "f" => Ok(Some(Action::ToggleEpicFocus {
+                epic_id: self.epic_id,
+            })),
+            "y" => Ok(Some(Action::YankEpic {
+                epic_id: self.epic_id,
+            })),
+            "h" => Ok(Some(Action::ExportEpicReport {
+                epic_id: self.epic_id,
+            })),
+            "s" => Ok(Some(Action::SplitEpic {
+                epic_id: self.epic_id,
+            })),
+            "m" => Ok(Some(Action::MergeEpic {
+                epic_id: self.epic_id,
+            })),
+            "o" => Ok(Some(Action::NavigateToDependencies {
+                epic_id: self.epic_id,
+            })),
+            "t" => Ok(Some(Action::NavigateToTimeline {
+                epic_id: self.epic_id,
+            })),
+            "b" => Ok(Some(Action::NavigateToBurnup {
+                epic_id: self.epic_id,
+            })),
+            "i" => Ok(Some(Action::ToggleMirror {
+                epic_id: self.epic_id,
+            })),
             input => {
-                if let Ok(story_id) = input.parse::<u32>() {
-                    if stories.contains_key(&story_id) {
+                if let Some(story_id) = self.config.id_format.parse(input) {
+                    let stories = self.dao.list_stories_of_epic(self.epic_id)?;
+                    if stories.iter().any(|story| story.id == story_id) {
                         return Ok(Some(Action::NavigateToStoryDetail {
                             epic_id: self.epic_id,
                             story_id,
                         }));
                     }
+                    // mirrored stories open in their own epic
+                    let db_state = self.dao.read_db()?;
+                    let mirrored = db_state
+                        .epics
+                        .get(&self.epic_id)
+                        .is_some_and(|epic| epic.mirrors.contains(&story_id));
+                    if let Some(epic_id) = db_state.epic_of(story_id).filter(|_| mirrored) {
+                        return Ok(Some(Action::NavigateToStoryDetail { epic_id, story_id }));
+                    }
                 }
                 Ok(None)
             }
@@ -91,61 +242,217 @@ impl Page for EpicDetail {
 #[cfg(test)]
 mod tests {
     use crate::{
-        models::{Epic, Story},
+        fixtures::DbFixtureBuilder,
+        models::Status,
         ui::pages::{
-            page_test_utils::{create_epic_and_story, make_dao},
-            story_details::StoryDetail,
+            page_test_utils::make_config, renderer::BufferRenderer, story_details::StoryDetail,
         },
     };
 
     use super::*;
 
     fn make_sut() -> StoryDetail {
-        let dao = make_dao();
-        let (epic_id, story_id) = create_epic_and_story(&dao);
+        let fixture = DbFixtureBuilder::new().with_epic("").with_story("");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
         StoryDetail {
             epic_id,
             story_id,
             dao,
+            config: make_config(),
+            time_format_toggled: false,
         }
     }
 
     #[test]
     fn draw_page_should_not_throw_error() {
         let sut = make_sut();
-        assert_eq!(sut.draw_page().is_ok(), true);
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_ok(), true);
     }
 
     #[test]
-    fn handle_input_should_not_throw_error() {
+    fn draw_page_should_show_epic_progress_and_comments() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story_status(Status::Closed)
+            .with_story("Invoices")
+            .with_story_comment("waiting on finance");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let story_id = fixture.last_story_id().unwrap();
+        let sut = StoryDetail {
+            epic_id,
+            story_id,
+            dao: fixture.build_dao(),
+            config: make_config(),
+            time_format_toggled: false,
+        };
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.lines[0],
+            "EPIC 1 > Payments | OPEN | 1/2 stories done (50%)"
+        );
+        assert_eq!(renderer.contents().contains("| waiting on finance"), true);
+    }
+
+    #[test]
+    fn draw_page_should_thread_comments() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("")
+            .with_story("")
+            .with_story_comment("which card types?")
+            .with_story_comment("blocked on finance");
+        let sut = StoryDetail {
+            epic_id: fixture.last_epic_id().unwrap(),
+            story_id: fixture.last_story_id().unwrap(),
+            dao: fixture.build_dao(),
+            config: make_config(),
+            time_format_toggled: true,
+        };
+        sut.dao
+            .reply_to_comment(sut.story_id, 1, "visa only".to_owned())
+            .unwrap();
+        sut.dao
+            .edit_comment(sut.story_id, 2, "finance signed off".to_owned())
+            .unwrap();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        let contents = renderer.contents();
+        let first = contents.find("C-1 just now | which card types?").unwrap();
+        let reply = contents.find("\n  C-3 just now | visa only").unwrap();
+        let second = contents
+            .find("C-2 just now | finance signed off (edited)")
+            .unwrap();
+        assert_eq!(first < reply && reply < second, true);
+    }
+
+    #[test]
+    fn handle_input_should_toggle_relative_comment_times() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("")
+            .with_story("")
+            .with_story_comment("on it");
+        let mut sut = StoryDetail {
+            epic_id: fixture.last_epic_id().unwrap(),
+            story_id: fixture.last_story_id().unwrap(),
+            dao: fixture.build_dao(),
+            config: make_config(),
+            time_format_toggled: false,
+        };
+        let draw = |sut: &StoryDetail| {
+            let mut renderer = BufferRenderer::new();
+            sut.draw_page(&mut renderer).unwrap();
+            renderer.contents()
+        };
+        assert_eq!(draw(&sut).contains("just now | on it"), false);
+
+        assert_eq!(sut.handle_input("r").unwrap(), Some(Action::Redraw));
+        assert_eq!(draw(&sut).contains("just now | on it"), true);
+    }
+
+    #[test]
+    fn draw_page_should_number_acceptance_criteria() {
         let sut = make_sut();
+        sut.dao
+            .add_acceptance_criterion(sut.story_id, "logs in".to_owned())
+            .unwrap();
+        sut.dao
+            .add_acceptance_criterion(sut.story_id, "logs out".to_owned())
+            .unwrap();
+        sut.dao
+            .toggle_acceptance_criterion(sut.story_id, 1)
+            .unwrap();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.contents().contains("AC-1 [x] logs in"), true);
+        assert_eq!(renderer.contents().contains("AC-2 [ ] logs out"), true);
+    }
+
+    #[test]
+    fn draw_page_should_list_external_blockers() {
+        let sut = make_sut();
+        sut.dao
+            .toggle_external_blocker(
+                sut.story_id,
+                "https://tracker.example.com/OPS-7",
+                "firewall change",
+            )
+            .unwrap();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer
+                .contents()
+                .contains("firewall change <https://tracker.example.com/OPS-7>"),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_input_should_not_throw_error() {
+        let mut sut = make_sut();
         assert_eq!(sut.handle_input("").is_ok(), true);
     }
 
     #[test]
     fn draw_page_should_throw_error_for_invalid_story_id() {
-        let dao = make_dao();
-        let epic_id = dao
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
-            .unwrap();
-        let _ = dao
-            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
-            .unwrap();
+        let fixture = DbFixtureBuilder::new().with_epic("").with_story("");
+        let epic_id = fixture.last_epic_id().unwrap();
+        let dao = fixture.build_dao();
         let sut = StoryDetail {
             epic_id,
             story_id: 999,
             dao,
+            config: make_config(),
+            time_format_toggled: false,
         };
-        assert_eq!(sut.draw_page().is_err(), true);
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_err(), true);
+    }
+
+    #[test]
+    fn draw_page_should_throw_error_for_invalid_epic_id() {
+        let fixture = DbFixtureBuilder::new().with_epic("").with_story("");
+        let story_id = fixture.last_story_id().unwrap();
+        let dao = fixture.build_dao();
+        let sut = StoryDetail {
+            epic_id: 999,
+            story_id,
+            dao,
+            config: make_config(),
+            time_format_toggled: false,
+        };
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_err(), true);
+    }
+
+    #[test]
+    fn handle_input_should_offer_branches_only_with_a_git_repo() {
+        let mut sut = make_sut();
+        let story_id = sut.story_id;
+        assert_eq!(sut.handle_input("b").unwrap(), None);
+
+        sut.config = Rc::new(Config {
+            git_repo: Some(".".to_owned()),
+            ..Config::default()
+        });
+        assert_eq!(
+            sut.handle_input("b").unwrap(),
+            Some(Action::CreateStoryBranch { story_id })
+        );
     }
 
     #[test]
     fn handle_input_should_return_the_correct_actions() {
-        let sut = make_sut();
+        let mut sut = make_sut();
         let story_id = sut.story_id;
         let epic_id = sut.epic_id;
 
-        let (p, u, d) = ("p", "u", "d");
+        let (p, g, u, x, y, d) = ("p", "g", "u", "x", "y", "d");
         let (junk_input, junk_input_with_valid_prefix, input_with_trailing_white_spaces) =
             ("j983f2j", "p983f2j", "p\n");
         let some_number = "1";
@@ -154,10 +461,82 @@ mod tests {
             sut.handle_input(p).unwrap(),
             Some(Action::NavigateToPreviousPage)
         );
+        assert_eq!(
+            sut.handle_input(g).unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id })
+        );
         assert_eq!(
             sut.handle_input(u).unwrap(),
             Some(Action::UpdateStoryStatus { story_id })
         );
+        assert_eq!(
+            sut.handle_input("k").unwrap(),
+            Some(Action::SetStoryKind { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("!").unwrap(),
+            Some(Action::RunPlugin { story_id })
+        );
+        assert_eq!(
+            sut.handle_input(x).unwrap(),
+            Some(Action::CloseStoryWithComment { story_id })
+        );
+        assert_eq!(
+            sut.handle_input(y).unwrap(),
+            Some(Action::YankStory { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("a").unwrap(),
+            Some(Action::QueueStory { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("f").unwrap(),
+            Some(Action::ToggleWatch { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("l").unwrap(),
+            Some(Action::ToggleBlocker { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("o").unwrap(),
+            Some(Action::ToggleExternalBlocker { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("s").unwrap(),
+            Some(Action::EstimateStory { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("c").unwrap(),
+            Some(Action::AddComment { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("h").unwrap(),
+            Some(Action::ReplyToComment { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("e").unwrap(),
+            Some(Action::EditStory { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("w").unwrap(),
+            Some(Action::EditComment { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("z").unwrap(),
+            Some(Action::DeleteComment { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("m").unwrap(),
+            Some(Action::SetReminder { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("n").unwrap(),
+            Some(Action::AddAcceptanceCriterion { story_id })
+        );
+        assert_eq!(
+            sut.handle_input("t").unwrap(),
+            Some(Action::ToggleAcceptanceCriterion { story_id })
+        );
         assert_eq!(
             sut.handle_input(d).unwrap(),
             Some(Action::DeleteStory { epic_id, story_id })
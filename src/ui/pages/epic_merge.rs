@@ -0,0 +1,163 @@
+use anyhow::Result;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// Shows the target epic as it will look once the source epic is merged
+/// into it. Nothing changes until the merge is confirmed.
+pub struct EpicMergePreview {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub source_id: u32,
+    pub target_id: u32,
+}
+
+impl Page for EpicMergePreview {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "-------------------------- MERGE PREVIEW --------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let merged = self
+            .dao
+            .preview_epic_merge(self.source_id, self.target_id)?;
+        renderer.write_line(&format!(
+            "Merging {} {} into {} {}",
+            self.config.id_format.format(self.source_id),
+            db_state.epics[&self.source_id].name,
+            self.config.id_format.format(self.target_id),
+            merged.name
+        ));
+        renderer.write_line("");
+        renderer.write_line(&format!("Status: {}", merged.status));
+        renderer.write_line("Description:");
+        for line in merged.description.lines() {
+            renderer.write_line(&format!("  {}", line));
+        }
+        renderer.write_line(&format!("Merged from: {}", merged.merged_from.join(", ")));
+
+        renderer.write_line("");
+        let source_stories = &db_state.epics[&self.source_id].stories;
+        let rows: Vec<Vec<String>> = merged
+            .stories
+            .iter()
+            .filter_map(|id| {
+                let story = db_state.stories.get(id)?;
+                let marker = if source_stories.contains(id) {
+                    "+"
+                } else {
+                    " "
+                };
+                Some(vec![
+                    marker.to_owned(),
+                    self.config.id_format.format(*id),
+                    story.name.clone(),
+                    story.status.to_string(),
+                ])
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "",
+                    width: 1,
+                },
+                Column {
+                    title: "id",
+                    width: 11,
+                },
+                Column {
+                    title: "name",
+                    width: 32,
+                },
+                Column {
+                    title: "status",
+                    width: 13,
+                },
+            ],
+            &rows,
+        );
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] cancel | [y] merge and delete the source epic");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "y" => Ok(Some(Action::ConfirmEpicMerge {
+                source_id: self.source_id,
+                target_id: self.target_id,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> EpicMergePreview {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Cards")
+            .with_epic("Billing")
+            .with_story("Invoices")
+            .build_dao();
+        EpicMergePreview {
+            dao,
+            config: make_config(),
+            source_id: 3,
+            target_id: 1,
+        }
+    }
+
+    #[test]
+    fn draw_page_should_show_the_merged_epic() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[1], "Merging 3 Billing into 1 Payments");
+        assert_eq!(renderer.contents().contains("Merged from: Billing"), true);
+        assert_eq!(renderer.contents().contains("+ | 4 "), true);
+        assert_eq!(sut.dao.read_db().unwrap().epics.len(), 2);
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("y").unwrap(),
+            Some(Action::ConfirmEpicMerge {
+                source_id: 3,
+                target_id: 1
+            })
+        );
+        assert_eq!(sut.handle_input("m").unwrap(), None);
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::forecast::{forecast_epics, weekly_throughput, FORECAST_RUNS};
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// Likely completion dates of every unfinished epic, simulated from the
+/// stories finished per week so far.
+pub struct ForecastPage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+}
+
+impl Page for ForecastPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- FORECAST ----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let now = Utc::now();
+        let throughput = weekly_throughput(&db_state, now);
+        let forecasts = forecast_epics(&db_state, &throughput, now);
+        if throughput.iter().all(|count| *count == 0) {
+            renderer.write_line(
+                "No story was finished lately, so there is nothing to forecast from yet.",
+            );
+        } else {
+            renderer.write_line(&format!(
+                "Stories finished in each of the last {} weeks: {}. Dates are when {} simulated runs finished the epic.",
+                throughput.len(),
+                throughput.iter().join(", "),
+                FORECAST_RUNS
+            ));
+            let date = |day: Option<NaiveDate>| {
+                day.map(|day| day.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "never".to_owned())
+            };
+            let rows: Vec<Vec<String>> = forecasts
+                .iter()
+                .map(|forecast| {
+                    vec![
+                        self.config.id_format.format(forecast.epic_id),
+                        db_state.epics[&forecast.epic_id].name.clone(),
+                        forecast.remaining.to_string(),
+                        date(forecast.p50),
+                        date(forecast.p85),
+                        date(forecast.p95),
+                    ]
+                })
+                .collect();
+            renderer.write_table(
+                &[
+                    Column {
+                        title: "id",
+                        width: 11,
+                    },
+                    Column {
+                        title: "epic",
+                        width: 25,
+                    },
+                    Column {
+                        title: "left",
+                        width: 5,
+                    },
+                    Column {
+                        title: "50%",
+                        width: 10,
+                    },
+                    Column {
+                        title: "85%",
+                        width: 10,
+                    },
+                    Column {
+                        title: "95%",
+                        width: 10,
+                    },
+                ],
+                &rows,
+            );
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [:id:] navigate to epic");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            input => {
+                if let Some(epic_id) = self.config.id_format.parse(input) {
+                    if self.dao.get_epic(epic_id).is_ok() {
+                        return Ok(Some(Action::NavigateToEpicDetail { epic_id }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        models::Status,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn draw_page_should_forecast_unfinished_epics() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build_dao();
+        dao.update_story_status(2, Status::Closed).unwrap();
+        let mut sut = ForecastPage {
+            dao,
+            config: make_config(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        let contents = renderer.contents();
+        assert_eq!(contents.contains("last 1 weeks: 1."), true);
+        assert_eq!(contents.contains("| Payments"), true);
+        assert_eq!(
+            sut.handle_input("1").unwrap(),
+            Some(Action::NavigateToEpicDetail { epic_id: 1 })
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::{Action, GroomOutcome};
+
+use super::page::Page;
+use super::page_helpers::epic_column;
+use super::renderer::{Column, Renderer, Span};
+
+/// Walks through today's grooming pick one story at a time. Every key that
+/// decides on the current story moves on to the next one.
+pub struct GroomingPage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub story_ids: Vec<u32>,
+    pub position: usize,
+}
+
+impl GroomingPage {
+    fn current_story_id(&self) -> Option<u32> {
+        self.story_ids.get(self.position).copied()
+    }
+
+    fn decide(&mut self, outcome: Option<GroomOutcome>) -> Option<Action> {
+        let story_id = self.current_story_id()?;
+        self.position += 1;
+        Some(
+            outcome.map_or(Action::Redraw, |outcome| Action::GroomStory {
+                story_id,
+                outcome,
+            }),
+        )
+    }
+}
+
+impl Page for GroomingPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- GROOMING ----------------------------",
+        )]);
+
+        let story = match self.current_story_id() {
+            Some(story_id) => self
+                .dao
+                .get_story(story_id)
+                .ok()
+                .map(|story| (story_id, story)),
+            None => None,
+        };
+        match story {
+            Some((story_id, story)) => {
+                renderer.write_line(&format!(
+                    "Story {} of {}",
+                    self.position + 1,
+                    self.story_ids.len()
+                ));
+                renderer.write_table(
+                    &[
+                        Column {
+                            title: "id",
+                            width: 11,
+                        },
+                        Column {
+                            title: "name",
+                            width: 32,
+                        },
+                        Column {
+                            title: "description",
+                            width: 27,
+                        },
+                        Column {
+                            title: "epic",
+                            width: 20,
+                        },
+                    ],
+                    &[vec![
+                        self.config.id_format.format(story_id),
+                        story.name,
+                        story.description,
+                        epic_column(&self.dao.read_db()?, story_id, &self.config),
+                    ]],
+                );
+                renderer.write_line("");
+                renderer.write_line("");
+                renderer.write_line(
+                    "[p] previous | [k] keep | [c] close | [s] mark stale | [b] bump to next up",
+                );
+            }
+            None => {
+                renderer.write_line("Nothing left to groom today.");
+                renderer.write_line("");
+                renderer.write_line("[p] previous");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "k" => Ok(self.decide(None)),
+            "c" => Ok(self.decide(Some(GroomOutcome::Close))),
+            "s" => Ok(self.decide(Some(GroomOutcome::MarkStale))),
+            "b" => Ok(self.decide(Some(GroomOutcome::Bump))),
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> GroomingPage {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("first")
+            .with_story("second")
+            .build_dao();
+        GroomingPage {
+            dao,
+            config: make_config(),
+            story_ids: vec![3, 2],
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn draw_page_should_show_the_current_story() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.contents().contains("Story 1 of 2"), true);
+        assert_eq!(renderer.lines[3].starts_with("3 "), true);
+        assert_eq!(renderer.contents().contains("second"), true);
+    }
+
+    #[test]
+    fn draw_page_should_say_when_everything_is_groomed() {
+        let mut sut = make_sut();
+        sut.position = 2;
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.contents().contains("Nothing left to groom today."),
+            true
+        );
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(sut.handle_input("k").unwrap(), Some(Action::Redraw));
+        assert_eq!(sut.position, 1);
+        assert_eq!(
+            sut.handle_input("b").unwrap(),
+            Some(Action::GroomStory {
+                story_id: 2,
+                outcome: GroomOutcome::Bump
+            })
+        );
+        assert_eq!(sut.handle_input("c").unwrap(), None);
+        assert_eq!(sut.position, 2);
+        assert_eq!(sut.handle_input("junk").unwrap(), None);
+    }
+}
@@ -1,47 +1,88 @@
 use anyhow::Result;
-use itertools::Itertools;
 use std::rc::Rc;
 
+use crate::config::Config;
 use crate::dao::JiraDAO;
 use crate::ui::actions::Action;
-use crate::ui::pages::page_helpers::get_column_string;
 
 use super::page::Page;
+use super::renderer::{Renderer, Span};
+use super::status_filter::StatusFilter;
 
 pub struct HomePage {
     pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub status_filter: StatusFilter,
 }
 
 impl Page for HomePage {
-    fn draw_page(&self) -> Result<()> {
-        println!("----------------------------- EPICS -----------------------------");
-        println!("     id     |               name               |      status      ");
-
-        let epics = self.dao.read_db()?.epics;
-        for id in epics.keys().sorted() {
-            let epic = &epics[id];
-            let id_col = get_column_string(&id.to_string(), 11);
-            let name_col = get_column_string(&epic.name, 32);
-            let status_col = get_column_string(&epic.status.to_string(), 17);
-            println!("{} | {} | {}", id_col, name_col, status_col);
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "----------------------------- EPICS -----------------------------",
+        )]);
+        if let Some(chips) = self.status_filter.chips() {
+            renderer.write_spans(&[Span::dim("showing "), Span::bold(&chips)]);
         }
 
-        println!();
-        println!();
+        let columns = &self.config.columns.epics;
+        let rows: Vec<Vec<String>> = self
+            .dao
+            .list_epics_summary()?
+            .into_iter()
+            .filter(|epic| self.status_filter.matches(&epic.status))
+            .map(|epic| {
+                columns
+                    .iter()
+                    .map(|column| column.epic_cell(&epic, &self.config.id_format))
+                    .collect()
+            })
+            .collect();
+        renderer.write_table(
+            &columns
+                .iter()
+                .map(|column| column.column())
+                .collect::<Vec<_>>(),
+            &rows,
+        );
+
+        renderer.write_line("");
+        renderer.write_line("");
 
-        println!("[q] quit | [c] create epic | [:id:] navigate to epic");
+        let mut menu =
+            "[q] quit | [c] create epic | [n] next up | [g] groom | [f] refine | [r] reminders | [l] watchlist | [v] forecast | [a] aliases | [e] recipes | [F1-F4] filter status"
+                .to_owned();
+        if self.config.write_behind_ms.is_some() {
+            menu.push_str(" | [w] save now");
+        }
+        if !self.config.workspaces.is_empty() {
+            menu.push_str(" | [s] switch workspace");
+        }
+        menu.push_str(" | [:id:] navigate to epic | [#id] jump to any item");
+        renderer.write_line(&menu);
 
         Ok(())
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        let epics = self.dao.read_db()?.epics;
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        if self.status_filter.handle_key(input) {
+            return Ok(Some(Action::Redraw));
+        }
         match input {
             "q" => Ok(Some(Action::Exit)),
             "c" => Ok(Some(Action::CreateEpic)),
+            "n" => Ok(Some(Action::NavigateToQueue)),
+            "g" => Ok(Some(Action::NavigateToGrooming)),
+            "r" => Ok(Some(Action::NavigateToReminders)),
+            "f" => Ok(Some(Action::NavigateToRefinement)),
+            "l" => Ok(Some(Action::NavigateToWatchlist)),
+            "v" => Ok(Some(Action::NavigateToForecast)),
+            "a" => Ok(Some(Action::NavigateToAliases)),
+            "e" => Ok(Some(Action::NavigateToRecipes)),
+            "w" if self.config.write_behind_ms.is_some() => Ok(Some(Action::Flush)),
+            "s" if !self.config.workspaces.is_empty() => Ok(Some(Action::SwitchWorkspace)),
             input => {
-                if let Ok(epic_id) = input.parse::<u32>() {
-                    if epics.contains_key(&epic_id) {
+                if let Some(epic_id) = self.config.id_format.parse(input) {
+                    if self.dao.get_epic(epic_id).is_ok() {
                         return Ok(Some(Action::NavigateToEpicDetail { epic_id }));
                     }
                 }
@@ -58,33 +99,149 @@ impl Page for HomePage {
 #[cfg(test)]
 mod tests {
 
-    use crate::{models::Epic, ui::pages::page_test_utils::make_dao};
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        models::{Epic, Status},
+        ui::pages::{
+            columns::{ListColumn, ListColumns},
+            page_test_utils::{make_config, make_dao},
+            renderer::BufferRenderer,
+        },
+    };
 
     use super::*;
 
     fn make_sut() -> HomePage {
         let dao = make_dao();
-        HomePage { dao }
+        HomePage {
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        }
     }
 
     #[test]
     fn draw_page_should_not_throw_error() {
         let sut = make_sut();
-        assert_eq!(sut.draw_page().is_ok(), true);
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_ok(), true);
+    }
+
+    #[test]
+    fn draw_page_should_list_epics() {
+        let dao = make_dao();
+        dao.create_epic(Epic::new("Payments".to_owned(), "".to_owned()))
+            .unwrap();
+        let sut = HomePage {
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.lines[1],
+            "    id      |               name               |      status      "
+        );
+        assert_eq!(
+            renderer.lines[2],
+            "1           | Payments                         | OPEN             "
+        );
     }
 
     #[test]
     fn handle_input_should_not_throw_error() {
-        let sut = make_sut();
+        let mut sut = make_sut();
         assert_eq!(sut.handle_input("").is_ok(), true);
     }
 
+    #[test]
+    fn draw_page_should_use_the_configured_columns() {
+        let sut = HomePage {
+            dao: DbFixtureBuilder::new()
+                .with_epic("Payments")
+                .with_story("Refunds")
+                .build_dao(),
+            config: Rc::new(Config {
+                columns: ListColumns {
+                    epics: vec![ListColumn::Name, ListColumn::Progress],
+                    ..ListColumns::default()
+                },
+                ..Config::default()
+            }),
+            status_filter: StatusFilter::default(),
+        };
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.lines[1],
+            "              name               |  progress "
+        );
+        assert_eq!(
+            renderer.lines[2],
+            "Payments                         | 0/1       "
+        );
+    }
+
+    #[test]
+    fn quick_filters_should_hide_other_statuses() {
+        let mut sut = HomePage {
+            dao: DbFixtureBuilder::new()
+                .with_epic("Payments")
+                .with_epic("Search")
+                .with_epic_status(Status::Closed)
+                .build_dao(),
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+
+        assert_eq!(sut.handle_input("f4").unwrap(), Some(Action::Redraw));
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[1], "showing [CLOSED]");
+        assert_eq!(renderer.contents().contains("Search"), true);
+        assert_eq!(renderer.contents().contains("Payments"), false);
+    }
+
+    #[test]
+    fn handle_input_should_offer_saving_only_with_write_behind() {
+        let mut sut = make_sut();
+        assert_eq!(sut.handle_input("w").unwrap(), None);
+
+        sut.config = Rc::new(Config {
+            write_behind_ms: Some(500),
+            ..Config::default()
+        });
+        assert_eq!(sut.handle_input("w").unwrap(), Some(Action::Flush));
+    }
+
+    #[test]
+    fn handle_input_should_offer_switching_only_with_workspaces() {
+        let mut sut = make_sut();
+        assert_eq!(sut.handle_input("s").unwrap(), None);
+
+        sut.config = Rc::new(Config {
+            workspaces: [("acme".to_owned(), "acme.json".to_owned())].into(),
+            ..Config::default()
+        });
+        assert_eq!(
+            sut.handle_input("s").unwrap(),
+            Some(Action::SwitchWorkspace)
+        );
+    }
+
     #[test]
     fn handle_input_should_return_the_correct_actions() {
         let dao = make_dao();
         let epic = Epic::new("".to_owned(), "".to_owned());
         let epic_id = dao.create_epic(epic).unwrap();
-        let sut = HomePage { dao };
+        let mut sut = HomePage {
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
 
         let valid_epic_id = epic_id.to_string();
         let (q, c) = ("q", "c");
@@ -97,6 +254,34 @@ mod tests {
 
         assert_eq!(sut.handle_input(q).unwrap(), Some(Action::Exit));
         assert_eq!(sut.handle_input(c).unwrap(), Some(Action::CreateEpic));
+        assert_eq!(
+            sut.handle_input("n").unwrap(),
+            Some(Action::NavigateToQueue)
+        );
+        assert_eq!(
+            sut.handle_input("g").unwrap(),
+            Some(Action::NavigateToGrooming)
+        );
+        assert_eq!(
+            sut.handle_input("r").unwrap(),
+            Some(Action::NavigateToReminders)
+        );
+        assert_eq!(
+            sut.handle_input("f").unwrap(),
+            Some(Action::NavigateToRefinement)
+        );
+        assert_eq!(
+            sut.handle_input("l").unwrap(),
+            Some(Action::NavigateToWatchlist)
+        );
+        assert_eq!(
+            sut.handle_input("v").unwrap(),
+            Some(Action::NavigateToForecast)
+        );
+        assert_eq!(
+            sut.handle_input("a").unwrap(),
+            Some(Action::NavigateToAliases)
+        );
         assert_eq!(
             sut.handle_input(&valid_epic_id).unwrap(),
             Some(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -1,35 +1,57 @@
-use std::rc::Rc;
-
-use crate::dao::JiraDAO;
-
+mod aliases;
+mod burnup;
+mod columns;
+mod dependencies;
 mod epic_details;
+mod epic_merge;
+mod forecast;
+mod grooming;
 mod home;
 mod page;
 mod page_helpers;
+mod queue;
+mod recipes;
+mod refinement;
+mod reminders;
+mod renderer;
+mod status_filter;
 mod story_details;
+mod timeline;
+mod watchlist;
 
 pub use page::*;
 pub use home::*;
 pub use epic_details::*;
 pub use story_details::*;
+pub use queue::*;
+pub use dependencies::*;
+pub use renderer::*;
+pub use status_filter::*;
+pub use columns::*;
+pub use grooming::*;
+pub use reminders::*;
+pub use refinement::*;
+pub use epic_merge::*;
+pub use timeline::*;
+pub use burnup::*;
+pub use watchlist::*;
+pub use forecast::*;
+pub use aliases::*;
+pub use recipes::*;
 
+#[cfg(test)]
 mod page_test_utils {
-    use super::*;
-    use crate::dao::test_utils::MockDB;
-    use crate::models::{Epic, Story};
+    use std::rc::Rc;
+
+    use crate::config::Config;
+    use crate::dao::{test_utils::MockDB, JiraDAO};
+
+    pub fn make_config() -> Rc<Config> {
+        Rc::new(Config::default())
+    }
 
     pub fn make_dao() -> Rc<JiraDAO> {
         let database = Box::new(MockDB::new());
         Rc::new(JiraDAO::new(database))
     }
-
-    pub fn create_epic_and_story(dao: &JiraDAO) -> (u32, u32) {
-        let epic_id = dao
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
-            .unwrap();
-        let story_id = dao
-            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
-            .unwrap();
-        (epic_id, story_id)
-    }
 }
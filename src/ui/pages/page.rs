@@ -4,8 +4,19 @@ use anyhow::Result;
 
 use crate::ui::actions::Action;
 
+use super::renderer::Renderer;
+
+/// A screen managed by the Navigator. Pages may keep their own state (a
+/// selection, a filter) and update it while handling input.
 pub trait Page {
-    fn draw_page(&self) -> Result<()>;
-    fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()>;
+    /// Returns [`Action::Redraw`] for input the page used itself and `None`
+    /// for input it ignored.
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>>;
+    /// Called when the page becomes the current page, including when the
+    /// user comes back to it from a page above.
+    fn on_enter(&mut self) {}
+    /// Called when another page is shown on top of this one or it is closed.
+    fn on_leave(&mut self) {}
     fn as_any(&self) -> &dyn Any;
 }
@@ -1,7 +1,37 @@
+use chrono::{DateTime, Utc};
 use ellipse::Ellipse;
 
 use std::cmp::Ordering::{Equal, Greater, Less};
 
+use crate::config::Config;
+use crate::models::{DBState, Story};
+use crate::sla::current_breach;
+
+/// The id and name of the story's epic, e.g. `1 Payments`, for listings that
+/// mix stories of several epics.
+pub fn epic_column(db_state: &DBState, story_id: u32, config: &Config) -> String {
+    db_state
+        .epic_of(story_id)
+        .and_then(|epic_id| {
+            let epic = db_state.epics.get(&epic_id)?;
+            Some(format!(
+                "{} {}",
+                config.id_format.format(epic_id),
+                epic.name
+            ))
+        })
+        .unwrap_or_default()
+}
+
+/// The story's status, flagged when it has been in it longer than its SLA,
+/// e.g. `IN PROGRESS !SLA`.
+pub fn status_cell(story: &Story, config: &Config, now: DateTime<Utc>) -> String {
+    match current_breach(story, &config.slas, &config.calendar, now) {
+        Some(_) => format!("{} !SLA", story.status),
+        None => story.status.to_string(),
+    }
+}
+
 pub fn get_column_string(text: &str, width: usize) -> String {
     let len = text.len();
     match len.cmp(&width) {
@@ -71,4 +101,41 @@ mod tests {
         assert_eq!(get_column_string(text3, width), "testme".to_owned());
         assert_eq!(get_column_string(text4, width), "tes...".to_owned());
     }
+
+    #[test]
+    fn epic_column_should_name_the_story_epic() {
+        let db_state = crate::fixtures::DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .build();
+        let config = Config {
+            id_format: crate::id_format::IdFormat::Prefixed {
+                prefix: "PAY".to_owned(),
+            },
+            ..Config::default()
+        };
+
+        assert_eq!(epic_column(&db_state, 2, &config), "PAY-1 Payments");
+        assert_eq!(epic_column(&db_state, 99, &config), "");
+    }
+
+    #[test]
+    fn status_cell_should_flag_stories_over_their_sla() {
+        let config = Config {
+            slas: vec![crate::sla::Sla {
+                status: crate::models::Status::InProgress,
+                working_days: 2,
+            }],
+            ..Config::default()
+        };
+        let mut story = Story::new("Refunds".to_owned(), "".to_owned());
+        story.set_status(crate::models::Status::InProgress);
+        let now = Utc::now();
+
+        assert_eq!(status_cell(&story, &config, now), "IN PROGRESS");
+        assert_eq!(
+            status_cell(&story, &config, now + chrono::Duration::days(7)),
+            "IN PROGRESS !SLA"
+        );
+    }
 }
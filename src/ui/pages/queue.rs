@@ -0,0 +1,225 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::page_helpers::{epic_column, status_cell};
+use super::renderer::{Column, Renderer, Span};
+
+/// The personal "next up" queue. Keeps a cursor so stories can be reordered
+/// with single keys.
+pub struct QueuePage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub selected: usize,
+}
+
+impl QueuePage {
+    fn selected_story_id(&self) -> Result<Option<u32>> {
+        Ok(self.dao.read_db()?.queue.get(self.selected).copied())
+    }
+}
+
+impl Page for QueuePage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- NEXT UP ----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let now = Utc::now();
+        let rows: Vec<Vec<String>> = db_state
+            .queue
+            .iter()
+            .enumerate()
+            .filter_map(|(position, id)| {
+                let story = db_state.stories.get(id)?;
+                let cursor = if position == self.selected { ">" } else { " " };
+                Some(vec![
+                    format!("{} {}", cursor, position + 1),
+                    self.config.id_format.format(*id),
+                    story.name.clone(),
+                    status_cell(story, &self.config, now),
+                    epic_column(&db_state, *id, &self.config),
+                ])
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "#",
+                    width: 5,
+                },
+                Column {
+                    title: "id",
+                    width: 11,
+                },
+                Column {
+                    title: "name",
+                    width: 32,
+                },
+                Column {
+                    title: "status",
+                    width: 17,
+                },
+                Column {
+                    title: "epic",
+                    width: 20,
+                },
+            ],
+            &rows,
+        );
+
+        renderer.write_line("");
+        renderer.write_line("");
+
+        renderer.write_line("[p] previous | [j] down | [k] up | [u] move up | [d] move down | [r] remove | [o] open | [s] start next");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        let queue_len = self.dao.read_db()?.queue.len();
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "s" => Ok(Some(Action::StartNextQueuedStory)),
+            "j" => {
+                if self.selected + 1 < queue_len {
+                    self.selected += 1;
+                }
+                Ok(Some(Action::Redraw))
+            }
+            "k" => {
+                self.selected = self.selected.saturating_sub(1);
+                Ok(Some(Action::Redraw))
+            }
+            "u" | "d" | "r" | "o" => {
+                let story_id = match self.selected_story_id()? {
+                    Some(story_id) => story_id,
+                    None => return Ok(None),
+                };
+                let action = match input {
+                    "u" => {
+                        self.selected = self.selected.saturating_sub(1);
+                        Action::MoveQueuedStory {
+                            story_id,
+                            offset: -1,
+                        }
+                    }
+                    "d" => {
+                        if self.selected + 1 < queue_len {
+                            self.selected += 1;
+                        }
+                        Action::MoveQueuedStory {
+                            story_id,
+                            offset: 1,
+                        }
+                    }
+                    "r" => Action::UnqueueStory { story_id },
+                    _ => Action::OpenQueuedStory { story_id },
+                };
+                Ok(Some(action))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn on_enter(&mut self) {
+        // the queue may have shrunk while another page was open
+        if let Ok(db_state) = self.dao.read_db() {
+            self.selected = self.selected.min(db_state.queue.len().saturating_sub(1));
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> (QueuePage, Vec<u32>) {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("")
+            .with_story("first")
+            .with_story("second");
+        let dao = fixture.build_dao();
+        let story_ids = vec![2, 3];
+        for story_id in &story_ids {
+            dao.queue_story(*story_id).unwrap();
+        }
+        let sut = QueuePage {
+            dao,
+            config: make_config(),
+            selected: 0,
+        };
+        (sut, story_ids)
+    }
+
+    #[test]
+    fn draw_page_should_mark_the_selected_story() {
+        let (sut, _) = make_sut();
+        let mut renderer = BufferRenderer::new();
+        assert_eq!(sut.draw_page(&mut renderer).is_ok(), true);
+        assert_eq!(renderer.lines[2].starts_with("> 1"), true);
+        assert_eq!(renderer.lines[3].starts_with("  2"), true);
+    }
+
+    #[test]
+    fn handle_input_should_move_the_cursor_within_the_queue() {
+        let (mut sut, story_ids) = make_sut();
+
+        assert_eq!(sut.handle_input("k").unwrap(), Some(Action::Redraw));
+        assert_eq!(sut.selected, 0);
+        sut.handle_input("j").unwrap();
+        sut.handle_input("j").unwrap();
+        assert_eq!(sut.selected, 1);
+
+        assert_eq!(
+            sut.handle_input("u").unwrap(),
+            Some(Action::MoveQueuedStory {
+                story_id: story_ids[1],
+                offset: -1
+            })
+        );
+        assert_eq!(sut.selected, 0);
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let (mut sut, story_ids) = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("s").unwrap(),
+            Some(Action::StartNextQueuedStory)
+        );
+        assert_eq!(
+            sut.handle_input("o").unwrap(),
+            Some(Action::OpenQueuedStory {
+                story_id: story_ids[0]
+            })
+        );
+        assert_eq!(
+            sut.handle_input("r").unwrap(),
+            Some(Action::UnqueueStory {
+                story_id: story_ids[0]
+            })
+        );
+        assert_eq!(sut.handle_input("junk").unwrap(), None);
+    }
+}
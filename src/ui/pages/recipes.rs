@@ -0,0 +1,104 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::recipes::available;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// The recipes to run, by number.
+pub struct RecipesPage {
+    pub config: Rc<Config>,
+}
+
+impl Page for RecipesPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "----------------------------- RECIPES -----------------------------",
+        )]);
+
+        let rows: Vec<Vec<String>> = available(&self.config.recipes)
+            .iter()
+            .enumerate()
+            .map(|(index, recipe)| {
+                vec![
+                    (index + 1).to_string(),
+                    recipe.name.clone(),
+                    recipe.steps.iter().map(|step| step.describe()).join(" -> "),
+                ]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "#",
+                    width: 3,
+                },
+                Column {
+                    title: "recipe",
+                    width: 20,
+                },
+                Column {
+                    title: "steps",
+                    width: 50,
+                },
+            ],
+            &rows,
+        );
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [:#:] run recipe");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            input => {
+                let count = available(&self.config.recipes).len();
+                match input.parse::<usize>() {
+                    Ok(number) if (1..=count).contains(&number) => {
+                        Ok(Some(Action::RunRecipe { index: number - 1 }))
+                    }
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::pages::{page_test_utils::make_config, renderer::BufferRenderer};
+
+    use super::*;
+
+    #[test]
+    fn draw_page_should_list_the_recipes_and_their_steps() {
+        let mut sut = RecipesPage {
+            config: make_config(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.lines[2].contains("| due reminders -> start the next story"),
+            true
+        );
+        assert_eq!(
+            sut.handle_input("1").unwrap(),
+            Some(Action::RunRecipe { index: 0 })
+        );
+        assert_eq!(sut.handle_input("2").unwrap(), None);
+    }
+}
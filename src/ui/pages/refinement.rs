@@ -0,0 +1,167 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Renderer, Span};
+
+/// Walks through the stories that are not ready yet, oldest first, showing
+/// what each still lacks. A story leaves the list once it is marked ready.
+pub struct RefinementPage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub position: usize,
+}
+
+impl RefinementPage {
+    fn unready_story_ids(&self) -> Result<Vec<u32>> {
+        let db_state = self.dao.read_db()?;
+        Ok(db_state
+            .stories
+            .iter()
+            .filter(|(_, story)| !story.ready && !story.status.is_done())
+            .map(|(id, _)| *id)
+            .sorted()
+            .collect())
+    }
+
+    fn current_story_id(&self) -> Result<Option<u32>> {
+        Ok(self.unready_story_ids()?.get(self.position).copied())
+    }
+}
+
+impl Page for RefinementPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "--------------------------- REFINEMENT ---------------------------",
+        )]);
+
+        let story_ids = self.unready_story_ids()?;
+        let Some(story_id) = story_ids.get(self.position).copied() else {
+            renderer.write_line("Every open story is ready.");
+            renderer.write_line("");
+            renderer.write_line("[p] previous");
+            return Ok(());
+        };
+        let story = self.dao.get_story(story_id)?;
+        renderer.write_line(&format!(
+            "Story {} of {}: {} {}",
+            self.position + 1,
+            story_ids.len(),
+            self.config.id_format.format(story_id),
+            story.name
+        ));
+        renderer.write_line("");
+        let gaps = story.refinement_gaps();
+        for item in ["a description", "acceptance criteria"] {
+            let check = if gaps.contains(&item) { "[ ]" } else { "[x]" };
+            renderer.write_line(&format!("{} {}", check, item));
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line(
+            "[p] previous | [e] edit description | [n] new criterion | [r] mark ready | [s] skip",
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        if input == "p" {
+            return Ok(Some(Action::NavigateToPreviousPage));
+        }
+        let Some(story_id) = self.current_story_id()? else {
+            return Ok(None);
+        };
+        match input {
+            "e" => Ok(Some(Action::EditStoryDescription { story_id })),
+            "n" => Ok(Some(Action::AddAcceptanceCriterion { story_id })),
+            "r" => Ok(Some(Action::MarkStoryReady { story_id })),
+            "s" => {
+                self.position += 1;
+                Ok(Some(Action::Redraw))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        models::Status,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> RefinementPage {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("shipped")
+            .with_story_status(Status::Closed)
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build_dao();
+        dao.update_story_description(3, "Card refunds".to_owned())
+            .unwrap();
+        RefinementPage {
+            dao,
+            config: make_config(),
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn draw_page_should_show_the_checklist_of_the_current_story() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[1], "Story 1 of 2: 3 Refunds");
+        assert_eq!(renderer.lines[3], "[x] a description");
+        assert_eq!(renderer.lines[4], "[ ] acceptance criteria");
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("e").unwrap(),
+            Some(Action::EditStoryDescription { story_id: 3 })
+        );
+        assert_eq!(
+            sut.handle_input("n").unwrap(),
+            Some(Action::AddAcceptanceCriterion { story_id: 3 })
+        );
+        assert_eq!(sut.handle_input("s").unwrap(), Some(Action::Redraw));
+        assert_eq!(
+            sut.handle_input("r").unwrap(),
+            Some(Action::MarkStoryReady { story_id: 4 })
+        );
+        sut.handle_input("s").unwrap();
+        assert_eq!(sut.handle_input("r").unwrap(), None);
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+        assert_eq!(
+            renderer.contents().contains("Every open story is ready."),
+            true
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::reminders::due_reminders;
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::page_helpers::epic_column;
+use super::renderer::{Column, Renderer, Span};
+
+/// Stories whose reminder is due. Snoozing asks for a new reminder time,
+/// dismissing clears it.
+pub struct RemindersPage {
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    pub selected: usize,
+}
+
+impl RemindersPage {
+    fn due_story_ids(&self) -> Result<Vec<u32>> {
+        Ok(due_reminders(&self.dao.read_db()?, Utc::now()))
+    }
+}
+
+impl Page for RemindersPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- REMINDERS ----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let rows: Vec<Vec<String>> = due_reminders(&db_state, Utc::now())
+            .iter()
+            .enumerate()
+            .filter_map(|(position, id)| {
+                let story = db_state.stories.get(id)?;
+                let cursor = if position == self.selected { ">" } else { " " };
+                Some(vec![
+                    cursor.to_owned(),
+                    self.config.id_format.format(*id),
+                    story.name.clone(),
+                    self.config.time_display.show(&story.remind_at?, true),
+                    epic_column(&db_state, *id, &self.config),
+                ])
+            })
+            .collect();
+        if rows.is_empty() {
+            renderer.write_line("No reminders are due.");
+        } else {
+            renderer.write_table(
+                &[
+                    Column {
+                        title: "",
+                        width: 1,
+                    },
+                    Column {
+                        title: "id",
+                        width: 11,
+                    },
+                    Column {
+                        title: "name",
+                        width: 32,
+                    },
+                    Column {
+                        title: "due",
+                        width: 19,
+                    },
+                    Column {
+                        title: "epic",
+                        width: 20,
+                    },
+                ],
+                &rows,
+            );
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+
+        renderer.write_line(
+            "[p] previous | [j] down | [k] up | [s] snooze | [d] dismiss | [o] open story",
+        );
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        let story_ids = self.due_story_ids()?;
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "j" => {
+                if self.selected + 1 < story_ids.len() {
+                    self.selected += 1;
+                }
+                Ok(Some(Action::Redraw))
+            }
+            "k" => {
+                self.selected = self.selected.saturating_sub(1);
+                Ok(Some(Action::Redraw))
+            }
+            "s" | "d" | "o" => {
+                let Some(story_id) = story_ids.get(self.selected).copied() else {
+                    return Ok(None);
+                };
+                let action = match input {
+                    "s" => Action::SetReminder { story_id },
+                    "d" => Action::DismissReminder { story_id },
+                    _ => match self.dao.read_db()?.epic_of(story_id) {
+                        Some(epic_id) => Action::NavigateToStoryDetail { epic_id, story_id },
+                        None => return Ok(None),
+                    },
+                };
+                Ok(Some(action))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn on_enter(&mut self) {
+        // snoozed or dismissed reminders drop off the list
+        if let Ok(story_ids) = self.due_story_ids() {
+            self.selected = self.selected.min(story_ids.len().saturating_sub(1));
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        fixtures::DbFixtureBuilder,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> RemindersPage {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("epic")
+            .with_story("overdue")
+            .with_story("due")
+            .with_story("later")
+            .build_dao();
+        dao.set_reminder(2, Some(Utc::now() - Duration::days(1)))
+            .unwrap();
+        dao.set_reminder(3, Some(Utc::now() - Duration::hours(2)))
+            .unwrap();
+        dao.set_reminder(4, Some(Utc::now() + Duration::hours(2)))
+            .unwrap();
+        RemindersPage {
+            dao,
+            config: make_config(),
+            selected: 0,
+        }
+    }
+
+    #[test]
+    fn draw_page_should_list_due_reminders() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[2].starts_with(">"), true);
+        assert_eq!(renderer.lines[2].contains("1d ago"), true);
+        assert_eq!(renderer.lines[3].contains("2h ago"), true);
+        assert_eq!(renderer.contents().contains("later"), false);
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("s").unwrap(),
+            Some(Action::SetReminder { story_id: 2 })
+        );
+        assert_eq!(sut.handle_input("j").unwrap(), Some(Action::Redraw));
+        assert_eq!(sut.handle_input("j").unwrap(), Some(Action::Redraw));
+        assert_eq!(sut.selected, 1);
+        assert_eq!(
+            sut.handle_input("d").unwrap(),
+            Some(Action::DismissReminder { story_id: 3 })
+        );
+        assert_eq!(
+            sut.handle_input("o").unwrap(),
+            Some(Action::NavigateToStoryDetail {
+                epic_id: 1,
+                story_id: 3
+            })
+        );
+        assert_eq!(sut.handle_input("junk").unwrap(), None);
+    }
+}
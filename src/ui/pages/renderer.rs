@@ -0,0 +1,169 @@
+use std::io::{self, Write};
+
+use super::page_helpers::get_column_string;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Bold,
+    Dim,
+    Error,
+    Success,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            style: Style::Plain,
+        }
+    }
+
+    pub fn bold(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            style: Style::Bold,
+        }
+    }
+
+    pub fn dim(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            style: Style::Dim,
+        }
+    }
+
+    pub fn error(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            style: Style::Error,
+        }
+    }
+
+    pub fn success(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            style: Style::Success,
+        }
+    }
+}
+
+pub struct Column {
+    pub title: &'static str,
+    pub width: usize,
+}
+
+/// Output target for pages. Implementations only need to know how to write
+/// a line of styled spans; plain lines and tables are built on top of that.
+pub trait Renderer {
+    fn write_spans(&mut self, spans: &[Span]);
+
+    fn write_line(&mut self, line: &str) {
+        self.write_spans(&[Span::plain(line)]);
+    }
+
+    fn write_table(&mut self, columns: &[Column], rows: &[Vec<String>]) {
+        let header = columns
+            .iter()
+            .map(|column| format!("{:^width$}", column.title, width = column.width))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        self.write_spans(&[Span::bold(&header)]);
+        for row in rows {
+            let line = columns
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| get_column_string(cell, column.width))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            self.write_line(&line);
+        }
+    }
+}
+
+/// Writes to the terminal, using ANSI escape codes for styles.
+pub struct StdoutRenderer;
+
+impl Renderer for StdoutRenderer {
+    fn write_spans(&mut self, spans: &[Span]) {
+        let mut stdout = io::stdout().lock();
+        for span in spans {
+            let _ = match span.style {
+                Style::Plain => write!(stdout, "{}", span.text),
+                Style::Bold => write!(stdout, "\x1b[1m{}\x1b[0m", span.text),
+                Style::Dim => write!(stdout, "\x1b[2m{}\x1b[0m", span.text),
+                Style::Error => write!(stdout, "\x1b[1;97;41m {} \x1b[0m", span.text),
+                Style::Success => write!(stdout, "\x1b[1;30;42m {} \x1b[0m", span.text),
+            };
+        }
+        let _ = writeln!(stdout);
+    }
+}
+
+/// Keeps the rendered lines as plain text, so tests can assert on page output.
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Default)]
+pub struct BufferRenderer {
+    pub lines: Vec<String>,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl BufferRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl Renderer for BufferRenderer {
+    fn write_spans(&mut self, spans: &[Span]) {
+        let line = spans.iter().map(|span| span.text.as_str()).collect();
+        self.lines.push(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_renderer_should_record_plain_text() {
+        let mut renderer = BufferRenderer::new();
+        renderer.write_line("title");
+        renderer.write_spans(&[Span::bold("[q]"), Span::plain(" quit")]);
+        renderer.write_line("");
+
+        assert_eq!(renderer.lines, vec!["title", "[q] quit", ""]);
+    }
+
+    #[test]
+    fn write_table_should_align_columns() {
+        let mut renderer = BufferRenderer::new();
+        let columns = [
+            Column {
+                title: "id",
+                width: 4,
+            },
+            Column {
+                title: "name",
+                width: 8,
+            },
+        ];
+        renderer.write_table(
+            &columns,
+            &[vec!["1".to_owned(), "a very long name".to_owned()]],
+        );
+
+        assert_eq!(renderer.contents(), " id  |   name  \n1    | a ver...");
+    }
+}
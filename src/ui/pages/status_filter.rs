@@ -0,0 +1,97 @@
+use crate::models::Status;
+
+/// Statuses a listing is narrowed to, toggled with F1..F4 (or by typing
+/// `f1`..`f4`) in the same order as the status prompt. Several can be
+/// active at once; none means everything is shown. Story listings can also
+/// be narrowed to ready stories.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StatusFilter {
+    statuses: Vec<Status>,
+    ready_only: bool,
+}
+
+impl StatusFilter {
+    /// Toggles the status bound to the key. Returns false for any other
+    /// input, so pages can fall through to their own keys.
+    pub fn handle_key(&mut self, input: &str) -> bool {
+        let status = match input.to_ascii_lowercase().as_str() {
+            "f1" => Status::Open,
+            "f2" => Status::InProgress,
+            "f3" => Status::Resolved,
+            "f4" => Status::Closed,
+            _ => return false,
+        };
+        match self.statuses.iter().position(|active| *active == status) {
+            Some(index) => {
+                self.statuses.remove(index);
+            }
+            None => self.statuses.push(status),
+        }
+        true
+    }
+
+    pub fn matches(&self, status: &Status) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(status)
+    }
+
+    pub fn toggle_ready_only(&mut self) {
+        self.ready_only = !self.ready_only;
+    }
+
+    pub fn matches_readiness(&self, ready: bool) -> bool {
+        ready || !self.ready_only
+    }
+
+    /// The active statuses as chips, e.g. `[OPEN] [CLOSED]`, or `None` when
+    /// nothing is filtered.
+    pub fn chips(&self) -> Option<String> {
+        let mut chips: Vec<String> = self
+            .statuses
+            .iter()
+            .map(|status| format!("[{}]", status))
+            .collect();
+        if self.ready_only {
+            chips.push("[READY]".to_owned());
+        }
+        if chips.is_empty() {
+            return None;
+        }
+        Some(chips.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_key_should_toggle_statuses() {
+        let mut sut = StatusFilter::default();
+        assert_eq!(sut.matches(&Status::Closed), true);
+
+        assert_eq!(sut.handle_key("f1"), true);
+        assert_eq!(sut.handle_key("F4"), true);
+        assert_eq!(sut.matches(&Status::Open), true);
+        assert_eq!(sut.matches(&Status::Closed), true);
+        assert_eq!(sut.matches(&Status::InProgress), false);
+        assert_eq!(sut.chips(), Some("[OPEN] [CLOSED]".to_owned()));
+
+        sut.handle_key("f1");
+        sut.handle_key("f4");
+        assert_eq!(sut.chips(), None);
+        assert_eq!(sut.handle_key("f"), false);
+        assert_eq!(sut.handle_key("f5"), false);
+    }
+
+    #[test]
+    fn ready_only_should_hide_unrefined_stories() {
+        let mut sut = StatusFilter::default();
+        assert_eq!(sut.matches_readiness(false), true);
+
+        sut.toggle_ready_only();
+        sut.handle_key("f2");
+        assert_eq!(sut.matches_readiness(false), false);
+        assert_eq!(sut.matches_readiness(true), true);
+        assert_eq!(sut.chips(), Some("[IN PROGRESS] [READY]".to_owned()));
+    }
+}
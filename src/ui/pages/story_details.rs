@@ -1,49 +1,266 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use std::path::Path;
 use std::rc::Rc;
 
-use crate::dao::JiraDAO;
+use crate::config::Config;
+use crate::dao::{JiraDAO, StoryView};
+use crate::git;
+use crate::models::{criterion_references, Comment};
 use crate::ui::actions::Action;
-use crate::ui::pages::page_helpers::get_column_string;
 
 use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
 
 pub struct StoryDetail {
     pub epic_id: u32,
     pub story_id: u32,
     pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+    /// Flipped with `r` to show times in the format the config doesn't use.
+    pub time_format_toggled: bool,
 }
 
 impl Page for StoryDetail {
-    fn draw_page(&self) -> Result<()> {
-        let dao_state = self.dao.read_db()?;
-        let story = dao_state
-            .stories
-            .get(&self.story_id)
-            .ok_or_else(|| anyhow!("could not find story!"))?;
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        let StoryView {
+            story,
+            epic,
+            blockers,
+        } = self.dao.get_story_view(self.epic_id, self.story_id)?;
 
-        println!("------------------------------ STORY ------------------------------");
-        println!(" id |     name     |         description         |    status    ");
+        let done = epic.stories_done;
+        let total = epic.stories_total;
+        let percentage = (done * 100).checked_div(total).unwrap_or(0);
+        renderer.write_spans(&[Span::dim(&format!(
+            "EPIC {} > {} | {} | {}/{} stories done ({}%)",
+            self.config.id_format.format(self.epic_id),
+            epic.name,
+            epic.status,
+            done,
+            total,
+            percentage
+        ))]);
+        renderer.write_line("");
 
-        let id_col = get_column_string(&self.story_id.to_string(), 3);
-        let name_col = get_column_string(&story.name, 12);
-        let description_col = get_column_string(&story.description.to_string(), 27);
-        let status_col = get_column_string(&story.status.to_string(), 17);
-        println!("{} | {} | {} | {}", id_col, name_col, description_col, status_col);
+        renderer.write_spans(&[Span::bold(
+            "------------------------------ STORY ------------------------------",
+        )]);
+        renderer.write_table(
+            &[
+                Column {
+                    title: "id",
+                    width: 10,
+                },
+                Column {
+                    title: "name",
+                    width: 12,
+                },
+                Column {
+                    title: "description",
+                    width: 27,
+                },
+                Column {
+                    title: "status",
+                    width: 17,
+                },
+            ],
+            &[vec![
+                self.config.id_format.format(self.story_id),
+                story.name,
+                story.description,
+                story.status.to_string(),
+            ]],
+        );
+        if let Some(kind) = &story.kind {
+            renderer.write_line(&format!("Type: {}", kind));
+        }
+        if let Some(points) = story.points {
+            let label = self.config.point_scale.label(points);
+            if label == points.to_string() {
+                renderer.write_line(&format!("Points: {}", points));
+            } else {
+                renderer.write_line(&format!("Points: {} ({})", label, points));
+            }
+        }
+        if story.ready {
+            renderer.write_line("Ready to be picked up");
+        }
+        if let Some(remind_at) = &story.remind_at {
+            renderer.write_line(&format!(
+                "Reminder: {}",
+                self.config.time_display.show(
+                    remind_at,
+                    self.config.time_display.relative != self.time_format_toggled
+                )
+            ));
+        }
 
-        println!();
-        println!();
+        if !story.blocked_by.is_empty() {
+            renderer.write_line("");
+            let blockers: Vec<String> = story
+                .blocked_by
+                .iter()
+                .map(
+                    |id| match blockers.iter().find(|blocker| blocker.id == *id) {
+                        Some(blocker) => format!(
+                            "{} {} ({})",
+                            self.config.id_format.format(*id),
+                            blocker.name,
+                            blocker.status
+                        ),
+                        None => self.config.id_format.format(*id),
+                    },
+                )
+                .collect();
+            renderer.write_line(&format!("Blocked by: {}", blockers.join(", ")));
+        }
+        if !story.external_blockers.is_empty() {
+            if story.blocked_by.is_empty() {
+                renderer.write_line("");
+            }
+            renderer.write_line("Blocked outside the board:");
+            for blocker in &story.external_blockers {
+                renderer.write_line(&format!("  {} <{}>", blocker.title, blocker.url));
+            }
+        }
+
+        if !story.acceptance_criteria.is_empty() {
+            renderer.write_line("");
+            renderer.write_spans(&[Span::bold(
+                "----------------------- ACCEPTANCE CRITERIA -----------------------",
+            )]);
+            for (index, criterion) in story.acceptance_criteria.iter().enumerate() {
+                let check = if criterion.done { "[x]" } else { "[ ]" };
+                renderer.write_line(&format!("AC-{} {} {}", index + 1, check, criterion.text));
+            }
+        }
 
-        println!("[p] previous | [u] update story | [d] delete story");
+        if !story.comments.is_empty() {
+            renderer.write_line("");
+            renderer.write_spans(&[Span::bold(
+                "---------------------------- COMMENTS ----------------------------",
+            )]);
+            for (index, depth) in threads(&story.comments) {
+                let comment = &story.comments[index];
+                let mut spans = vec![
+                    Span::plain(&"  ".repeat(depth)),
+                    Span::dim(&format!("C-{} ", index + 1)),
+                    Span::dim(&self.config.time_display.show(
+                        &comment.created_at,
+                        self.config.time_display.relative != self.time_format_toggled,
+                    )),
+                    Span::plain(" | "),
+                ];
+                spans.extend(comment_spans(&comment.text));
+                if comment.edited_at.is_some() {
+                    spans.push(Span::dim(" (edited)"));
+                }
+                renderer.write_spans(&spans);
+            }
+        }
+
+        if let Some(repo) = &self.config.git_repo {
+            renderer.write_line("");
+            renderer.write_spans(&[Span::bold(
+                "----------------------------- COMMITS -----------------------------",
+            )]);
+            let key = self.config.id_format.format(self.story_id);
+            match git::commits_mentioning(Path::new(repo), &key) {
+                Ok(commits) => {
+                    for commit in commits {
+                        renderer.write_spans(&[
+                            Span::dim(&commit.hash),
+                            Span::plain(" "),
+                            Span::plain(&commit.subject),
+                        ]);
+                    }
+                }
+                Err(error) => renderer.write_line(&format!("could not read commits: {}", error)),
+            }
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+
+        let mut menu =
+            "[p] previous | [g] go to epic | [u] update story | [k] set type | [s] estimate | [x] close with comment | [y] yank | [a] add to queue | [f] watch | [m] remind me | [l] blocked by | [o] blocked outside | [n] new criterion | [t] check criterion | [e] edit | [c] comment | [h] reply | [w] rewrite comment | [z] delete comment | [!] run plugin | [r] relative/absolute times"
+                .to_owned();
+        if self.config.git_repo.is_some() {
+            menu.push_str(" | [b] create branch");
+        }
+        menu.push_str(" | [d] delete story");
+        renderer.write_line(&menu);
 
         Ok(())
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "g" => Ok(Some(Action::NavigateToEpicDetail {
+                epic_id: self.epic_id,
+            })),
             "u" => Ok(Some(Action::UpdateStoryStatus {
                 story_id: self.story_id,
             })),
+            "k" => Ok(Some(Action::SetStoryKind {
+                story_id: self.story_id,
+            })),
+            "s" => Ok(Some(Action::EstimateStory {
+                story_id: self.story_id,
+            })),
+            "!" => Ok(Some(Action::RunPlugin {
+                story_id: self.story_id,
+            })),
+            "x" => Ok(Some(Action::CloseStoryWithComment {
+                story_id: self.story_id,
+            })),
+            "y" => Ok(Some(Action::YankStory {
+                story_id: self.story_id,
+            })),
+            "a" => Ok(Some(Action::QueueStory {
+                story_id: self.story_id,
+            })),
+            "f" => Ok(Some(Action::ToggleWatch {
+                story_id: self.story_id,
+            })),
+            "m" => Ok(Some(Action::SetReminder {
+                story_id: self.story_id,
+            })),
+            "l" => Ok(Some(Action::ToggleBlocker {
+                story_id: self.story_id,
+            })),
+            "o" => Ok(Some(Action::ToggleExternalBlocker {
+                story_id: self.story_id,
+            })),
+            "r" => {
+                self.time_format_toggled = !self.time_format_toggled;
+                Ok(Some(Action::Redraw))
+            }
+            "n" => Ok(Some(Action::AddAcceptanceCriterion {
+                story_id: self.story_id,
+            })),
+            "t" => Ok(Some(Action::ToggleAcceptanceCriterion {
+                story_id: self.story_id,
+            })),
+            "b" if self.config.git_repo.is_some() => Ok(Some(Action::CreateStoryBranch {
+                story_id: self.story_id,
+            })),
+            "c" => Ok(Some(Action::AddComment {
+                story_id: self.story_id,
+            })),
+            "h" => Ok(Some(Action::ReplyToComment {
+                story_id: self.story_id,
+            })),
+            "e" => Ok(Some(Action::EditStory {
+                story_id: self.story_id,
+            })),
+            "w" => Ok(Some(Action::EditComment {
+                story_id: self.story_id,
+            })),
+            "z" => Ok(Some(Action::DeleteComment {
+                story_id: self.story_id,
+            })),
             "d" => Ok(Some(Action::DeleteStory {
                 epic_id: self.epic_id,
                 story_id: self.story_id,
@@ -57,11 +274,66 @@ impl Page for StoryDetail {
     }
 }
 
+/// Comment indexes in thread order, each with how deep it is nested: every
+/// comment is followed by its replies, oldest first.
+fn threads(comments: &[Comment]) -> Vec<(usize, usize)> {
+    fn visit(
+        comments: &[Comment],
+        parent: Option<usize>,
+        depth: usize,
+        order: &mut Vec<(usize, usize)>,
+    ) {
+        for (index, comment) in comments.iter().enumerate() {
+            let number = index + 1;
+            // a reply to a missing comment starts its own thread
+            let reply_to = comment
+                .reply_to
+                .filter(|parent| *parent != number && *parent <= comments.len());
+            if reply_to == parent && !order.iter().any(|(seen, _)| *seen == index) {
+                order.push((index, depth));
+                visit(comments, Some(number), depth + 1, order);
+            }
+        }
+    }
+    let mut order = vec![];
+    visit(comments, None, 0, &mut order);
+    // replies going round in circles can only come from hand-edited files
+    for index in 0..comments.len() {
+        if !order.iter().any(|(seen, _)| *seen == index) {
+            order.push((index, 0));
+        }
+    }
+    order
+}
+
+/// Splits a comment so `AC-<n>` references stand out.
+fn comment_spans(text: &str) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut position = 0;
+    for (range, _) in criterion_references(text) {
+        if range.start > position {
+            spans.push(Span::plain(&text[position..range.start]));
+        }
+        spans.push(Span::bold(&text[range.clone()]));
+        position = range.end;
+    }
+    if position < text.len() || spans.is_empty() {
+        spans.push(Span::plain(&text[position..]));
+    }
+    spans
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
+        fixtures::DbFixtureBuilder,
         models::{Epic, Story},
-        ui::pages::{epic_details::EpicDetail, page_test_utils::make_dao},
+        ui::pages::{
+            epic_details::EpicDetail,
+            page_test_utils::{make_config, make_dao},
+            renderer::BufferRenderer,
+            status_filter::StatusFilter,
+        },
     };
 
     use super::*;
@@ -73,28 +345,72 @@ mod tests {
                 let epic_id = dao
                     .create_epic(Epic::new("".to_owned(), "".to_owned()))
                     .unwrap();
-                EpicDetail { epic_id, dao }
+                EpicDetail {
+                    epic_id,
+                    dao,
+                    config: make_config(),
+                    status_filter: StatusFilter::default(),
+                }
             }
-            None => EpicDetail { epic_id: 999, dao },
+            None => EpicDetail {
+                epic_id: 999,
+                dao,
+                config: make_config(),
+                status_filter: StatusFilter::default(),
+            },
         }
     }
 
     #[test]
     fn draw_page_should_not_throw_error() {
         let sut = make_sut(Some(()));
-        assert_eq!(sut.draw_page().is_ok(), true);
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_ok(), true);
     }
 
     #[test]
     fn handle_input_should_not_throw_error() {
-        let sut = make_sut(Some(()));
+        let mut sut = make_sut(Some(()));
         assert_eq!(sut.handle_input("").is_ok(), true);
     }
 
     #[test]
     fn draw_page_should_throw_error_for_invalid_epic_id() {
         let sut = make_sut(None);
-        assert_eq!(sut.draw_page().is_err(), true);
+        assert_eq!(sut.draw_page(&mut BufferRenderer::new()).is_err(), true);
+    }
+
+    #[test]
+    fn draw_page_should_list_mirrored_stories() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_epic("Compliance")
+            .with_story("Audit log")
+            .build_dao();
+        dao.toggle_mirror(1, 3).unwrap();
+        let mut sut = EpicDetail {
+            epic_id: 1,
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        let contents = renderer.contents();
+        assert_eq!(contents.contains("MIRRORED"), true);
+        assert_eq!(contents.contains("| 2 Compliance"), true);
+        assert_eq!(
+            sut.handle_input("3").unwrap(),
+            Some(Action::NavigateToStoryDetail {
+                epic_id: 2,
+                story_id: 3
+            })
+        );
+        assert_eq!(
+            sut.handle_input("i").unwrap(),
+            Some(Action::ToggleMirror { epic_id: 1 })
+        );
     }
 
     #[test]
@@ -106,8 +422,13 @@ mod tests {
         let story_id = dao
             .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
             .unwrap();
-        let sut = EpicDetail { epic_id, dao };
-        let (p, u, d, c) = ("p", "u", "d", "c");
+        let mut sut = EpicDetail {
+            epic_id,
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+        let (p, u, d, c, f, y, h) = ("p", "u", "d", "c", "f", "y", "h");
         let (
             invalid_story_id,
             junk_input,
@@ -131,6 +452,38 @@ mod tests {
             sut.handle_input(c).unwrap(),
             Some(Action::CreateStory { epic_id: 1 })
         );
+        assert_eq!(
+            sut.handle_input(f).unwrap(),
+            Some(Action::ToggleEpicFocus { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input(y).unwrap(),
+            Some(Action::YankEpic { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input(h).unwrap(),
+            Some(Action::ExportEpicReport { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input("o").unwrap(),
+            Some(Action::NavigateToDependencies { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input("t").unwrap(),
+            Some(Action::NavigateToTimeline { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input("b").unwrap(),
+            Some(Action::NavigateToBurnup { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input("s").unwrap(),
+            Some(Action::SplitEpic { epic_id: 1 })
+        );
+        assert_eq!(
+            sut.handle_input("m").unwrap(),
+            Some(Action::MergeEpic { epic_id: 1 })
+        );
         assert_eq!(
             sut.handle_input(&story_id.to_string()).unwrap(),
             Some(Action::NavigateToStoryDetail {
@@ -149,4 +502,109 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn threads_should_nest_replies_under_their_comment() {
+        let comments = vec![
+            Comment::new("question".to_owned()),
+            Comment::new("note".to_owned()),
+            Comment::reply("answer".to_owned(), 1),
+            Comment::reply("thanks".to_owned(), 3),
+            Comment::reply("lost".to_owned(), 9),
+        ];
+
+        assert_eq!(
+            threads(&comments),
+            vec![(0, 0), (2, 1), (3, 2), (1, 0), (4, 0)]
+        );
+    }
+
+    #[test]
+    fn comment_spans_should_highlight_criterion_references() {
+        assert_eq!(
+            comment_spans("covers AC-2."),
+            vec![Span::plain("covers "), Span::bold("AC-2"), Span::plain(".")]
+        );
+        assert_eq!(comment_spans(""), vec![Span::plain("")]);
+    }
+
+    #[test]
+    fn epic_detail_should_only_show_its_own_stories() {
+        let fixture = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_epic("Search")
+            .with_story("Autocomplete");
+        let (epic_id, story_id) = (
+            fixture.last_epic_id().unwrap(),
+            fixture.last_story_id().unwrap(),
+        );
+        let mut sut = EpicDetail {
+            epic_id,
+            dao: fixture.build_dao(),
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.contents().contains("Autocomplete"), true);
+        assert_eq!(renderer.contents().contains("Refunds"), false);
+        assert_eq!(sut.handle_input("2").unwrap(), None);
+        assert_eq!(
+            sut.handle_input(&story_id.to_string()).unwrap(),
+            Some(Action::NavigateToStoryDetail { epic_id, story_id })
+        );
+    }
+
+    #[test]
+    fn epic_detail_should_filter_to_ready_stories() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build_dao();
+        dao.update_story_description(3, "Monthly invoices".to_owned())
+            .unwrap();
+        dao.add_acceptance_criterion(3, "sent on the 1st".to_owned())
+            .unwrap();
+        dao.set_story_ready(3, true).unwrap();
+        let mut sut = EpicDetail {
+            epic_id: 1,
+            dao,
+            config: make_config(),
+            status_filter: StatusFilter::default(),
+        };
+
+        assert_eq!(sut.handle_input("f5").unwrap(), Some(Action::Redraw));
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.contents().contains("[READY]"), true);
+        assert_eq!(renderer.contents().contains("Invoices"), true);
+        assert_eq!(renderer.contents().contains("Refunds"), false);
+    }
+
+    #[test]
+    fn epic_detail_should_show_split_provenance() {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .build_dao();
+        let new_epic_id = dao.split_epic(1, &[2], "Refunds".to_owned()).unwrap();
+        let render = |epic_id| {
+            let sut = EpicDetail {
+                epic_id,
+                dao: Rc::clone(&dao),
+                config: make_config(),
+                status_filter: StatusFilter::default(),
+            };
+            let mut renderer = BufferRenderer::new();
+            sut.draw_page(&mut renderer).unwrap();
+            renderer.contents()
+        };
+
+        assert_eq!(render(1).contains("Split into: 3"), true);
+        assert_eq!(render(new_epic_id).contains("Split from: 1"), true);
+    }
 }
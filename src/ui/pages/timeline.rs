@@ -0,0 +1,209 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::dao::JiraDAO;
+use crate::timeline::{epic_timeline, TimelineEvent};
+use crate::ui::actions::Action;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// How an epic evolved: its stories' creations, status changes and comments
+/// in the order they happened. Status changes tell how many working days
+/// the story spent in the status it left.
+pub struct EpicTimeline {
+    pub epic_id: u32,
+    pub dao: Rc<JiraDAO>,
+    pub config: Rc<Config>,
+}
+
+impl Page for EpicTimeline {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- TIMELINE ----------------------------",
+        )]);
+
+        let db_state = self.dao.read_db()?;
+        let timeline = epic_timeline(&db_state, self.epic_id)?;
+        let mut status_since: HashMap<u32, DateTime<Utc>> = HashMap::new();
+        let rows: Vec<Vec<String>> = timeline
+            .iter()
+            .map(|entry| {
+                let name = db_state
+                    .stories
+                    .get(&entry.story_id)
+                    .map(|story| story.name.clone())
+                    .unwrap_or_default();
+                let event = match &entry.event {
+                    TimelineEvent::Created => {
+                        status_since.insert(entry.story_id, entry.at);
+                        "created".to_owned()
+                    }
+                    TimelineEvent::StatusChanged { from, to } => {
+                        let working_days = status_since
+                            .insert(entry.story_id, entry.at)
+                            .map(|since| {
+                                self.config
+                                    .calendar
+                                    .working_days_between(since.date_naive(), entry.at.date_naive())
+                            })
+                            .unwrap_or(0);
+                        match working_days {
+                            0 => format!("{} > {}", from, to),
+                            1 => format!("{} > {} (1 working day)", from, to),
+                            days => format!("{} > {} ({} working days)", from, to, days),
+                        }
+                    }
+                    TimelineEvent::Commented { text } => format!("commented: {}", text),
+                };
+                vec![
+                    self.config.time_display.format(&entry.at),
+                    self.config.id_format.format(entry.story_id),
+                    name,
+                    event,
+                ]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "when",
+                    width: 20,
+                },
+                Column {
+                    title: "id",
+                    width: 11,
+                },
+                Column {
+                    title: "story",
+                    width: 20,
+                },
+                Column {
+                    title: "what happened",
+                    width: 40,
+                },
+            ],
+            &rows,
+        );
+        if timeline.is_empty() {
+            renderer.write_line("Nothing recorded yet.");
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [:id:] navigate to story");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            input => {
+                if let Some(story_id) = self.config.id_format.parse(input) {
+                    let stories = self.dao.list_stories_of_epic(self.epic_id)?;
+                    if stories.iter().any(|story| story.id == story_id) {
+                        return Ok(Some(Action::NavigateToStoryDetail {
+                            epic_id: self.epic_id,
+                            story_id,
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{
+        dao::test_utils::MockDB,
+        fixtures::DbFixtureBuilder,
+        models::Status,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+    };
+
+    use super::*;
+
+    fn make_sut() -> EpicTimeline {
+        let dao = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story_comment("needs finance")
+            .build_dao();
+        dao.update_story_status(2, Status::InProgress).unwrap();
+        EpicTimeline {
+            epic_id: 1,
+            dao,
+            config: make_config(),
+        }
+    }
+
+    #[test]
+    fn draw_page_should_list_events_in_order() {
+        let sut = make_sut();
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(renderer.lines[2].contains("| created"), true);
+        assert_eq!(
+            renderer.lines[3].contains("| commented: needs finance"),
+            true
+        );
+        assert_eq!(renderer.lines[4].contains("| OPEN > IN PROGRESS"), true);
+    }
+
+    #[test]
+    fn handle_input_should_return_the_correct_actions() {
+        let mut sut = make_sut();
+
+        assert_eq!(
+            sut.handle_input("p").unwrap(),
+            Some(Action::NavigateToPreviousPage)
+        );
+        assert_eq!(
+            sut.handle_input("2").unwrap(),
+            Some(Action::NavigateToStoryDetail {
+                epic_id: 1,
+                story_id: 2
+            })
+        );
+        assert_eq!(sut.handle_input("1").unwrap(), None);
+    }
+
+    #[test]
+    fn draw_page_should_count_working_days_in_the_left_status() {
+        let mut state = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .build();
+        let story = state.stories.get_mut(&2).unwrap();
+        // a Monday, and the Monday after
+        story.created_at = Some(Utc.with_ymd_and_hms(2023, 5, 1, 9, 0, 0).unwrap());
+        story.set_status(Status::InProgress);
+        story.status_changes[0].at = Utc.with_ymd_and_hms(2023, 5, 8, 9, 0, 0).unwrap();
+        let sut = EpicTimeline {
+            epic_id: 1,
+            dao: Rc::new(JiraDAO::new(Box::new(MockDB::with_state(state)))),
+            config: make_config(),
+        };
+
+        let mut renderer = BufferRenderer::new();
+        sut.draw_page(&mut renderer).unwrap();
+
+        assert_eq!(
+            renderer.lines[3].contains("| OPEN > IN PROGRESS (5 working days)"),
+            true
+        );
+    }
+}
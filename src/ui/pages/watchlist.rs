@@ -0,0 +1,151 @@
+use anyhow::Result;
+use std::rc::Rc;
+
+use crate::config::Config;
+use crate::ui::actions::Action;
+use crate::watchlist::WatchEntry;
+
+use super::page::Page;
+use super::renderer::{Column, Renderer, Span};
+
+/// Watched stories from every workspace, as their boards had them when the
+/// page was opened.
+pub struct WatchlistPage {
+    pub entries: Vec<WatchEntry>,
+    /// The workspace on screen, whose stories can be opened from here.
+    pub workspace: Option<String>,
+    pub config: Rc<Config>,
+}
+
+impl Page for WatchlistPage {
+    fn draw_page(&self, renderer: &mut dyn Renderer) -> Result<()> {
+        renderer.write_spans(&[Span::bold(
+            "---------------------------- WATCHLIST ----------------------------",
+        )]);
+
+        let rows: Vec<Vec<String>> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    entry.watched.workspace.clone().unwrap_or_default(),
+                    self.config.id_format.format(entry.watched.story_id),
+                    entry.name.clone(),
+                    entry
+                        .status
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                    entry
+                        .last_activity
+                        .map(|at| self.config.time_display.format(&at))
+                        .unwrap_or_default(),
+                    if entry.stale { "stale" } else { "" }.to_owned(),
+                ]
+            })
+            .collect();
+        renderer.write_table(
+            &[
+                Column {
+                    title: "workspace",
+                    width: 12,
+                },
+                Column {
+                    title: "id",
+                    width: 11,
+                },
+                Column {
+                    title: "story",
+                    width: 25,
+                },
+                Column {
+                    title: "status",
+                    width: 12,
+                },
+                Column {
+                    title: "last activity",
+                    width: 20,
+                },
+                Column {
+                    title: "",
+                    width: 6,
+                },
+            ],
+            &rows,
+        );
+        if self.entries.is_empty() {
+            renderer.write_line("Nothing watched yet, press f on a story to watch it.");
+        }
+
+        renderer.write_line("");
+        renderer.write_line("");
+        renderer.write_line("[p] previous | [:id:] open a story of this workspace");
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            input => {
+                let Some(story_id) = self.config.id_format.parse(input) else {
+                    return Ok(None);
+                };
+                let on_screen = self.entries.iter().any(|entry| {
+                    entry.watched.story_id == story_id
+                        && entry.watched.workspace == self.workspace
+                        && entry.status.is_some()
+                });
+                Ok(on_screen.then_some(Action::JumpToItem { item_id: story_id }))
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::Status,
+        ui::pages::{page_test_utils::make_config, renderer::BufferRenderer},
+        watchlist::WatchedStory,
+    };
+
+    use super::*;
+
+    fn entry(workspace: Option<&str>, story_id: u32, stale: bool) -> WatchEntry {
+        WatchEntry {
+            watched: WatchedStory {
+                workspace: workspace.map(str::to_owned),
+                story_id,
+            },
+            name: format!("story {}", story_id),
+            status: Some(Status::Open),
+            last_activity: None,
+            stale,
+        }
+    }
+
+    #[test]
+    fn watchlist_page_should_open_only_stories_of_this_workspace() {
+        let mut sut = WatchlistPage {
+            entries: vec![entry(Some("acme"), 2, true), entry(None, 3, false)],
+            workspace: None,
+            config: make_config(),
+        };
+        let mut renderer = BufferRenderer::default();
+        sut.draw_page(&mut renderer).unwrap();
+
+        let output = renderer.lines.join("\n");
+        assert_eq!(output.contains("acme"), true);
+        assert_eq!(output.contains("stale"), true);
+        assert_eq!(sut.handle_input("2").unwrap(), None);
+        assert_eq!(
+            sut.handle_input("3").unwrap(),
+            Some(Action::JumpToItem { item_id: 3 })
+        );
+    }
+}
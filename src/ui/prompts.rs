@@ -1,14 +1,81 @@
 use crate::{
+    estimation::PointScale,
     models::{Epic, Status, Story},
-    ui::io_utils::get_user_input,
+    title_suggestion::suggest_title,
+    ui::io_utils::{edit_text, get_user_input, read_text},
 };
 
+/// Asks before a destructive action. Receives the entity type and what else
+/// will be lost with it.
+pub type ConfirmPrompt = dyn Fn(&str, &[String]) -> bool;
+
+/// Shows what is about to happen and asks whether to go ahead.
+pub type ApprovalPrompt = dyn Fn(&str) -> bool;
+
+/// Receives a suggested file name and returns the chosen path.
+pub type PathPrompt = dyn Fn(&str) -> Option<String>;
+
+/// Returns what the user typed, or `None` when the prompt was cancelled.
+pub type TextPrompt = dyn Fn() -> Option<String>;
+
+/// Receives what is about to happen and the text to type to go ahead, and
+/// returns whether it was typed.
+pub type TypedConfirmPrompt = dyn Fn(&str, &str) -> bool;
+
+/// Receives the statuses to offer and returns the one picked.
+pub type StatusPrompt = dyn Fn(&[Status]) -> Option<Status>;
+
+/// Receives the configured story kinds and returns the chosen one, or `-`
+/// to clear it.
+pub type KindPrompt = dyn Fn(&[String]) -> Option<String>;
+
+/// Receives the names to choose from, e.g. of plugins or workspaces, and
+/// returns the one picked.
+pub type ChoicePrompt = dyn Fn(&[String]) -> Option<String>;
+
+/// Receives what the number is for, e.g. `reply to`, and returns what the
+/// user typed.
+pub type NumberPrompt = dyn Fn(&str) -> Option<String>;
+
+/// Receives the current text and returns its replacement.
+pub type EditPrompt = dyn Fn(&str) -> Option<String>;
+
+/// Receives a field's name and current value, and returns the value as
+/// edited, or `None` to stop editing.
+pub type FieldPrompt = dyn Fn(&str, &str) -> Option<String>;
+
+/// Receives the scale to pick from and returns the estimate, or `-` to
+/// clear it.
+pub type EstimatePrompt = dyn Fn(&PointScale) -> Option<String>;
+
 pub struct Prompts {
-    pub create_epic: Box<dyn Fn() -> Epic>,
-    pub create_story: Box<dyn Fn() -> Story>,
-    pub delete_epic: Box<dyn Fn() -> bool>,
-    pub delete_story: Box<dyn Fn() -> bool>,
-    pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub create_epic: Box<dyn Fn() -> Option<Epic>>,
+    pub create_story: Box<dyn Fn() -> Option<Story>>,
+    pub confirm: Box<ConfirmPrompt>,
+    pub approve: Box<ApprovalPrompt>,
+    pub confirm_bulk: Box<TypedConfirmPrompt>,
+    pub update_status: Box<StatusPrompt>,
+    pub close_story: Box<dyn Fn() -> Option<(Status, String)>>,
+    pub export_path: Box<PathPrompt>,
+    pub blocker: Box<TextPrompt>,
+    /// Returns the ticket URL followed by an optional title.
+    pub external_blocker: Box<TextPrompt>,
+    pub acceptance_criterion: Box<TextPrompt>,
+    pub criterion_number: Box<TextPrompt>,
+    /// Returns the new epic's name and the ids of the stories to move into it.
+    pub split_epic: Box<dyn Fn() -> Option<(String, String)>>,
+    pub merge_target: Box<TextPrompt>,
+    pub mirror: Box<TextPrompt>,
+    pub story_kind: Box<KindPrompt>,
+    pub estimate: Box<EstimatePrompt>,
+    pub reminder: Box<TextPrompt>,
+    pub description: Box<TextPrompt>,
+    pub plugin: Box<ChoicePrompt>,
+    pub workspace: Box<ChoicePrompt>,
+    pub comment: Box<TextPrompt>,
+    pub comment_number: Box<NumberPrompt>,
+    pub edit_comment: Box<EditPrompt>,
+    pub edit_field: Box<FieldPrompt>,
 }
 
 impl Prompts {
@@ -16,55 +83,295 @@ impl Prompts {
         Self {
             create_epic: Box::new(create_epic_prompt),
             create_story: Box::new(create_story_prompt),
-            delete_epic: Box::new(delete_epic_prompt),
-            delete_story: Box::new(delete_story_prompt),
+            confirm: Box::new(confirm_prompt),
+            approve: Box::new(approval_prompt),
+            confirm_bulk: Box::new(typed_confirm_prompt),
             update_status: Box::new(update_status_prompt),
+            close_story: Box::new(close_story_prompt),
+            export_path: Box::new(export_path_prompt),
+            blocker: Box::new(blocker_prompt),
+            external_blocker: Box::new(external_blocker_prompt),
+            acceptance_criterion: Box::new(acceptance_criterion_prompt),
+            criterion_number: Box::new(criterion_number_prompt),
+            split_epic: Box::new(split_epic_prompt),
+            merge_target: Box::new(merge_target_prompt),
+            mirror: Box::new(mirror_prompt),
+            story_kind: Box::new(story_kind_prompt),
+            estimate: Box::new(estimate_prompt),
+            reminder: Box::new(reminder_prompt),
+            description: Box::new(description_prompt),
+            plugin: Box::new(plugin_prompt),
+            workspace: Box::new(workspace_prompt),
+            comment: Box::new(comment_prompt),
+            comment_number: Box::new(comment_number_prompt),
+            edit_comment: Box::new(edit_comment_prompt),
+            edit_field: Box::new(edit_field_prompt),
         }
     }
 }
 
-fn create_epic_prompt() -> Epic {
-    println!("Epic Name:");
-    let name = get_user_input();
-    println!("Epic Description:");
-    let description = get_user_input();
-    Epic::new(name, description)
+impl Default for Prompts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Typing this (or pressing Esc before Enter) at any prompt cancels it.
+pub const CANCEL_TOKEN: &str = "esc";
+
+fn create_epic_prompt() -> Option<Epic> {
+    draw_header("New epic (leave a field empty or type esc to cancel)");
+    let name = prompt_text("Epic Name: ")?;
+    let description = prompt_text("Epic Description: ")?;
+    Some(Epic::new(name, description))
 }
 
-fn create_story_prompt() -> Story {
-    println!("Story Name:");
-    let name = get_user_input();
-    println!("Story Description:");
-    let description = get_user_input();
-    Story::new(name, description)
+fn create_story_prompt() -> Option<Story> {
+    draw_header("New story (leave the name empty to take it from the description, esc to cancel)");
+    let name = read_text("Story Name: ")?;
+    if name.trim().eq_ignore_ascii_case(CANCEL_TOKEN) || name.trim() == "\u{1b}" {
+        return None;
+    }
+    let description = prompt_text("Story Description: ")?;
+    if !name.trim().is_empty() {
+        return Some(Story::new(name, description));
+    }
+    let suggestion = suggest_title(&description)?;
+    let name = if approval_prompt(&format!("Title the story \"{}\"?", suggestion)) {
+        suggestion
+    } else {
+        prompt_text("Story Name: ")?
+    };
+    Some(Story::new(name, description))
 }
 
-fn delete_epic_prompt() -> bool {
-    draw_header("Are you sure you want to delete this story? [Y/n]: ");
+fn confirm_prompt(entity: &str, consequences: &[String]) -> bool {
+    draw_header(&confirmation_message(entity, consequences));
     get_user_input().trim().eq("Y")
 }
 
-fn delete_story_prompt() -> bool {
-    draw_header("Are you sure you want to delete this story? [Y/n]: ");
+fn approval_prompt(message: &str) -> bool {
+    draw_header(&format!("{}\n[Y/n]: ", message));
     get_user_input().trim().eq("Y")
 }
 
-fn update_status_prompt() -> Option<Status> {
-    draw_header("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED): ");
-    let status = get_user_input().trim().parse::<u8>();
-    if let Ok(status) = status {
-        match status {
-            1 => Some(Status::Open),
-            2 => Some(Status::Closed),
-            3 => Some(Status::Resolved),
-            4 => Some(Status::InProgress),
-            _ => None,
-        };
+fn typed_confirm_prompt(summary: &str, expected: &str) -> bool {
+    draw_header(&format!("{}\nType {} to go ahead: ", summary, expected));
+    get_user_input().trim() == expected
+}
+
+fn confirmation_message(entity: &str, consequences: &[String]) -> String {
+    let mut message = format!("Are you sure you want to delete this {}?", entity);
+    for consequence in consequences {
+        message.push_str(&format!("\n - {}", consequence));
     }
-    None
+    message.push_str("\n[Y/n]: ");
+    message
+}
+
+fn update_status_prompt(options: &[Status]) -> Option<Status> {
+    let choices = options
+        .iter()
+        .enumerate()
+        .map(|(index, status)| format!("{} - {}", index + 1, status))
+        .collect::<Vec<_>>()
+        .join(", ");
+    draw_header(&format!("New Status ({}): ", choices));
+    parse_status_choice(&get_user_input(), options)
+}
+
+fn close_story_prompt() -> Option<(Status, String)> {
+    draw_header("Closing comment (leave empty or type esc to cancel):");
+    let comment = prompt_text("> ")?;
+    println!("Close as (1 - RESOLVED, 2 - CLOSED):");
+    match get_user_input().trim() {
+        "1" => Some((Status::Resolved, comment)),
+        "2" => Some((Status::Closed, comment)),
+        _ => None,
+    }
+}
+
+fn export_path_prompt(suggested: &str) -> Option<String> {
+    draw_header(&format!(
+        "Save report as (Enter for {}, esc to cancel):",
+        suggested
+    ));
+    let path = read_text("> ")?;
+    if path.eq_ignore_ascii_case(CANCEL_TOKEN) {
+        return None;
+    }
+    if path.is_empty() {
+        return Some(suggested.to_owned());
+    }
+    Some(path)
+}
+
+fn blocker_prompt() -> Option<String> {
+    draw_header("Blocked by story (an existing blocker is removed, esc to cancel):");
+    prompt_text("Story id: ")
+}
+
+fn external_blocker_prompt() -> Option<String> {
+    draw_header("Blocked by an outside ticket (an existing URL is removed, esc to cancel):");
+    prompt_text("URL and title: ")
+}
+
+fn split_epic_prompt() -> Option<(String, String)> {
+    draw_header("Split epic (leave a field empty or type esc to cancel)");
+    let story_ids = prompt_text("Stories to move (ids separated by commas): ")?;
+    let name = prompt_text("New Epic Name: ")?;
+    Some((name, story_ids))
+}
+
+fn merge_target_prompt() -> Option<String> {
+    draw_header("Merge this epic into another one (a preview is shown first, esc to cancel)");
+    prompt_text("Epic id: ")
+}
+
+fn mirror_prompt() -> Option<String> {
+    draw_header("Mirror a story of another epic here (a mirrored one is removed, esc to cancel):");
+    prompt_text("Story id: ")
+}
+
+fn story_kind_prompt(kinds: &[String]) -> Option<String> {
+    draw_header(&format!(
+        "Story type, one of: {} (- for none, esc to cancel)",
+        kinds.join(", ")
+    ));
+    prompt_text("Type: ")
+}
+
+fn estimate_prompt(scale: &PointScale) -> Option<String> {
+    draw_header(&format!(
+        "Story points, one of: {} (- for none, esc to cancel)",
+        scale
+    ));
+    prompt_text("Points: ")
+}
+
+fn plugin_prompt(names: &[String]) -> Option<String> {
+    draw_header(&format!(
+        "Run a plugin on this story, one of: {} (esc to cancel)",
+        names.join(", ")
+    ));
+    prompt_text("Plugin: ")
+}
+
+fn workspace_prompt(names: &[String]) -> Option<String> {
+    draw_header(&format!(
+        "Switch to workspace, one of: {} (esc to cancel)",
+        names.join(", ")
+    ));
+    prompt_text("Workspace: ")
+}
+
+fn description_prompt() -> Option<String> {
+    draw_header("New description (leave empty or type esc to cancel)");
+    prompt_text("Story Description: ")
+}
+
+fn reminder_prompt() -> Option<String> {
+    draw_header(
+        "Remind me in 30m, 2h, 3d, 3wd (working days), 1w, at YYYY-MM-DD HH:MM or e.g. tomorrow, next friday, eod (esc to cancel)",
+    );
+    prompt_text("When: ")
+}
+
+fn acceptance_criterion_prompt() -> Option<String> {
+    draw_header("New acceptance criterion (leave empty or type esc to cancel)");
+    prompt_text("> ")
+}
+
+fn criterion_number_prompt() -> Option<String> {
+    draw_header("Check or uncheck acceptance criterion (esc to cancel)");
+    prompt_text("Number: ")
+}
+
+fn comment_prompt() -> Option<String> {
+    draw_header("New comment (leave empty or type esc to cancel)");
+    prompt_text("> ")
+}
+
+fn comment_number_prompt(purpose: &str) -> Option<String> {
+    draw_header(&format!("Comment to {} (esc to cancel)", purpose));
+    prompt_text("Number: ")
+}
+
+fn edit_comment_prompt(current: &str) -> Option<String> {
+    draw_header(&format!(
+        "Edit comment (leave empty or type esc to cancel)\nWas: {}",
+        current
+    ));
+    prompt_text("> ")
+}
+
+fn edit_field_prompt(field: &str, current: &str) -> Option<String> {
+    draw_header("Edit (Enter saves and moves to the next field, clear it or type esc to stop)");
+    edit_text(&format!("{}: ", field), current)
+        .filter(|input| !input.is_empty() && !is_cancel(input))
+}
+
+fn prompt_text(prompt: &str) -> Option<String> {
+    read_text(prompt).filter(|input| !is_cancel(input))
+}
+
+fn is_cancel(input: &str) -> bool {
+    let input = input.trim();
+    input.is_empty() || input.eq_ignore_ascii_case(CANCEL_TOKEN) || input == "\u{1b}"
+}
+
+fn parse_status_choice(input: &str, options: &[Status]) -> Option<Status> {
+    let index = input.trim().parse::<usize>().ok()?.checked_sub(1)?;
+    options.get(index).cloned()
 }
 
 fn draw_header(text: &str) {
     println!("----------------------------");
     println!("{}", text);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_cancel_should_accept_empty_input_and_cancel_tokens() {
+        assert_eq!(is_cancel(""), true);
+        assert_eq!(is_cancel("  "), true);
+        assert_eq!(is_cancel("esc"), true);
+        assert_eq!(is_cancel("ESC"), true);
+        assert_eq!(is_cancel("\u{1b}"), true);
+        assert_eq!(is_cancel("escalation"), false);
+    }
+
+    #[test]
+    fn confirmation_message_should_name_the_entity_and_consequences() {
+        assert_eq!(
+            confirmation_message("epic", &["2 stories will also be deleted".to_owned()]),
+            "Are you sure you want to delete this epic?\n - 2 stories will also be deleted\n[Y/n]: "
+        );
+        assert_eq!(
+            confirmation_message("story", &[]),
+            "Are you sure you want to delete this story?\n[Y/n]: "
+        );
+    }
+
+    #[test]
+    fn parse_status_choice_should_map_menu_numbers() {
+        let all = &Status::ALL;
+        assert_eq!(parse_status_choice("1", all), Some(Status::Open));
+        assert_eq!(parse_status_choice("2", all), Some(Status::InProgress));
+        assert_eq!(parse_status_choice("3", all), Some(Status::Resolved));
+        assert_eq!(parse_status_choice("4", all), Some(Status::Closed));
+        assert_eq!(parse_status_choice("", all), None);
+        assert_eq!(parse_status_choice("5", all), None);
+        assert_eq!(parse_status_choice("0", all), None);
+    }
+
+    #[test]
+    fn parse_status_choice_should_number_the_offered_statuses() {
+        let options = [Status::InProgress, Status::Closed];
+        assert_eq!(parse_status_choice("2", &options), Some(Status::Closed));
+        assert_eq!(parse_status_choice("3", &options), None);
+    }
+}
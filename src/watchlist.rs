@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::grooming::STALE_AFTER_DAYS;
+use crate::models::{DBState, Status, Story};
+
+/// A story on the watchlist, with the workspace whose board it is on;
+/// `None` is the default board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchedStory {
+    pub workspace: Option<String>,
+    pub story_id: u32,
+}
+
+/// Stories to keep an eye on across every workspace, saved in a file of
+/// their own, e.g. `data/watchlist.json`.
+pub struct Watchlist {
+    path: PathBuf,
+    pub stories: Vec<WatchedStory>,
+}
+
+impl Watchlist {
+    /// Starts empty when the file doesn't exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let stories = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .with_context(|| anyhow!("invalid watchlist in {}", path.display()))?
+        } else {
+            vec![]
+        };
+        Ok(Self { path, stories })
+    }
+
+    /// Watches the story, or stops watching it if it already was, and saves
+    /// the list. Returns whether the story is watched now.
+    pub fn toggle(&mut self, workspace: Option<&str>, story_id: u32) -> Result<bool> {
+        let watched = WatchedStory {
+            workspace: workspace.map(str::to_owned),
+            story_id,
+        };
+        let was_watched = self.stories.contains(&watched);
+        if was_watched {
+            self.stories.retain(|story| *story != watched);
+        } else {
+            self.stories.push(watched);
+        }
+        self.save()?;
+        Ok(!was_watched)
+    }
+
+    /// Follows the stories of the workspace's board to their new ids after
+    /// a renumbering, saving the list if any of them moved.
+    pub fn renumber(&mut self, workspace: Option<&str>, new_ids: &HashMap<u32, u32>) -> Result<()> {
+        let mut moved = false;
+        for watched in &mut self.stories {
+            if watched.workspace.as_deref() != workspace {
+                continue;
+            }
+            if let Some(new_id) = new_ids.get(&watched.story_id) {
+                watched.story_id = *new_id;
+                moved = true;
+            }
+        }
+        if moved {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.stories)?)
+            .with_context(|| anyhow!("could not save {}", self.path.display()))
+    }
+}
+
+/// A watched story as its board has it now.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEntry {
+    pub watched: WatchedStory,
+    /// The story's name, or why it couldn't be looked up.
+    pub name: String,
+    pub status: Option<Status>,
+    pub last_activity: Option<DateTime<Utc>>,
+    /// Still open with nothing happening for `STALE_AFTER_DAYS`.
+    pub stale: bool,
+}
+
+/// Looks every watched story up on its workspace's board, reading each
+/// board once however many of its stories are watched. Ids that were
+/// renumbered or merged away are followed through the board's aliases.
+pub fn collect_watchlist(
+    watchlist: &Watchlist,
+    mut read_board: impl FnMut(Option<&str>) -> Result<DBState>,
+    now: DateTime<Utc>,
+) -> Vec<WatchEntry> {
+    let stale_before = now - Duration::days(STALE_AFTER_DAYS);
+    let mut boards: HashMap<Option<String>, Result<DBState, String>> = HashMap::new();
+    watchlist
+        .stories
+        .iter()
+        .map(|watched| {
+            let board = boards.entry(watched.workspace.clone()).or_insert_with(|| {
+                read_board(watched.workspace.as_deref()).map_err(|error| format!("{:#}", error))
+            });
+            let story = match board {
+                Ok(board) => board
+                    .resolve(watched.story_id)
+                    .and_then(|story_id| board.stories.get(&story_id))
+                    .ok_or_else(|| "(no longer on the board)".to_owned()),
+                Err(error) => Err(format!("(can't read the board: {})", error)),
+            };
+            match story {
+                Ok(story) => {
+                    let last_activity = last_activity(story);
+                    WatchEntry {
+                        watched: watched.clone(),
+                        name: story.name.clone(),
+                        status: Some(story.status.clone()),
+                        last_activity,
+                        stale: !story.status.is_done()
+                            && last_activity.is_none_or(|at| at < stale_before),
+                    }
+                }
+                Err(reason) => WatchEntry {
+                    watched: watched.clone(),
+                    name: reason,
+                    status: None,
+                    last_activity: None,
+                    stale: false,
+                },
+            }
+        })
+        .collect()
+}
+
+/// When the story was last created, moved or commented on, as far as the
+/// board recorded it.
+pub fn last_activity(story: &Story) -> Option<DateTime<Utc>> {
+    let changes = story.status_changes.iter().map(|change| change.at);
+    let comments = story
+        .comments
+        .iter()
+        .map(|comment| comment.edited_at.unwrap_or(comment.created_at));
+    story
+        .created_at
+        .into_iter()
+        .chain(changes)
+        .chain(comments)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    #[test]
+    fn toggle_should_save_the_watchlist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("watchlist.json");
+        let mut watchlist = Watchlist::load(&path).unwrap();
+
+        assert_eq!(watchlist.toggle(Some("acme"), 2).unwrap(), true);
+        assert_eq!(watchlist.toggle(None, 2).unwrap(), true);
+        assert_eq!(watchlist.toggle(Some("acme"), 2).unwrap(), false);
+
+        assert_eq!(
+            Watchlist::load(&path).unwrap().stories,
+            vec![WatchedStory {
+                workspace: None,
+                story_id: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn renumber_should_only_move_the_stories_of_the_board() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("watchlist.json");
+        let mut watchlist = Watchlist::load(&path).unwrap();
+        watchlist.toggle(Some("acme"), 5).unwrap();
+        watchlist.toggle(None, 5).unwrap();
+
+        watchlist
+            .renumber(Some("acme"), &HashMap::from([(5, 2)]))
+            .unwrap();
+
+        let ids: Vec<u32> = Watchlist::load(&path)
+            .unwrap()
+            .stories
+            .iter()
+            .map(|watched| watched.story_id)
+            .collect();
+        assert_eq!(ids, vec![2, 5]);
+    }
+
+    #[test]
+    fn collect_watchlist_should_read_each_board_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watchlist = Watchlist::load(dir.path().join("watchlist.json")).unwrap();
+        watchlist.toggle(Some("acme"), 2).unwrap();
+        watchlist.toggle(Some("acme"), 3).unwrap();
+        watchlist.toggle(Some("acme"), 7).unwrap();
+        watchlist.toggle(Some("acme"), 9).unwrap();
+        watchlist.toggle(Some("gone"), 2).unwrap();
+        let mut board = DbFixtureBuilder::new()
+            .with_epic("Payments")
+            .with_story("Refunds")
+            .with_story("Invoices")
+            .build();
+        board.stories.get_mut(&2).unwrap().created_at = None;
+        board.aliases.insert(7, 3);
+        let mut reads = vec![];
+
+        let entries = collect_watchlist(
+            &watchlist,
+            |workspace| {
+                reads.push(workspace.map(str::to_owned));
+                match workspace {
+                    Some("acme") => Ok(board.clone()),
+                    _ => Err(anyhow!("no such file")),
+                }
+            },
+            Utc::now(),
+        );
+
+        assert_eq!(
+            reads,
+            vec![Some("acme".to_owned()), Some("gone".to_owned())]
+        );
+        let summary: Vec<(&str, bool)> = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.stale))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Refunds", true),
+                ("Invoices", false),
+                ("Invoices", false),
+                ("(no longer on the board)", false),
+                ("(can't read the board: no such file)", false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::Status;
+
+/// The status changes allowed for one kind of story, configured as e.g.
+/// `[{ "from": "Open", "to": ["Closed"] }]`. A status without a step is
+/// final: nothing can follow it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workflow(pub Vec<WorkflowStep>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    pub from: Status,
+    pub to: Vec<Status>,
+}
+
+impl Workflow {
+    /// The statuses a story can move to from `from`, in status menu order.
+    pub fn next_statuses(&self, from: &Status) -> Vec<Status> {
+        Status::ALL
+            .into_iter()
+            .filter(|to| self.allows(from, to))
+            .collect()
+    }
+
+    pub fn allows(&self, from: &Status, to: &Status) -> bool {
+        self.0
+            .iter()
+            .any(|step| step.from == *from && step.to.contains(to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workflow_should_only_allow_configured_steps() {
+        let chore: Workflow =
+            serde_json::from_str(r#"[{ "from": "Open", "to": ["Closed", "InProgress"] }]"#)
+                .unwrap();
+
+        assert_eq!(chore.allows(&Status::Open, &Status::Closed), true);
+        assert_eq!(chore.allows(&Status::Open, &Status::Resolved), false);
+        assert_eq!(chore.allows(&Status::Closed, &Status::Open), false);
+        assert_eq!(
+            chore.next_statuses(&Status::Open),
+            vec![Status::InProgress, Status::Closed]
+        );
+        assert_eq!(chore.next_statuses(&Status::Closed), vec![]);
+    }
+}
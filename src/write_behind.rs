@@ -0,0 +1,252 @@
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use crate::dao::Database;
+use crate::models::DBState;
+
+enum Message {
+    Retrieve(Sender<Result<DBState>>),
+    Persist(DBState),
+    Flush(Sender<Result<()>>),
+}
+
+/// Wraps a database so writes return immediately and are persisted by a
+/// background thread once no other write arrived for `delay`. Reads are
+/// served from memory after the first one. Pending writes are persisted on
+/// [`Database::flush`] and when the wrapper is dropped. A failed write stays
+/// pending and is retried after the next `delay`.
+pub struct WriteBehindDatabase {
+    state: RefCell<Option<DBState>>,
+    sender: Option<Sender<Message>>,
+    worker: Option<JoinHandle<()>>,
+    /// Set by the worker when a background write fails, reported by the
+    /// next write or flush.
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl WriteBehindDatabase {
+    pub fn new(database: Box<dyn Database + Send>, delay: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let failure = Arc::new(Mutex::new(None));
+        let worker_failure = Arc::clone(&failure);
+        let worker = thread::spawn(move || run_worker(database, receiver, delay, worker_failure));
+        Self {
+            state: RefCell::new(None),
+            sender: Some(sender),
+            worker: Some(worker),
+            failure,
+        }
+    }
+
+    fn send(&self, message: Message) -> Result<()> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(message).ok())
+            .ok_or_else(|| anyhow!("the background writer stopped"))
+    }
+
+    fn take_failure(&self) -> Result<()> {
+        match self
+            .failure
+            .lock()
+            .ok()
+            .and_then(|mut failure| failure.take())
+        {
+            Some(error) => Err(anyhow!("a background write failed: {}", error)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Database for WriteBehindDatabase {
+    fn retrieve(&self) -> Result<DBState> {
+        if let Some(state) = self.state.borrow().as_ref() {
+            return Ok(state.clone());
+        }
+        let (reply, response) = mpsc::channel();
+        self.send(Message::Retrieve(reply))?;
+        let state = response.recv()??;
+        *self.state.borrow_mut() = Some(state.clone());
+        Ok(state)
+    }
+
+    fn persist(&self, state: &DBState) -> Result<()> {
+        self.take_failure()?;
+        *self.state.borrow_mut() = Some(state.clone());
+        self.send(Message::Persist(state.clone()))
+    }
+
+    fn flush(&self) -> Result<()> {
+        let (reply, response) = mpsc::channel();
+        self.send(Message::Flush(reply))?;
+        let flushed = response.recv()?;
+        let background = self.take_failure();
+        flushed.and(background)
+    }
+}
+
+impl Drop for WriteBehindDatabase {
+    fn drop(&mut self) {
+        // closing the channel makes the worker write what is pending and stop
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_worker(
+    database: Box<dyn Database + Send>,
+    receiver: Receiver<Message>,
+    delay: Duration,
+    failure: Arc<Mutex<Option<String>>>,
+) {
+    let mut pending: Option<DBState> = None;
+    let write_pending = |pending: &mut Option<DBState>| -> Result<()> {
+        if let Some(state) = pending.as_ref() {
+            database.persist(state)?;
+        }
+        *pending = None;
+        Ok(())
+    };
+    loop {
+        let message = if pending.is_some() {
+            match receiver.recv_timeout(delay) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(error) = write_pending(&mut pending) {
+                        if let Ok(mut failure) = failure.lock() {
+                            *failure = Some(format!("{:#}", error));
+                        }
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    let _ = write_pending(&mut pending);
+                    return;
+                }
+            }
+        } else {
+            match receiver.recv() {
+                Ok(message) => message,
+                Err(_) => return,
+            }
+        };
+        match message {
+            Message::Retrieve(reply) => {
+                let _ = reply.send(database.retrieve());
+            }
+            Message::Persist(state) => pending = Some(state),
+            Message::Flush(reply) => {
+                let result = write_pending(&mut pending).and_then(|()| database.flush());
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::DbFixtureBuilder;
+
+    /// Shares its state with the test, which can't reach into the worker.
+    #[derive(Clone, Default)]
+    struct SharedDB {
+        state: Arc<Mutex<Option<DBState>>>,
+        writes: Arc<Mutex<usize>>,
+    }
+
+    impl Database for SharedDB {
+        fn retrieve(&self) -> Result<DBState> {
+            Ok(self
+                .state
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_else(|| DbFixtureBuilder::new().build()))
+        }
+
+        fn persist(&self, state: &DBState) -> Result<()> {
+            *self.state.lock().unwrap() = Some(state.clone());
+            *self.writes.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    /// Tells the test about every write it refuses.
+    struct FailingDB {
+        attempts: Sender<()>,
+    }
+
+    impl Database for FailingDB {
+        fn retrieve(&self) -> Result<DBState> {
+            Ok(DbFixtureBuilder::new().build())
+        }
+
+        fn persist(&self, _state: &DBState) -> Result<()> {
+            let _ = self.attempts.send(());
+            Err(anyhow!("disk full"))
+        }
+    }
+
+    #[test]
+    fn persist_should_be_visible_before_it_is_written() {
+        let database = SharedDB::default();
+        let sut = WriteBehindDatabase::new(Box::new(database.clone()), Duration::from_secs(60));
+
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        sut.persist(&state).unwrap();
+
+        assert_eq!(sut.retrieve().unwrap(), state);
+        assert_eq!(*database.writes.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn flush_should_write_only_the_latest_state() {
+        let database = SharedDB::default();
+        let sut = WriteBehindDatabase::new(Box::new(database.clone()), Duration::from_secs(60));
+
+        sut.persist(&DbFixtureBuilder::new().with_epic("first").build())
+            .unwrap();
+        let latest = DbFixtureBuilder::new().with_epic("second").build();
+        sut.persist(&latest).unwrap();
+        sut.flush().unwrap();
+
+        assert_eq!(*database.state.lock().unwrap(), Some(latest));
+        assert_eq!(*database.writes.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn drop_should_write_pending_state() {
+        let database = SharedDB::default();
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        {
+            let sut = WriteBehindDatabase::new(Box::new(database.clone()), Duration::from_secs(60));
+            sut.persist(&state).unwrap();
+        }
+
+        assert_eq!(*database.state.lock().unwrap(), Some(state));
+    }
+
+    #[test]
+    fn background_failures_should_be_reported() {
+        let (attempts, attempted) = mpsc::channel();
+        let sut =
+            WriteBehindDatabase::new(Box::new(FailingDB { attempts }), Duration::from_millis(1));
+        let state = DbFixtureBuilder::new().with_epic("epic").build();
+        sut.persist(&state).unwrap();
+        // the failed write is retried, so once it is tried again the first
+        // failure has been recorded
+        attempted.recv().unwrap();
+        attempted.recv().unwrap();
+
+        assert_eq!(sut.persist(&state).is_err(), true);
+        assert_eq!(sut.flush().is_err(), true);
+    }
+}